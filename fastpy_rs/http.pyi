@@ -1,18 +1,26 @@
-def get(url: str) -> str:
+from typing import Any, Awaitable, Dict, Iterable, List, Optional, Sequence, Tuple, Union
+
+
+def get(url: str, *, retry: Optional[Retry] = None) -> str:
     r"""
     Makes an HTTP GET request to the specified URL and returns the response body as a string.
     
+    Unlike `aget`, which returns a `Response`, this returns only the body; use
+    `request("GET", url, ...)` to get the status, headers and `attempts` as well.
+    
     # Arguments
     
     * `url` - The URL to make the GET request to
+    * `retry` - A `Retry` policy for sending the request again when it fails; by default it is sent once
     
     # Returns
     
-    str - A string containing the response body
+    str - A string containing the response body, decoded with the charset from `Content-Type` or UTF-8
     
     # Raises
     
-    * `ValueError` - If the request fails or the response status is not successful
+    * `ValueError` - If the response status is not successful (`"Status code: ..."`), after any retries
+    * `RequestError` - A `ValueError` subclass raised if the request fails without a response; its `attempts` lists every try
     
     # Examples
     
@@ -23,10 +31,817 @@ def get(url: str) -> str:
     response = fastpy_rs.http.get("https://httpbin.org/get")
     print(response)  # Output: JSON response from the server
     
+    # Retry when the server is briefly unavailable
+    body = fastpy_rs.http.get("https://httpbin.org/get", retry=fastpy_rs.http.Retry(max_attempts=3))
+    
     # Handle errors
     try:
         fastpy_rs.http.get("https://nonexistent.url")
     except ValueError as e:
         print(f"Request failed: {e}")
     ```
-    """
\ No newline at end of file
+    """
+
+class RequestError(ValueError):
+    r"""
+    Subclass of ValueError raised when a request fails without a response, such as
+    when it cannot connect or times out.
+    
+    `attempts` lists an `Attempt` for every time the request was sent; there is more
+    than one when a `Retry` policy retried it.
+    """
+
+    attempts: List["Attempt"]
+
+
+class Attempt:
+    r"""
+    One attempt at sending a request, as listed in `Response.attempts` and `RequestError.attempts`.
+    """
+
+    status: Optional[int]
+    """The response status, or `None` if the attempt failed without a response."""
+    error: Optional[str]
+    """Why the attempt failed without a response, or `None` if there was one."""
+    elapsed: float
+    """How long the attempt took, in seconds."""
+    delay: float
+    """How long the request waited after this attempt before the next one, in seconds; 0 for the last attempt."""
+
+
+class Retry:
+    r"""
+    When and how often a failed request is sent again.
+    
+    A request is retried when it fails to connect, times out or is cut off, or when the
+    response status is one of `statuses`, as long as its method is one of `methods` and fewer
+    than `max_attempts` attempts have been made. Errors that would happen again, such as an
+    invalid URL or too many redirects, are raised at once. Before attempt `n + 1` the request waits
+    `backoff * 2 ** (n - 1)` seconds, at most `max_backoff`; with `jitter` the wait is
+    drawn at random from the upper half of that, so that clients failing together do not
+    retry together.
+    
+    A `Retry-After` header on a retried response, in seconds or as an HTTP date, replaces
+    the computed wait. If it asks for longer than `max_backoff`, the response is returned
+    without retrying.
+    
+    When the attempts run out, the last response is returned, or the last error raised.
+    Either way `attempts` on it lists what happened.
+    
+    # Arguments
+    
+    * `max_attempts` - The total number of attempts, including the first one
+    * `backoff` - The wait before the first retry, in seconds; it doubles for every retry after that
+    * `max_backoff` - The longest wait between attempts, in seconds
+    * `jitter` - Randomize each wait between half and all of its computed length
+    * `statuses` - Response status codes that are retried
+    * `methods` - HTTP methods that are retried; others are sent once
+    * `respect_retry_after` - Wait as long as the `Retry-After` header asks
+    
+    # Raises
+    
+    * `ValueError` - If `max_attempts` is 0, a wait is negative or a method is invalid
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    retry = fastpy_rs.http.Retry(max_attempts=5, backoff=0.2, statuses=[429, 503])
+    response = fastpy_rs.http.request("GET", "https://httpbin.org/status/503", retry=retry)
+    print(response.status)         # Output: 503
+    print(len(response.attempts))  # Output: 5
+    
+    with fastpy_rs.http.Client(base_url="https://api.example.com", retry=fastpy_rs.http.Retry()) as client:
+        client.get("/flaky")
+    ```
+    """
+
+    max_attempts: int
+    """The total number of attempts, including the first one."""
+    backoff: float
+    """The wait before the first retry, in seconds."""
+    max_backoff: float
+    """The longest wait between attempts, in seconds."""
+    jitter: bool
+    """Whether waits are randomized."""
+    statuses: List[int]
+    """Response status codes that are retried."""
+    methods: List[str]
+    """HTTP methods that are retried."""
+    respect_retry_after: bool
+    """Whether `Retry-After` headers are honored."""
+
+    def __init__(
+        self,
+        max_attempts: int = 3,
+        *,
+        backoff: float = 0.5,
+        max_backoff: float = 30.0,
+        jitter: bool = True,
+        statuses: Optional[Sequence[int]] = None,
+        methods: Optional[Sequence[str]] = None,
+        respect_retry_after: bool = True,
+    ) -> None: ...
+
+
+class Response:
+    r"""
+    An HTTP response.
+    
+    `headers` is a dict with lower-case names; a header sent several times has its values
+    joined with `", "`. The body is read completely before the response is returned.
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.post("https://httpbin.org/post", json={"id": 7})
+    print(response.status)                   # Output: 200
+    print(response.headers["content-type"])  # Output: application/json
+    print(response.json()["json"])           # Output: {'id': 7}
+    ```
+    """
+
+    status: int
+    """The status code, such as `200`."""
+    url: str
+    """The final URL, after any redirects."""
+    headers: Dict[str, str]
+    """The response headers, with lower-case names."""
+    content: bytes
+    """The body as `bytes`."""
+    attempts: List[Attempt]
+    """An `Attempt` for every time the request was sent; more than one if it was retried."""
+
+    @property
+    def text(self) -> str:
+        r"""
+        The body decoded as text, using the charset from `Content-Type` or UTF-8.
+        
+        Bytes that are invalid in that encoding are replaced with U+FFFD.
+        """
+        ...
+
+    def json(self) -> Any:
+        r"""
+        Parses the body as JSON, with the same parser as `fastpy_rs.json.parse_json`.
+        
+        # Raises
+        
+        * `JSONDecodeError` - If the body is not valid JSON
+        """
+        ...
+
+    def raise_for_status(self) -> None:
+        r"""
+        Raises `ValueError` if the status is a client or server error (400 and above).
+        """
+        ...
+
+
+def request(
+    method: str,
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Response:
+    r"""
+    Sends an HTTP request and returns the `Response`.
+    
+    Redirects are followed. A response with an error status is returned like any other;
+    call `raise_for_status()` to turn it into an exception.
+    
+    # Arguments
+    
+    * `method` - The HTTP method, such as `"GET"` or `"POST"`; case-insensitive
+    * `url` - The URL to send the request to
+    * `headers` - Extra request headers, as a dict of names to values
+    * `params` - Query parameters appended to the URL, as a dict or a list of `(name, value)` pairs; a list value repeats the parameter and `None` values are left out
+    * `json` - An object to send as a JSON body, serialized like `fastpy_rs.json.serialize_json` does
+    * `data` - A raw body as `bytes` or `str`, or a dict or list of pairs sent as a form
+    * `timeout` - The timeout for each attempt at the request, in seconds, or `None` to wait forever
+    * `retry` - A `Retry` policy for sending the request again when it fails; by default it is sent once
+    
+    # Returns
+    
+    `Response` - The status, headers, final URL and body
+    
+    # Raises
+    
+    * `ValueError` - If the method is invalid or both `json` and `data` are given
+    * `RequestError` - A `ValueError` subclass raised if the request fails without a response (`"Request failed: ..."`)
+    * `TypeError` - If `data` or `params` has an unsupported type
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.request("GET", "https://httpbin.org/get", params={"q": "rust"})
+    print(response.status)  # Output: 200
+    print(response.json()["args"])  # Output: {'q': 'rust'}
+    
+    response = fastpy_rs.http.request("PUT", "https://httpbin.org/put", data=b"raw", timeout=5)
+    response.raise_for_status()
+    
+    response = fastpy_rs.http.request("GET", "https://httpbin.org/get", retry=fastpy_rs.http.Retry(max_attempts=5))
+    print(len(response.attempts))  # Output: 1
+    ```
+    """
+    ...
+
+
+def post(
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Response:
+    r"""
+    Sends a POST request; the same as `request("POST", url, ...)`.
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.post("https://httpbin.org/post", json={"id": 7})
+    print(response.json()["json"])  # Output: {'id': 7}
+    ```
+    """
+    ...
+
+
+def put(
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Response:
+    r"""
+    Sends a PUT request; the same as `request("PUT", url, ...)`.
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.put("https://httpbin.org/put", data=b"\x00\x01")
+    ```
+    """
+    ...
+
+
+def patch(
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Response:
+    r"""
+    Sends a PATCH request; the same as `request("PATCH", url, ...)`.
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.patch("https://httpbin.org/patch", json={"name": "new"})
+    ```
+    """
+    ...
+
+
+def delete(
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Response:
+    r"""
+    Sends a DELETE request; the same as `request("DELETE", url, ...)`.
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.delete("https://httpbin.org/delete")
+    print(response.status)  # Output: 200
+    ```
+    """
+    ...
+
+
+def head(
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Response:
+    r"""
+    Sends a HEAD request; the same as `request("HEAD", url, ...)`.
+    
+    The response has the headers only, and `content` is always empty.
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    response = fastpy_rs.http.head("https://httpbin.org/get")
+    print(response.headers["content-type"])  # Output: application/json
+    ```
+    """
+    ...
+
+
+class Client:
+    r"""
+    A reusable HTTP session.
+    
+    The client keeps a pool of open connections, so consecutive requests to the same host
+    skip the TCP and TLS handshakes. Cookies set by responses are stored and sent back on
+    later requests. A client can be shared between threads; requests run with the GIL
+    released and do not block each other.
+    
+    Use it as a context manager, or call `close()`, to release the connections when done.
+    
+    # Arguments
+    
+    * `base_url` - A URL that relative request URLs are appended to
+    * `headers` - Headers sent with every request; headers given to a request take precedence
+    * `timeout` - The default timeout for each request, in seconds, or `None` to wait forever
+    * `cookies` - Cookies sent with every request to the host of `base_url`, or to every host
+      when there is no `base_url`, as a dict of names to values
+    * `retry` - A `Retry` policy applied to every request; a request's own `retry` takes precedence
+    
+    # Raises
+    
+    * `ValueError` - If `base_url` is not a valid URL or `timeout` is not positive
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    with fastpy_rs.http.Client(base_url="https://api.example.com/v1", headers={"Authorization": "Bearer token"}) as client:
+        users = client.get("users", params={"page": 1}).json()
+        client.post("users", json={"name": "Alice"}).raise_for_status()
+    ```
+    """
+
+    def __init__(
+        self,
+        *,
+        base_url: Optional[str] = None,
+        headers: Optional[Dict[str, Any]] = None,
+        timeout: Optional[float] = 30.0,
+        cookies: Optional[Dict[str, str]] = None,
+        retry: Optional[Retry] = None,
+    ) -> None: ...
+
+    def request(
+        self,
+        method: str,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a request through the client, like `fastpy_rs.http.request` does.
+        
+        `url` may be relative to `base_url`. `timeout` and `retry` override the client's
+        settings when given, so `timeout=None` waits forever even if the client has a timeout;
+        the other arguments are the same as for `fastpy_rs.http.request`.
+        
+        # Raises
+        
+        * `ValueError` - If the client is closed, or for the same reasons as `fastpy_rs.http.request`
+        * `RequestError` - If the request fails without a response
+        """
+        ...
+
+    def get(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a GET request; the same as `request("GET", url, ...)`.
+        """
+        ...
+
+    def post(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a POST request; the same as `request("POST", url, ...)`.
+        """
+        ...
+
+    def put(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a PUT request; the same as `request("PUT", url, ...)`.
+        """
+        ...
+
+    def patch(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a PATCH request; the same as `request("PATCH", url, ...)`.
+        """
+        ...
+
+    def delete(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a DELETE request; the same as `request("DELETE", url, ...)`.
+        """
+        ...
+
+    def head(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Response:
+        r"""
+        Sends a HEAD request; the same as `request("HEAD", url, ...)`.
+        """
+        ...
+
+    def close(self) -> None:
+        r"""
+        Closes the client's connections. Requests made afterwards raise `ValueError`.
+        
+        Closing a client that is already closed does nothing.
+        """
+        ...
+
+    @property
+    def closed(self) -> bool:
+        r"""
+        Whether `close()` has been called.
+        """
+        ...
+
+    def __enter__(self) -> "Client": ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+
+
+def arequest(
+    method: str,
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    json: Any = None,
+    data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Awaitable[Response]:
+    r"""
+    Sends an HTTP request without blocking the event loop; await the result to get the
+    `Response`.
+    
+    Takes the same arguments as `request`. Requests made this way share one pool of
+    connections. Cancelling the awaiting task aborts the request, including any retries
+    it is waiting to make.
+    
+    # Returns
+    
+    `Awaitable[Response]` - An awaitable that resolves to a `Response`
+    
+    # Raises
+    
+    * `ValueError` - For the same reasons as `request`
+    * `RequestError` - When awaited, if the request fails without a response
+    * `RuntimeError` - If called outside a running event loop
+    
+    # Examples
+    ```python
+    import asyncio
+    import fastpy_rs
+    
+    async def main():
+        response = await fastpy_rs.http.arequest("POST", "https://httpbin.org/post", json={"id": 7})
+        print(response.json()["json"])  # Output: {'id': 7}
+    
+    asyncio.run(main())
+    ```
+    """
+    ...
+
+
+def aget(
+    url: str,
+    *,
+    headers: Optional[Dict[str, Any]] = None,
+    params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+    timeout: Optional[float] = 30.0,
+    retry: Optional[Retry] = None,
+) -> Awaitable[Response]:
+    r"""
+    Sends a GET request without blocking the event loop; the same as `arequest("GET", url, ...)`.
+    
+    # Examples
+    ```python
+    import asyncio
+    import fastpy_rs
+    
+    async def main():
+        urls = ["https://httpbin.org/get", "https://httpbin.org/ip"]
+        responses = await asyncio.gather(*(fastpy_rs.http.aget(url) for url in urls))
+        print([r.status for r in responses])  # Output: [200, 200]
+    
+    asyncio.run(main())
+    ```
+    """
+    ...
+
+
+class AsyncClient:
+    r"""
+    The asyncio counterpart of `Client`.
+    
+    Takes the same arguments as `Client`, and has the same request methods, except that
+    each returns an awaitable. Use it as an async context manager, or await `aclose()`,
+    to release the connections when done.
+    
+    # Examples
+    ```python
+    import asyncio
+    import fastpy_rs
+    
+    async def main():
+        async with fastpy_rs.http.AsyncClient(base_url="https://api.example.com/v1") as client:
+            pages = await asyncio.gather(*(client.get("users", params={"page": n}) for n in range(1, 4)))
+            users = [user for page in pages for user in page.json()]
+    
+    asyncio.run(main())
+    ```
+    """
+
+    def __init__(
+        self,
+        *,
+        base_url: Optional[str] = None,
+        headers: Optional[Dict[str, Any]] = None,
+        timeout: Optional[float] = 30.0,
+        cookies: Optional[Dict[str, str]] = None,
+        retry: Optional[Retry] = None,
+    ) -> None: ...
+
+    def request(
+        self,
+        method: str,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a request through the client, like `Client.request` does, and returns an
+        awaitable that resolves to the `Response`. As there, `timeout=None` waits forever even
+        if the client has a timeout.
+        
+        # Raises
+        
+        * `ValueError` - If the client is closed, or for the same reasons as `Client.request`
+        """
+        ...
+
+    def get(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a GET request; the same as `request("GET", url, ...)`.
+        """
+        ...
+
+    def post(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a POST request; the same as `request("POST", url, ...)`.
+        """
+        ...
+
+    def put(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a PUT request; the same as `request("PUT", url, ...)`.
+        """
+        ...
+
+    def patch(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a PATCH request; the same as `request("PATCH", url, ...)`.
+        """
+        ...
+
+    def delete(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        json: Any = None,
+        data: Optional[Union[bytes, str, Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a DELETE request; the same as `request("DELETE", url, ...)`.
+        """
+        ...
+
+    def head(
+        self,
+        url: str,
+        *,
+        headers: Optional[Dict[str, Any]] = None,
+        params: Optional[Union[Dict[str, Any], Sequence[Tuple[str, Any]]]] = None,
+        timeout: Optional[float] = ...,
+        retry: Optional[Retry] = None,
+    ) -> Awaitable[Response]:
+        r"""
+        Sends a HEAD request; the same as `request("HEAD", url, ...)`.
+        """
+        ...
+
+    def aclose(self) -> Awaitable[None]:
+        r"""
+        Closes the client's connections. Requests made afterwards raise `ValueError`;
+        requests still in flight complete normally.
+        
+        Returns an awaitable, for symmetry with the other methods; the client is closed
+        as soon as this is called.
+        """
+        ...
+
+    @property
+    def closed(self) -> bool:
+        r"""
+        Whether `aclose()` has been called.
+        """
+        ...
+
+    def __aenter__(self) -> Awaitable["AsyncClient"]: ...
+    def __aexit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> Awaitable[bool]: ...
+
+
+def get_many(
+    urls: Iterable[str],
+    *,
+    concurrency: int = 32,
+    timeout: Optional[float] = 30.0,
+    headers: Optional[Dict[str, Any]] = None,
+    retry: Optional[Retry] = None,
+) -> List[Union[Response, RequestError]]:
+    r"""
+    Fetches many URLs concurrently with GET requests and returns the results in input order.
+    
+    The requests run on the extension's tokio runtime with the GIL released, at most
+    `concurrency` at a time. A request that fails does not stop the others: its place in the
+    result holds the `RequestError` it would have raised instead of a `Response`. As with
+    `request`, a response with an error status is still a `Response`.
+    
+    # Arguments
+    
+    * `urls` - An iterable of URLs
+    * `concurrency` - The maximum number of requests in flight at once
+    * `timeout` - The timeout for each request, in seconds, or `None` to wait forever
+    * `headers` - Headers sent with every request
+    * `retry` - A `Retry` policy applied to each request
+    
+    # Returns
+    
+    `List[Union[Response, RequestError]]` - A `Response` or a `RequestError` for each URL, in the order of `urls`
+    
+    # Raises
+    
+    * `ValueError` - If `concurrency` is 0 or `timeout` is not positive
+    * `KeyboardInterrupt` - If interrupted; requests still running are cancelled
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    urls = [f"https://httpbin.org/status/{code}" for code in (200, 404)] + ["http://nonexistent.invalid/"]
+    for url, result in zip(urls, fastpy_rs.http.get_many(urls, concurrency=8, timeout=5)):
+        if isinstance(result, Exception):
+            print(url, "failed:", result)
+        else:
+            print(url, result.status)
+    # Output:
+    # https://httpbin.org/status/200 200
+    # https://httpbin.org/status/404 404
+    # http://nonexistent.invalid/ failed: Request failed: ...
+    ```
+    """
+    ...
@@ -1,20 +1,52 @@
-from typing import Dict, Any
+import json as _json
+import os
+from typing import IO, Any, Callable, Iterable, Iterator, List, Optional, Tuple, Union
 
-def parse_json(json_str: str) -> Dict:
+class JSONDecodeError(_json.JSONDecodeError):
     r"""
-    Parses a JSON string into a Python dictionary .
+    Raised when a document is not valid JSON.
+    
+    Subclass of both `ValueError` and the stdlib `json.JSONDecodeError`, so existing
+    `except json.JSONDecodeError` handlers catch it unchanged.
+    
+    # Attributes
+    
+    * `msg` - The unformatted error message
+    * `doc` - The JSON document being parsed (bytes input is decoded as UTF-8)
+    * `pos` - The character index in `doc` where parsing failed
+    * `lineno` - The line corresponding to `pos`
+    * `colno` - The column corresponding to `pos`
+    """
+    msg: str
+    doc: str
+    pos: int
+    lineno: int
+    colno: int
+
+def parse_json(json_str: Union[str, bytes, bytearray, memoryview]) -> Any:
+    r"""
+    Parses a JSON document into the corresponding Python object.
+    
+    Any JSON value is accepted at the top level, so the result can be a `dict`, `list`,
+    `str`, `int`, `float`, `bool` or `None`.
+
+    Ints of any size stay exact, and strings may hold lone surrogates just like with
+    the stdlib. `NaN` and `Infinity` are not JSON and are rejected; use `loads` for those.
     
     # Arguments
     
-    * `json_str` - A string containing valid JSON data
+    * `json_str` - The JSON document as `str`, `bytes`, `bytearray` or `memoryview`
     
     # Returns
     
-    `Dict` - A Python dictionary representing the parsed JSON data
+    `Any` - A Python object representing the parsed JSON data
     
     # Raises
     
-    * `ValueError` - If the input string is not valid JSON or if the JSON is not an object at the top level
+    * `JSONDecodeError` - If the input is not valid JSON; a subclass of both `ValueError` and
+      the stdlib `json.JSONDecodeError`, carrying `msg`, `doc`, `pos`, `lineno` and `colno`
+    * `RecursionError` - If the document is nested more than 1000 levels deep
+    * `TypeError` - If the input is not a `str` or a bytes-like object
     
     # Examples
     ```python
@@ -28,16 +60,157 @@ def parse_json(json_str: str) -> Dict:
     # Parse JSON with nested structures
     nested = fastpy_rs.json.parse_json('{"users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}]}')
     print(nested['users'][0]['name'])  # Output: Alice
+    
+    # Top-level arrays and scalars, straight from bytes
+    print(fastpy_rs.json.parse_json(b'[1, 2, 3]'))  # Output: [1, 2, 3]
+    print(fastpy_rs.json.parse_json(b'"hello"'))    # Output: hello
+    
+    # Find out where a document is broken
+    try:
+        fastpy_rs.json.parse_json('{"a": 1,\n "b": }')
+    except fastpy_rs.json.JSONDecodeError as e:
+        print(e.lineno, e.colno, e.pos)  # Output: 2 7 15
     ```
     """
+
+def parse_json5(json_str: Union[str, bytes, bytearray, memoryview]) -> Any:
+    r"""
+    Parses a JSON5 document into the corresponding Python object.
+    
+    Every JSON document is valid JSON5, so this also reads plain JSON, just more slowly than
+    `parse_json`. On top of JSON it accepts `//` and `/* */` comments, a trailing comma after
+    the last array item or object member, single-quoted strings, unquoted (identifier) keys,
+    hexadecimal ints such as `0xFF`, numbers with a leading `+` or a leading or trailing
+    decimal point, `Infinity` and `NaN`, and strings continued over several lines with a
+    trailing backslash.
+    
+    # Arguments
+    
+    * `json_str` - The JSON5 document as `str`, `bytes`, `bytearray` or `memoryview`
     
-def serialize_json(obj: Any) -> str:
+    # Returns
+    
+    `Any` - A Python object representing the parsed data; ints of any size stay exact
+    
+    # Raises
+    
+    * `JSONDecodeError` - If the input is not valid JSON5, carrying `msg`, `doc`, `pos`, `lineno` and `colno`
+    * `TypeError` - If the input is not a `str` or a bytes-like object
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    config = fastpy_rs.json.parse_json5('''
+    {
+        // Served from the edge cache
+        host: 'example.com',
+        port: 0x1F90,
+        retries: [1, 2, 4,],
+    }
+    ''')
+    print(config)  # Output: {'host': 'example.com', 'port': 8080, 'retries': [1, 2, 4]}
+    ```
+    """
+
+def repair(text: str) -> str:
+    r"""
+    Extracts the first JSON value from `text` and repairs it into valid JSON.
+    
+    Meant for JSON written by language models: it may be wrapped in prose or a Markdown code
+    fence, cut off mid-string, or missing closing brackets. Valid JSON found in the text is
+    returned unchanged. Anything else is rewritten as compact JSON, fixing on the way:
+    
+    * open strings, arrays and objects at the end of the text, which are closed
+    * single, curly and backtick quotes, unquoted keys and bare-word values, which become strings
+    * unescaped quotes inside strings, raw control characters and invalid escapes
+    * missing and trailing commas, missing values (written as `null`) and mismatched brackets
+    * `True`/`False`/`None`, `NaN`/`Infinity` (written as `null`), comments and `...` placeholders
+    * numbers such as `+1`, `.5`, `5.`, `007` or `0x1F`
+    
+    # Arguments
+    
+    * `text` - Text containing a JSON value, possibly malformed or truncated
+    
+    # Returns
+    
+    `str` - The repaired JSON document
+    
+    # Raises
+    
+    * `ValueError` - If the text contains no JSON value at all, or it is nested too deeply
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    reply = 'Sure! Here is the data:\n```json\n{"name": "Ann", tags: [\'a\', "b",], "bio": "Likes'
+    print(fastpy_rs.json.repair(reply))
+    # Output: {"name":"Ann","tags":["a","b"],"bio":"Likes"}
+    ```
+    """
+
+def parse_lenient_llm(text: str) -> Any:
+    r"""
+    Extracts the first JSON value from `text`, repairs it like `repair` does and parses it.
+    
+    # Arguments
+    
+    * `text` - Text containing a JSON value, possibly malformed or truncated
+    
+    # Returns
+    
+    `Any` - The parsed value, with the same types `parse_json` returns
+    
+    # Raises
+    
+    * `ValueError` - If the text contains no JSON value at all, or it is nested too deeply
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    reply = "The answer is {'city': 'Paris', 'population': 2102650, 'capital': True"
+    print(fastpy_rs.json.parse_lenient_llm(reply))
+    # Output: {'city': 'Paris', 'population': 2102650, 'capital': True}
+    ```
+    """
+    
+def serialize_json(
+    obj: Any,
+    *,
+    indent: Optional[Union[int, str]] = None,
+    sort_keys: bool = False,
+    ensure_ascii: bool = False,
+    separators: Optional[Tuple[str, str]] = None,
+    default: Optional[Callable[[Any], Any]] = None,
+    skipkeys: bool = False,
+    allow_nan: bool = False,
+    check_circular: bool = True,
+) -> str:
     r"""
     Serializes a Python object to a JSON string.
     
+    The keyword arguments mirror `json.dumps`, so `indent=2, sort_keys=True, ensure_ascii=True`
+    gives byte-for-byte the same text as the stdlib. Without any options the output stays compact.
+    
+    Besides the JSON primitives, tuples and sets become arrays, `datetime`/`date`/`time` become
+    ISO 8601 strings, `uuid.UUID` its canonical string, `Decimal` a number with all of its digits,
+    `Enum` members their value, dataclasses objects of their fields and `bytes` base64 strings.
+    Ints of any size are written exactly, and dict keys that are ints, floats, bools or `None`
+    are converted to strings the same way `json.dumps` does.
+    
     # Arguments
     
-    * `obj` - A Python object to serialize (dict, list, str, int, float, bool, None)
+    * `obj` - A Python object to serialize (dict, list, str, int, float, bool, None and the types above)
+    * `indent` - Pretty-print with this many spaces (or this string) per level; `None` keeps everything on one line
+    * `sort_keys` - Write dictionary keys in sorted order
+    * `ensure_ascii` - Escape every non-ASCII character as `\uXXXX`
+    * `separators` - `(item_separator, key_separator)` tuple; defaults to `(",", ":")`, or `(",", ": ")` when `indent` is set
+    * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+    * `skipkeys` - Silently drop dict keys that are not str, int, float, bool or `None` instead of raising
+    * `allow_nan` - Write `NaN`, `Infinity` and `-Infinity` for non-finite floats instead of raising
+    * `check_circular` - Detect reference cycles and raise `ValueError` instead of `RecursionError`
     
     # Returns
     
@@ -45,7 +218,10 @@ def serialize_json(obj: Any) -> str:
     
     # Raises
     
-    * `ValueError` - If the object contains types that cannot be serialized to JSON
+    * `ValueError` - If the object contains types that cannot be serialized to JSON, or a reference cycle
+    * `RecursionError` - If the object is nested too deeply
+    * `TypeError` - If `indent` is neither an int, a str nor `None`
+    * Any exception raised by `default`, unchanged
     
     # Examples
     ```python
@@ -55,5 +231,974 @@ def serialize_json(obj: Any) -> str:
     data = {"name": "John", "age": 30, "active": True}
     json_str = fastpy_rs.json.serialize_json(data)
     print(json_str)  # Output: {"name":"John","age":30,"active":true}
+    
+    # Pretty-print the JSON
+    pretty_json = fastpy_rs.json.serialize_json(data, indent=2, sort_keys=True)
+    print(pretty_json)
+    # Output:
+    # {
+    #   "active": true,
+    #   "age": 30,
+    #   "name": "John"
+    # }
+    
+    # Fall back to a custom conversion
+    json_str = fastpy_rs.json.serialize_json({"tags": {"a"}, "obj": object()}, default=repr)
+    ```
+    """
+
+def loads(
+    s: Union[str, bytes, bytearray, memoryview],
+    *,
+    cls: None = None,
+    object_hook: Optional[Callable[[dict], Any]] = None,
+    parse_float: Optional[Callable[[str], Any]] = None,
+    parse_int: Optional[Callable[[str], Any]] = None,
+    parse_constant: Optional[Callable[[str], Any]] = None,
+    object_pairs_hook: Optional[Callable[[List[Tuple[str, Any]]], Any]] = None,
+) -> Any:
+    r"""
+    Deserializes a JSON document to a Python object, as a drop-in replacement for `json.loads`.
+    
+    Both run the same decoder, but this one follows the stdlib to the letter: `NaN`,
+    `Infinity` and `-Infinity` are accepted, errors carry the stdlib's messages and the
+    hooks receive the same arguments they would from the stdlib decoder.
+    
+    # Arguments
+    
+    * `s` - The JSON document as `str`, `bytes`, `bytearray` or `memoryview`
+    * `cls` - Only `None` is supported; custom decoder classes are rejected
+    * `object_hook` - Called with every decoded `dict`; its return value replaces the dict
+    * `parse_float` - Called with the text of every JSON float (e.g. `decimal.Decimal`)
+    * `parse_int` - Called with the text of every JSON int
+    * `parse_constant` - Called with `"NaN"`, `"Infinity"` or `"-Infinity"`
+    * `object_pairs_hook` - Called with the list of `(key, value)` pairs of every object; takes priority over `object_hook`
+    
+    # Returns
+    
+    `Any` - The decoded Python object
+    
+    # Raises
+    
+    * `JSONDecodeError` - If the input is not valid JSON, with the stdlib's messages and positions
+    * `TypeError` - If `cls` is given or the input is not a `str` or a bytes-like object
+    
+    # Examples
+    ```python
+    import decimal
+    import fastpy_rs.json as json
+    
+    data = json.loads('{"price": 1.10, "qty": 3}', parse_float=decimal.Decimal)
+    print(data)  # Output: {'price': Decimal('1.10'), 'qty': 3}
+    ```
+    """
+
+def load(
+    fp: Any,
+    *,
+    cls: None = None,
+    object_hook: Optional[Callable[[dict], Any]] = None,
+    parse_float: Optional[Callable[[str], Any]] = None,
+    parse_int: Optional[Callable[[str], Any]] = None,
+    parse_constant: Optional[Callable[[str], Any]] = None,
+    object_pairs_hook: Optional[Callable[[List[Tuple[str, Any]]], Any]] = None,
+) -> Any:
+    r"""
+    Deserializes a JSON document read from a file-like object, as a drop-in replacement for `json.load`.
+    
+    # Arguments
+    
+    * `fp` - An object with a `read()` method returning `str` or `bytes`
+    * Keyword arguments are the same as for `loads`
+    
+    # Returns
+    
+    `Any` - The decoded Python object
+    
+    # Raises
+    
+    * `JSONDecodeError` - If the content is not valid JSON
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    with open("config.json", "rb") as f:
+        config = json.load(f)
+    ```
+    """
+
+def dumps(
+    obj: Any,
+    *,
+    skipkeys: bool = False,
+    ensure_ascii: bool = True,
+    check_circular: bool = True,
+    allow_nan: bool = True,
+    cls: None = None,
+    indent: Optional[Union[int, str]] = None,
+    separators: Optional[Tuple[str, str]] = None,
+    default: Optional[Callable[[Any], Any]] = None,
+    sort_keys: bool = False,
+) -> str:
+    r"""
+    Serializes a Python object to a JSON string, as a drop-in replacement for `json.dumps`.
+    
+    Defaults are the stdlib's (`ensure_ascii=True`, `", "` and `": "` separators, `NaN` allowed),
+    so the output is identical to `json.dumps`. Everything `serialize_json` accepts is
+    supported here as well.
+    
+    # Arguments
+    
+    * `obj` - A Python object to serialize
+    * `skipkeys` - Silently drop dict keys that are not str, int, float, bool or `None` instead of raising
+    * `ensure_ascii` - Escape every non-ASCII character as `\uXXXX`
+    * `check_circular` - Detect reference cycles and raise `ValueError` instead of recursing until `RecursionError`
+    * `allow_nan` - Write `NaN`, `Infinity` and `-Infinity` for non-finite floats instead of raising
+    * `cls` - Only `None` is supported; custom encoder classes are rejected
+    * `indent` - Pretty-print with this many spaces (or this string) per level
+    * `separators` - `(item_separator, key_separator)` tuple; defaults to `(", ", ": ")`, or `(",", ": ")` when `indent` is set
+    * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+    * `sort_keys` - Write dictionary keys in sorted order
+    
+    # Returns
+    
+    `str` - A JSON string representation of the input object
+    
+    # Raises
+    
+    * `ValueError` - For unserializable objects, circular references or non-finite floats with `allow_nan=False`
+    * `RecursionError` - If the object is nested too deeply
+    * `TypeError` - If `cls` is given or `indent` is neither an int, a str nor `None`
+    * Any exception raised by `default`, unchanged
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    print(json.dumps({"b": [1, 2.5, None], "a": "é"}, sort_keys=True))
+    # Output: {"a": "\u00e9", "b": [1, 2.5, null]}
+    ```
+    """
+
+def dump(
+    obj: Any,
+    fp: Any,
+    *,
+    skipkeys: bool = False,
+    ensure_ascii: bool = True,
+    check_circular: bool = True,
+    allow_nan: bool = True,
+    cls: None = None,
+    indent: Optional[Union[int, str]] = None,
+    separators: Optional[Tuple[str, str]] = None,
+    default: Optional[Callable[[Any], Any]] = None,
+    sort_keys: bool = False,
+) -> None:
+    r"""
+    Serializes a Python object as JSON to a file-like object, as a drop-in replacement for `json.dump`.
+    
+    # Arguments
+    
+    * `obj` - A Python object to serialize
+    * `fp` - An object with a `write()` method accepting `str`
+    * Keyword arguments are the same as for `dumps`
+    
+    # Raises
+    
+    * The same exceptions as `dumps`, plus anything raised by `fp.write`
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    with open("config.json", "w") as f:
+        json.dump({"debug": False}, f, indent=2)
+    ```
+    """
+
+def load_file(path: Union[str, os.PathLike]) -> Any:
+    r"""
+    Parses the JSON file at `path` into the corresponding Python object.
+    
+    The file is memory-mapped, or decompressed first when it is gzip, with the GIL released;
+    parsing then works directly on the mapped bytes, without first copying them into a
+    Python `str` the way `parse_json(open(path).read())` does. A UTF-8 BOM is skipped.
+    
+    # Arguments
+    
+    * `path` - The file to read, as `str` or `os.PathLike`; gzip files (such as `*.json.gz`) are decompressed transparently
+    
+    # Returns
+    
+    * `Any` - The parsed document, as `parse_json` would return it
+    
+    # Raises
+    
+    * `JSONDecodeError` - If the file is not valid JSON; its `doc` is empty rather than a copy of the
+      file, while `pos`, `lineno` and `colno` still point into it
+    * `OSError` - If the file cannot be read, or is gzip data that is corrupt
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    data = json.load_file("dump.json")
+    events = json.load_file("events.json.gz")
+    ```
+    """
+
+def dump_file(
+    obj: Any,
+    path: Union[str, os.PathLike],
+    *,
+    indent: Optional[Union[int, str]] = None,
+    sort_keys: bool = False,
+    ensure_ascii: bool = False,
+    separators: Optional[Tuple[str, str]] = None,
+    default: Optional[Callable[[Any], Any]] = None,
+    skipkeys: bool = False,
+    allow_nan: bool = False,
+    check_circular: bool = True,
+    compress_level: int = 9,
+) -> None:
+    r"""
+    Serializes `obj` as JSON into the file at `path`.
+    
+    Output is serialized exactly like `serialize_json` does and streamed to the file in
+    chunks as it is produced, so the whole document is never held in memory. The GIL is
+    held while `obj` is being walked, so other threads cannot change it halfway through,
+    while a background thread compresses and writes the finished chunks without it.
+    A path ending in `.gz` is written gzip-compressed.
+    
+    # Arguments
+    
+    * `obj` - A Python object to serialize
+    * `path` - Where to write, as `str` or `os.PathLike`; an existing file is overwritten
+    * `compress_level` - The gzip compression level from 0 to 9, used for `.gz` paths
+    * The other keyword arguments are the same as for `serialize_json`
+    
+    # Raises
+    
+    * `ValueError` - For unserializable objects, circular references, non-finite floats with
+      `allow_nan=False` or a bad `compress_level`; the file is left partially written
+    * `OSError` - If the file cannot be written
+    * Any exception raised by `default`, unchanged
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    json.dump_file({"users": users}, "users.json", indent=2)
+    json.dump_file(events, "events.json.gz")
+    ```
+    """
+
+def canonicalize(obj: Any, *, default: Optional[Callable[[Any], Any]] = None) -> bytes:
+    r"""
+    Serializes a Python object to its canonical JSON form (RFC 8785).
+    
+    The output has no whitespace, object keys sorted by their UTF-16 code units, numbers
+    written the way ECMAScript's `Number.prototype.toString` writes them and strings with only
+    the escapes JSON requires. Equal data always gives the same bytes, so the result can be
+    hashed or signed and checked against any other JCS implementation.
+    
+    The same types as `serialize_json` are accepted. JCS numbers are IEEE 754 doubles: floats
+    and `Decimal`s are rounded to the nearest double, while ints that a double cannot hold
+    exactly are rejected rather than silently changed.
+    
+    # Arguments
+    
+    * `obj` - A Python object to serialize
+    * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+    
+    # Returns
+    
+    * The canonical UTF-8 encoded JSON text as `bytes`
+    
+    # Raises
+    
+    * `ValueError` - For unserializable objects, circular references, `NaN`/`Infinity`, ints that
+      are not exactly representable as a double, or strings containing lone surrogates
+    * `RecursionError` - If the object is nested too deeply
+    * Any exception raised by `default`, unchanged
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    data = {"b": [1.0, 1e21, 0.000001], "a": "€\n"}
+    print(fastpy_rs.json.canonicalize(data))
+    # Output: b'{"a":"\xe2\x82\xac\\n","b":[1,1e+21,0.000001]}'
+    
+    # A content hash that matches other JCS implementations
+    digest = fastpy_rs.crypto.sha256(fastpy_rs.json.canonicalize(data))
+    ```
+    """
+
+class JsonLinesReader(Iterator[Any]):
+    r"""
+    Iterator over the values of a JSON Lines document, returned by `parse_jsonl(..., lazy=True)`.
+    """
+    def __iter__(self) -> "JsonLinesReader": ...
+    def __next__(self) -> Any: ...
+
+def parse_jsonl(
+    source: Union[bytes, bytearray, memoryview, str, os.PathLike],
+    *,
+    lazy: bool = False,
+) -> Union[List[Any], JsonLinesReader]:
+    r"""
+    Parses a JSON Lines document: one JSON value per line.
+    
+    Blank lines are skipped and a UTF-8 BOM at the start is ignored. The input is read and
+    split into lines with the GIL released, in batches, so even with `lazy=True` the
+    per-record cost is a single parse.
+    
+    # Arguments
+    
+    * `source` - The document as `bytes`/`bytearray`/`memoryview`, or a path (`str` or `os.PathLike`) to read it from
+    * `lazy` - Return an iterator that reads the input batch by batch instead of a list of every record
+    
+    # Returns
+    
+    `list | JsonLinesReader` - A list of the parsed values, or an iterator over them with `lazy=True`
+    
+    # Raises
+    
+    * `JSONDecodeError` - If a line is not valid JSON; `lineno` is the line in the input and `doc` that line's text
+    * `OSError` - If the file cannot be read
+    * `TypeError` - If `source` is neither bytes-like nor a path
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    records = fastpy_rs.json.parse_jsonl(b'{"id": 1}\n{"id": 2}\n')
+    print(records)  # Output: [{'id': 1}, {'id': 2}]
+    
+    # Stream a large file without holding every record in memory
+    for event in fastpy_rs.json.parse_jsonl("events.jsonl", lazy=True):
+        print(event["type"])
+    ```
+    """
+
+def dump_jsonl(
+    iterable: Iterable[Any],
+    path: Union[str, os.PathLike],
+    *,
+    sort_keys: bool = False,
+    ensure_ascii: bool = False,
+    default: Optional[Callable[[Any], Any]] = None,
+) -> int:
+    r"""
+    Writes every item of `iterable` as one compact JSON document per line.
+    
+    Items are serialized exactly like `serialize_json` does; the output is buffered and
+    written to the file with the GIL released.
+    
+    # Arguments
+    
+    * `iterable` - Any iterable of serializable objects (a generator works too)
+    * `path` - Where to write, as `str` or `os.PathLike`; an existing file is overwritten
+    * `sort_keys` - Write dictionary keys in sorted order
+    * `ensure_ascii` - Escape every non-ASCII character as `\uXXXX`
+    * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+    
+    # Returns
+    
+    `int` - The number of lines written
+    
+    # Raises
+    
+    * `ValueError` - If an item cannot be serialized; the lines before it have already been written
+    * `OSError` - If the file cannot be written
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    count = fastpy_rs.json.dump_jsonl(({"id": i} for i in range(3)), "ids.jsonl")
+    print(count)  # Output: 3
+    ```
+    """
+
+class ItemsIterator(Iterator[Any]):
+    r"""
+    Iterator returned by `iter_items`.
+    """
+    def __iter__(self) -> "ItemsIterator": ...
+    def __next__(self) -> Any: ...
+
+def iter_items(
+    source: Union[str, os.PathLike, IO[bytes], IO[str], bytes, bytearray, memoryview],
+    prefix: str = "",
+) -> ItemsIterator:
+    r"""
+    Iterates over the values found under `prefix` in a JSON document, reading it incrementally.
+    
+    `prefix` uses ijson's notation: object keys and `item` for array elements, joined by dots.
+    `""` is the whole document, `"item"` every element of a top-level array and
+    `"results.item"` every element of the array under the `"results"` key. Memory use is
+    bounded by the largest single matching value, not by the size of the document.
+    
+    # Arguments
+    
+    * `source` - A path (`str` or `os.PathLike`), a binary or text file object, or the document as bytes
+    * `prefix` - Where the values to yield are located
+    
+    # Returns
+    
+    `ItemsIterator` - An iterator over the matching values as Python objects
+    
+    # Raises
+    
+    * `JSONDecodeError` - If the document is not valid JSON, raised when the iterator reaches the problem; `pos` is a byte offset
+    * `OSError` - If the file cannot be read
+    * `TypeError` - If `source` is neither a path, a file object nor bytes-like
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    # {"count": 2, "results": [{"id": 1}, {"id": 2}]}
+    for result in fastpy_rs.json.iter_items("export.json", prefix="results.item"):
+        print(result["id"])
+    
+    with open("events.json", "rb") as f:
+        first = next(fastpy_rs.json.iter_items(f, "item"))
+    ```
+    """
+
+class Query:
+    r"""
+    A compiled JMESPath or JSONPath expression, returned by `compile`.
+    """
+    def __init__(self, expression: str) -> None: ...
+    @property
+    def expression(self) -> str:
+        r"""
+        The source text of the expression.
+        """
+    def search(self, doc: Any) -> Any:
+        r"""
+        Evaluates the expression against `doc`, which may be a parsed document or JSON text.
+        """
+
+def query(doc: Any, expression: Union[str, Query]) -> Any:
+    r"""
+    Evaluates a JMESPath or JSONPath expression against a document.
+    
+    Expressions starting with `$` are JSONPath (RFC 9535) and return the list of every
+    matching value. Anything else is JMESPath and returns a single result, `None` when
+    nothing matches. Compiled expressions are cached, so calling `query` in a loop with
+    the same expression only parses it once.
+    
+    # Arguments
+    
+    * `doc` - A parsed document (dicts, lists and scalars), or JSON text as `str`, `bytes`, `bytearray` or `memoryview`
+    * `expression` - A JMESPath or JSONPath expression, or a `Query` returned by `compile`
+    
+    # Returns
+    
+    `Any` - The JMESPath result, or a list of matches for JSONPath
+    
+    # Raises
+    
+    * `ValueError` - If the expression is invalid or uses an unknown function
+    * `TypeError` - If a JMESPath function is called with arguments of the wrong type
+    * `JSONDecodeError` - If `doc` is JSON text that does not parse
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    doc = {"users": [{"name": "Ann", "age": 31}, {"name": "Bob", "age": 17}]}
+    print(fastpy_rs.json.query(doc, "users[?age > `18`].name"))  # Output: ['Ann']
+    print(fastpy_rs.json.query(doc, "$.users[*].age"))            # Output: [31, 17]
+    print(fastpy_rs.json.query('{"a": {"b": [1, 2]}}', "a.b[-1]"))  # Output: 2
     ```
-    """
\ No newline at end of file
+    """
+
+def compile(expression: str) -> Query:
+    r"""
+    Compiles a JMESPath or JSONPath expression once for repeated use.
+    
+    # Arguments
+    
+    * `expression` - A JMESPath expression, or a JSONPath expression starting with `$`
+    
+    # Returns
+    
+    `Query` - A compiled expression whose `search(doc)` method evaluates it
+    
+    # Raises
+    
+    * `ValueError` - If the expression is invalid or uses an unknown function
+    
+    # Examples
+    ```python
+    import fastpy_rs
+    
+    names = fastpy_rs.json.compile("items[].name")
+    for page in pages:
+        print(names.search(page))
+    ```
+    """
+
+class ValidationError(ValueError):
+    r"""
+    Subclass of ValueError describing why a document does not match a JSON Schema or the
+    type given to `decode`.
+    """
+    message: str
+    path: str
+    schema_path: Optional[str]
+    instance: Any
+
+class Validator:
+    r"""
+    A compiled JSON Schema.
+    
+    Documents passed as `str`, `bytes`, `bytearray` or `memoryview` are treated as JSON text
+    and validated without creating any Python objects; anything else is validated as an
+    already parsed document.
+    
+    # Arguments
+    
+    * `schema` - The schema as a dict (or bool), or as JSON text
+    * `draft` - `4`, `6`, `7`, `2019` or `2020`; by default the draft is taken from `$schema`, falling back to 2020-12
+    
+    # Raises
+    
+    * `ValueError` - If the schema is not a valid JSON Schema or `draft` is not supported
+    * `JSONDecodeError` - If the schema is JSON text that does not parse
+    * `RecursionError` - If the schema, or a document checked against it, is nested more
+      than 1000 levels deep
+    
+    Documents are checked the same way, and raise `ValueError` if they hold an int that does
+    not fit in 64 bits.
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    validator = json.Validator({"type": "object", "required": ["id"], "properties": {"id": {"type": "integer"}}})
+    print(validator.is_valid('{"id": 7}'))     # Output: True
+    for error in validator.iter_errors({"id": "7"}):
+        print(error.path, error.message)         # Output: /id "7" is not of type "integer"
+    ```
+    """
+    def __init__(self, schema: Any, *, draft: Optional[int] = None) -> None: ...
+    @property
+    def draft(self) -> int:
+        r"""
+        The draft the schema is validated under, as `4`, `6`, `7`, `2019` or `2020`.
+        """
+    def is_valid(self, instance: Any) -> bool:
+        r"""
+        Returns whether `instance` is valid; `str` and bytes-like instances are parsed as JSON text first.
+        """
+    def validate(self, instance: Any) -> None:
+        r"""
+        Raises the first `ValidationError` found in `instance`, if there is one.
+        """
+    def iter_errors(self, instance: Any) -> Iterator[ValidationError]:
+        r"""
+        Returns an iterator over every `ValidationError` in `instance`, each with JSON Pointer
+        `path` and `schema_path` attributes.
+        """
+
+def decode(data: Any, *, type: Any) -> Any:
+    r"""
+    Decodes JSON into an instance of `type`, checking every value against its type hints.
+    
+    Supported types are dataclasses, `TypedDict`s, `list[...]`, `tuple[...]`, `set[...]`,
+    `frozenset[...]`, `dict[str, ...]` and `dict[int, ...]`, `Optional[...]` and other unions,
+    `Literal[...]`, `Enum` subclasses, `Annotated[...]`, `NewType`s, `typing.Any`, the JSON
+    scalars (`str`, `int`, `float`, `bool`, `None`), and `datetime`, `date`, `time`, `UUID`,
+    `Decimal` and `bytes` as written by `serialize_json`. Forward references are resolved and
+    types may be recursive.
+    
+    Checks are strict: an `int` field does not accept `1.0` or `"1"`, though a `float` field
+    accepts ints. Dataclass fields with a default may be missing, and unknown keys are ignored.
+    The type hints of each type are introspected once and cached.
+    
+    JSON text is read by the same decoder as `parse_json`: ints of any size stay exact, and a
+    `Decimal` gets a number's digits exactly as written, so `1.10` becomes `Decimal('1.10')`.
+    
+    # Arguments
+    
+    * `data` - JSON text as `str`, `bytes`, `bytearray` or `memoryview`, or already parsed data
+    * `type` - The type to decode into
+    
+    # Returns
+    
+    * `Any` - An instance of `type`
+    
+    # Raises
+    
+    * `ValidationError` - If the data does not match the type; `path` is a JSON Pointer to the
+      offending value, which is also named in the message
+    * `JSONDecodeError` - If `data` is JSON text that does not parse
+    * `RecursionError` - If `data` is nested more than 1000 levels deep
+    * `TypeError` - If `type` is not supported
+    
+    # Examples
+    ```python
+    from dataclasses import dataclass
+    from typing import Optional
+    import fastpy_rs.json as json
+    
+    @dataclass
+    class Item:
+        sku: str
+        qty: int = 1
+    
+    @dataclass
+    class Order:
+        id: int
+        items: list[Item]
+        note: Optional[str] = None
+    
+    order = json.decode('{"id": 7, "items": [{"sku": "A-1", "qty": 2}, {"sku": "B-2"}]}', type=Order)
+    print(order.items[1])  # Output: Item(sku='B-2', qty=1)
+    
+    json.decode('{"id": 7, "items": [{"sku": "A-1", "qty": "2"}]}', type=Order)
+    # ValidationError: Expected `int`, got `str` - at `/items/0/qty`
+    ```
+    """
+
+def infer_schema(
+    samples: Union[Iterable[Any], str, bytes],
+    *,
+    max_enum: int = 10,
+    detect_formats: bool = True,
+) -> dict:
+    r"""
+    Infers a JSON Schema (draft 2020-12) that every sample document validates against.
+    
+    Types are merged across samples, so a value that is sometimes `null` gets
+    `"type": ["string", "null"]`, and ints and floats together become `number`. Object
+    properties present in every sample are `required`. A string field becomes an `enum`
+    when it has at most `max_enum` distinct values and each value occurs twice on average;
+    either way it also gets a `format` when all its values are `date-time`, `date`, `time`,
+    `uuid`, `email`, `uri` or `ipv4`.
+    
+    # Arguments
+    
+    * `samples` - An iterable of parsed documents, or JSON text holding an array of documents
+    * `max_enum` - Most distinct values a string field may have to become an `enum`; `0` disables enums
+    * `detect_formats` - Whether to detect string formats
+    
+    # Returns
+    
+    * `dict` - The schema, ready for `serialize_json` or `Validator`
+    
+    # Raises
+    
+    * `ValueError` - If there are no samples, or `samples` is JSON text that is not an array
+    * `JSONDecodeError` - If `samples` is JSON text that does not parse
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    samples = [
+        {"id": 1, "status": "active", "email": "ann@example.com", "tags": ["a"]},
+        {"id": 2, "status": "active", "email": "bob@example.com", "note": None},
+        {"id": 3, "status": "closed", "email": "eve@example.com", "note": "late"},
+        {"id": 4, "status": "active", "email": "joe@example.com", "note": None},
+    ]
+    schema = json.infer_schema(samples)
+    print(schema["required"])                 # Output: ['email', 'id', 'status']
+    print(schema["properties"]["status"])     # Output: {'type': 'string', 'enum': ['active', 'closed']}
+    print(schema["properties"]["email"])      # Output: {'type': 'string', 'format': 'email'}
+    print(schema["properties"]["note"])       # Output: {'type': ['string', 'null']}
+    ```
+    """
+
+def flatten(
+    obj: Union[dict, List[dict], str, bytes],
+    *,
+    sep: str = ".",
+    max_level: Optional[int] = None,
+) -> Union[dict, List[dict]]:
+    r"""
+    Flattens nested objects into a single level, joining keys with `sep`.
+    
+    Arrays and empty objects are kept as values, as `pandas.json_normalize` does.
+    An array of objects is flattened object by object.
+    
+    # Arguments
+    
+    * `obj` - A dict or a list of dicts, or JSON text holding one
+    * `sep` - The separator placed between nested keys
+    * `max_level` - How many levels of nesting to flatten; by default all of them
+    
+    # Returns
+    
+    * `Union[dict, List[dict]]` - The flattened dict, or a list of them
+    
+    # Raises
+    
+    * `ValueError` - If two keys flatten to the same name, as `{"a.b": 1, "a": {"b": 2}}` does, or a dict contains itself
+    * `RecursionError` - If objects are nested more than 1000 levels deep
+    * `TypeError` - If `obj` is not an object or an array of objects, or a dict has a key that is not a `str`
+    * `JSONDecodeError` - If `obj` is JSON text that does not parse
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    print(json.flatten({"id": 1, "user": {"name": "Ann", "address": {"city": "Oslo"}}, "tags": ["a"]}))
+    # Output: {'id': 1, 'user.name': 'Ann', 'user.address.city': 'Oslo', 'tags': ['a']}
+    print(json.flatten('{"a": {"b": {"c": 1}}}', sep="_", max_level=1))
+    # Output: {'a_b': {'c': 1}}
+    ```
+    """
+
+def unflatten(obj: Union[dict, List[dict], str, bytes], *, sep: str = ".") -> Union[dict, List[dict]]:
+    r"""
+    Rebuilds nested objects from keys joined with `sep`, undoing `flatten`.
+    
+    # Arguments
+    
+    * `obj` - A flat dict or a list of them, or JSON text holding one
+    * `sep` - The separator between nested keys
+    
+    # Returns
+    
+    * `Union[dict, List[dict]]` - The nested dict, or a list of them
+    
+    # Raises
+    
+    * `ValueError` - If `sep` is empty, or a key such as `a.b` needs `a` to be an object while `a` has a value of its own
+    * `TypeError` - If `obj` is not an object or an array of objects, or a dict has a key that is not a `str`
+    * `JSONDecodeError` - If `obj` is JSON text that does not parse
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    print(json.unflatten({"id": 1, "user.name": "Ann", "user.address.city": "Oslo"}))
+    # Output: {'id': 1, 'user': {'name': 'Ann', 'address': {'city': 'Oslo'}}}
+    ```
+    """
+
+def to_rows(
+    records: Union[dict, List[dict], str, bytes],
+    record_path: Optional[Union[str, List[str]]] = None,
+    meta: Optional[List[Union[str, List[str]]]] = None,
+    *,
+    sep: str = ".",
+    max_level: Optional[int] = None,
+    meta_prefix: Optional[str] = None,
+    record_prefix: Optional[str] = None,
+    errors: str = "raise",
+) -> List[dict]:
+    r"""
+    Turns records into flat row dicts, exploding the array at `record_path` into one row per item.
+    
+    Without `record_path` every record becomes one flattened row. With it, each item of
+    the array found by following `record_path` from a record becomes a row, and each
+    `meta` field of that record is copied into all of its rows. A `record_path` with several
+    keys explodes nested arrays: `["orders", "lines"]` gives one row per line of each order.
+    Items that are not objects are placed in the rows under the key `"0"`. This mirrors
+    `pandas.json_normalize`, except that `meta` paths always start from the record.
+    
+    # Arguments
+    
+    * `records` - A list of dicts or a single dict, or JSON text holding one
+    * `record_path` - A key or list of keys leading to the array to explode
+    * `meta` - Fields of each record to add to its rows, as keys or lists of keys into nested objects
+    * `sep` - The separator placed between nested keys
+    * `max_level` - How many levels of nesting to flatten in each row; by default all of them
+    * `meta_prefix` - A prefix for the meta keys
+    * `record_prefix` - A prefix for the keys taken from the exploded items
+    * `errors` - `"raise"` to fail when a record lacks a `meta` field, or `"ignore"` to use `None`
+    
+    # Returns
+    
+    * `List[dict]` - A list of flat row dicts
+    
+    # Raises
+    
+    * `KeyError` - If a record lacks a key of `record_path`, or a `meta` field while `errors` is `"raise"`
+    * `TypeError` - If `record_path` leads to something other than an array or null, or the records are not objects
+    * `ValueError` - If `meta` is given without `record_path`, a meta key clashes with a row key, two keys
+      of a record flatten to the same name, a record contains itself, or `errors` is invalid
+    * `RecursionError` - If a record is nested more than 1000 levels deep
+    * `JSONDecodeError` - If `records` is JSON text that does not parse
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    data = [
+        {"id": 1, "customer": {"name": "Ann"}, "lines": [{"sku": "A", "qty": 2}, {"sku": "B", "qty": 1}]},
+        {"id": 2, "customer": {"name": "Bob"}, "lines": [{"sku": "C", "qty": 5}]},
+    ]
+    for row in json.to_rows(data, record_path="lines", meta=["id", ["customer", "name"]]):
+        print(row)
+    # Output:
+    # {'sku': 'A', 'qty': 2, 'id': 1, 'customer.name': 'Ann'}
+    # {'sku': 'B', 'qty': 1, 'id': 1, 'customer.name': 'Ann'}
+    # {'sku': 'C', 'qty': 5, 'id': 2, 'customer.name': 'Bob'}
+    ```
+    """
+
+def diff(a: Any, b: Any) -> List[dict]:
+    r"""
+    Computes the JSON Patch (RFC 6902) that turns `a` into `b`.
+    
+    Objects are compared key by key and arrays element by element, so the patch only
+    touches what changed. Applying the result to `a` with `apply_patch` gives a document
+    equal to `b`.
+    
+    # Arguments
+    
+    * `a` - The source document
+    * `b` - The target document
+    
+    # Returns
+    
+    `List[dict]` - Patch operations, as dicts with `op`, `path` and `value` keys
+    
+    # Raises
+    
+    * `TypeError` - If a dict has a key that is not a `str`
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    patch = json.diff({"a": 1, "b": [1, 2]}, {"a": 2, "b": [1, 2, 3]})
+    print(patch)
+    # Output: [{'op': 'replace', 'path': '/a', 'value': 2}, {'op': 'add', 'path': '/b/2', 'value': 3}]
+    ```
+    """
+
+def apply_patch(doc: Any, patch: Union[List[dict], str, bytes], *, in_place: bool = False) -> Any:
+    r"""
+    Applies a JSON Patch (RFC 6902) to a document.
+    
+    Operations are applied in order. By default the patch is atomic: `doc` is left
+    untouched and a new document is returned, or nothing changes at all if an operation
+    fails.
+    
+    # Arguments
+    
+    * `doc` - The document to patch
+    * `patch` - A list of operations, or the patch as JSON text
+    * `in_place` - Modify `doc` itself instead of a copy; a failed operation then leaves the earlier ones applied
+    
+    # Returns
+    
+    `Any` - The patched document
+    
+    # Raises
+    
+    * `ValueError` - If an operation is malformed, refers to a location that does not exist or a `test` fails
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    doc = {"users": [{"name": "Ann"}]}
+    patched = json.apply_patch(doc, [
+        {"op": "add", "path": "/users/-", "value": {"name": "Bob"}},
+        {"op": "replace", "path": "/users/0/name", "value": "Anna"},
+    ])
+    print(patched)  # Output: {'users': [{'name': 'Anna'}, {'name': 'Bob'}]}
+    ```
+    """
+
+def merge_patch(doc: Any, patch: Any) -> Any:
+    r"""
+    Applies a JSON Merge Patch (RFC 7396) to a document.
+    
+    Keys of `patch` replace those of `doc`, recursively for nested objects, and keys set
+    to `None` are removed. A patch that is not an object replaces the document entirely.
+    
+    # Arguments
+    
+    * `doc` - The document to patch; it is not modified
+    * `patch` - The merge patch
+    
+    # Returns
+    
+    `Any` - The patched document
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    config = {"host": "db", "pool": {"min": 1, "max": 4}, "debug": True}
+    print(json.merge_patch(config, {"pool": {"max": 8}, "debug": None}))
+    # Output: {'host': 'db', 'pool': {'min': 1, 'max': 8}}
+    ```
+    """
+
+def pointer_get(doc: Any, pointer: str, default: Any = None) -> Any:
+    r"""
+    Returns the value a JSON Pointer (RFC 6901) refers to, like `dict.get`.
+    
+    # Arguments
+    
+    * `doc` - The document
+    * `pointer` - A JSON Pointer such as `"/users/0/name"`; `""` is the whole document
+    * `default` - Returned when the location does not exist
+    
+    # Returns
+    
+    `Any` - The value at `pointer` (the object itself, not a copy), or `default`
+    
+    # Raises
+    
+    * `ValueError` - If `pointer` is not a valid JSON Pointer
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    doc = {"users": [{"name": "Ann"}], "a/b": 1}
+    print(json.pointer_get(doc, "/users/0/name"))  # Output: Ann
+    print(json.pointer_get(doc, "/a~1b"))          # Output: 1
+    print(json.pointer_get(doc, "/users/5", None))  # Output: None
+    ```
+    """
+
+def pointer_set(doc: Any, pointer: str, value: Any) -> Any:
+    r"""
+    Sets the value at a JSON Pointer (RFC 6901), modifying the document in place.
+    
+    The parent of the location must exist. In an array, an index replaces the element
+    there and `-` appends.
+    
+    # Arguments
+    
+    * `doc` - The document to modify
+    * `pointer` - A JSON Pointer such as `"/users/0/name"`
+    * `value` - The value to store
+    
+    # Returns
+    
+    `Any` - The document, which is `value` itself when `pointer` is `""`
+    
+    # Raises
+    
+    * `KeyError` - If the parent of the location does not exist or the index is out of range
+    * `ValueError` - If `pointer` is not a valid JSON Pointer
+    * `TypeError` - If the parent is not a dict or list
+    
+    # Examples
+    ```python
+    import fastpy_rs.json as json
+    
+    doc = {"users": [{"name": "Ann"}]}
+    json.pointer_set(doc, "/users/0/age", 31)
+    json.pointer_set(doc, "/users/-", {"name": "Bob"})
+    print(doc)  # Output: {'users': [{'name': 'Ann', 'age': 31}, {'name': 'Bob'}]}
+    ```
+    """
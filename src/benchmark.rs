@@ -1,8 +1,6 @@
-use std::sync::mpsc::{Receiver, Sender};
-
 use pyo3::exceptions::{PyRuntimeError, PyTypeError};
 use pyo3::prelude::*;
-use pyo3::types::{PyDelta, PyDict, PyList, PyTuple};
+use pyo3::types::{PyDelta, PyDict, PyTuple};
 
 /// Calls the passed callable and returns a timedelta object with the time it took to call the function.
 /// Expect natural overhead of 100-1000 microseconds but in extreme cases this can be a lot higher.
@@ -68,13 +68,13 @@ pub fn sha256_str(data: &str) -> String {
 pub fn md5_(_py: Python<'_>, data: Bound<'_, PyAny>) -> PyResult<String> {
     if let Ok(s) = data.downcast::<PyString>() {
         let sbytes = s.to_string();
-        return Ok(format!("{:x}", md5::compute(sbytes)));
+        Ok(format!("{:x}", md5::compute(sbytes)))
     } else if let Ok(b) = data.downcast::<PyBytes>() {
-        return Ok(format!("{:x}", md5::compute(b.as_bytes())));
+        Ok(format!("{:x}", md5::compute(b.as_bytes())))
     } else {
-        return Err(pyo3::exceptions::PyTypeError::new_err(
+        Err(pyo3::exceptions::PyTypeError::new_err(
             "Expected bytes or str",
-        ));
+        ))
     }
 }
 
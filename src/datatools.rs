@@ -76,19 +76,29 @@ pub fn base64_decode(data: String) -> PyResult<Vec<u8>> {
 #[pyfunction]
 #[pyo3(signature = (data, compress_level = 9))]
 pub fn gzip_compress(data: &[u8], compress_level: i32) -> PyResult<Vec<u8>> {
-    if compress_level > 9 || compress_level < 0 {
-        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Bad compression level : {}", compress_level)));
-    }
-    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(compress_level as u32));
+    let mut encoder = GzEncoder::new(Vec::new(), gzip_level(compress_level)?);
     encoder.write_all(data)?;
     let bytes = encoder.finish()?;
     Ok(bytes)
 }
 
 
+/// Checks a gzip compression level; also used to write `.gz` files in the `json` module.
+pub(crate) fn gzip_level(compress_level: i32) -> PyResult<Compression> {
+    if !(0..=9).contains(&compress_level) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Bad compression level : {}", compress_level)));
+    }
+    Ok(Compression::new(compress_level as u32))
+}
+
 #[pyfunction]
 pub fn gzip_decompress(data: &[u8]) -> PyResult<Vec<u8>> {
-    let mut decoder = bufread::GzDecoder::new(&data[..]);
+    Ok(gunzip(data)?)
+}
+
+/// Decompresses gzip data; also used to read `.gz` files in the `json` module.
+pub(crate) fn gunzip(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = bufread::GzDecoder::new(data);
     let mut out = Vec::new();
     decoder.read_to_end(&mut out)?;
     Ok(out)
@@ -103,5 +113,5 @@ pub fn url_encode(data: &str) -> PyResult<String> {
 pub fn url_decode(data: &str) -> PyResult<String> {
     decode(data)
         .map(|cow| cow.into_owned())
-        .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid UTF-8 sequence")))
+        .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid UTF-8 sequence"))
 }
\ No newline at end of file
@@ -1,17 +1,45 @@
 use pyo3::prelude::*;
-use pyo3::exceptions::PyValueError;
-use std::time::Duration;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::types::{PyByteArray, PyBytes, PyDict, PyList, PyString, PyTuple};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Method;
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+
+mod asyncio;
+mod batch;
+mod client;
+mod response;
+mod retry;
+
+pub use asyncio::{aget, arequest, AsyncClient};
+pub use batch::get_many;
+pub use client::Client;
+pub use response::Response;
+pub use retry::{request_error_type, Attempt, Retry};
+use response::ResponseData;
+use retry::{AttemptError, Failure};
+
+use crate::json;
+
+/// The timeout used when none is given, in seconds.
+const DEFAULT_TIMEOUT: f64 = 30.0;
 
 /// Makes an HTTP GET request to the specified URL and returns the response body as a string.
 ///
+/// Unlike `aget`, which returns a `Response`, this returns only the body; use
+/// `request("GET", url, ...)` to get the status, headers and `attempts` as well.
+///
 /// # Arguments
 /// * `url` - The URL to make the GET request to
+/// * `retry` - A `Retry` policy for sending the request again when it fails; by default it is sent once
 ///
 /// # Returns
-/// * A string containing the response body
+/// * A string containing the response body, decoded with the charset from `Content-Type` or UTF-8
 ///
 /// # Raises
-/// * `ValueError` - If the request fails or the response status is not successful
+/// * `ValueError` - If the response status is not successful (`"Status code: ..."`), after any retries
+/// * `RequestError` - A `ValueError` subclass raised if the request fails without a response; its `attempts` lists every try
 ///
 /// # Examples
 /// ```python
@@ -21,6 +49,9 @@ use std::time::Duration;
 /// response = fastpy_rs.http.get("https://httpbin.org/get")
 /// print(response)  # Output: JSON response from the server
 ///
+/// # Retry when the server is briefly unavailable
+/// body = fastpy_rs.http.get("https://httpbin.org/get", retry=fastpy_rs.http.Retry(max_attempts=3))
+///
 /// # Handle errors
 /// try:
 ///     fastpy_rs.http.get("https://nonexistent.url")
@@ -28,25 +59,373 @@ use std::time::Duration;
 ///     print(f"Request failed: {e}")
 /// ```
 #[pyfunction]
-pub fn get(py: Python, url: String) -> PyResult<String> {
-    let body = py.allow_threads(|| {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .map_err(|e| format!("Can't build client: {}", e))?;
+#[pyo3(signature = (url, *, retry = None))]
+pub fn get(py: Python, url: String, retry: Option<Retry>) -> PyResult<String> {
+    let spec = RequestSpec { retry, ..RequestSpec::new("GET", url, None, None, None, None, Some(DEFAULT_TIMEOUT))? };
+    let client = py.allow_threads(|| build_client(client_builder())).map_err(PyValueError::new_err)?;
+    let data = py.allow_threads(|| spec.send(&client)).map_err(|failure| failure.into_pyerr(py))?;
+    if !(200..300).contains(&data.status) {
+        return Err(PyValueError::new_err(format!("Status code: {}", response::status_line(data.status))));
+    }
+    Response::new(py, data)?.text(py)?.extract()
+}
+
+/// Sends an HTTP request and returns the `Response`.
+///
+/// Redirects are followed. A response with an error status is returned like any other;
+/// call `raise_for_status()` to turn it into an exception.
+///
+/// # Arguments
+/// * `method` - The HTTP method, such as `"GET"` or `"POST"`; case-insensitive
+/// * `url` - The URL to send the request to
+/// * `headers` - Extra request headers, as a dict of names to values
+/// * `params` - Query parameters appended to the URL, as a dict or a list of `(name, value)` pairs; a list value repeats the parameter and `None` values are left out
+/// * `json` - An object to send as a JSON body, serialized like `fastpy_rs.json.serialize_json` does
+/// * `data` - A raw body as `bytes` or `str`, or a dict or list of pairs sent as a form
+/// * `timeout` - The timeout for each attempt at the request, in seconds, or `None` to wait forever
+/// * `retry` - A `Retry` policy for sending the request again when it fails; by default it is sent once
+///
+/// # Returns
+/// * A `Response` with the status, headers, final URL and body
+///
+/// # Raises
+/// * `ValueError` - If the method is invalid or both `json` and `data` are given
+/// * `RequestError` - A `ValueError` subclass raised if the request fails without a response (`"Request failed: ..."`)
+/// * `TypeError` - If `data` or `params` has an unsupported type
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// response = fastpy_rs.http.request("GET", "https://httpbin.org/get", params={"q": "rust"})
+/// print(response.status)  # Output: 200
+/// print(response.json()["args"])  # Output: {'q': 'rust'}
+///
+/// response = fastpy_rs.http.request("PUT", "https://httpbin.org/put", data=b"raw", timeout=5)
+/// response.raise_for_status()
+///
+/// response = fastpy_rs.http.request("GET", "https://httpbin.org/get", retry=fastpy_rs.http.Retry(max_attempts=5))
+/// print(len(response.attempts))  # Output: 1
+/// ```
+#[pyfunction]
+#[pyo3(signature = (method, url, *, headers = None, params = None, json = None, data = None, timeout = Some(DEFAULT_TIMEOUT), retry = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn request<'py>(
+    py: Python<'py>,
+    method: &str,
+    url: String,
+    headers: Option<Bound<'py, PyDict>>,
+    params: Option<Bound<'py, PyAny>>,
+    json: Option<Bound<'py, PyAny>>,
+    data: Option<Bound<'py, PyAny>>,
+    timeout: Option<f64>,
+    retry: Option<Retry>,
+) -> PyResult<Response> {
+    let spec = RequestSpec { retry, ..RequestSpec::new(method, url, headers, params, json, data, timeout)? };
+    let client = py.allow_threads(|| build_client(client_builder())).map_err(PyValueError::new_err)?;
+    let data = py.allow_threads(|| spec.send(&client)).map_err(|failure| failure.into_pyerr(py))?;
+    Response::new(py, data)
+}
+
+macro_rules! shortcut {
+    ($(#[$doc:meta])* $name:ident, $method:literal) => {
+        $(#[$doc])*
+        #[pyfunction]
+        #[allow(clippy::too_many_arguments)]
+        #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Some(DEFAULT_TIMEOUT), retry = None))]
+        pub fn $name<'py>(
+            py: Python<'py>,
+            url: String,
+            headers: Option<Bound<'py, PyDict>>,
+            params: Option<Bound<'py, PyAny>>,
+            json: Option<Bound<'py, PyAny>>,
+            data: Option<Bound<'py, PyAny>>,
+            timeout: Option<f64>,
+            retry: Option<Retry>,
+        ) -> PyResult<Response> {
+            request(py, $method, url, headers, params, json, data, timeout, retry)
+        }
+    };
+}
+
+shortcut! {
+    /// Sends a POST request; the same as `request("POST", url, ...)`.
+    ///
+    /// # Examples
+    /// ```python
+    /// import fastpy_rs
+    ///
+    /// response = fastpy_rs.http.post("https://httpbin.org/post", json={"id": 7})
+    /// print(response.json()["json"])  # Output: {'id': 7}
+    /// ```
+    post, "POST"
+}
+
+shortcut! {
+    /// Sends a PUT request; the same as `request("PUT", url, ...)`.
+    ///
+    /// # Examples
+    /// ```python
+    /// import fastpy_rs
+    ///
+    /// response = fastpy_rs.http.put("https://httpbin.org/put", data=b"\x00\x01")
+    /// ```
+    put, "PUT"
+}
+
+shortcut! {
+    /// Sends a PATCH request; the same as `request("PATCH", url, ...)`.
+    ///
+    /// # Examples
+    /// ```python
+    /// import fastpy_rs
+    ///
+    /// response = fastpy_rs.http.patch("https://httpbin.org/patch", json={"name": "new"})
+    /// ```
+    patch, "PATCH"
+}
+
+shortcut! {
+    /// Sends a DELETE request; the same as `request("DELETE", url, ...)`.
+    ///
+    /// # Examples
+    /// ```python
+    /// import fastpy_rs
+    ///
+    /// response = fastpy_rs.http.delete("https://httpbin.org/delete")
+    /// print(response.status)  # Output: 200
+    /// ```
+    delete, "DELETE"
+}
+
+shortcut! {
+    /// Sends a HEAD request; the same as `request("HEAD", url, ...)`.
+    ///
+    /// The response has the headers only, and `content` is always empty.
+    ///
+    /// # Examples
+    /// ```python
+    /// import fastpy_rs
+    ///
+    /// response = fastpy_rs.http.head("https://httpbin.org/get")
+    /// print(response.headers["content-type"])  # Output: application/json
+    /// ```
+    head, "HEAD"
+}
+
+/// Builds the request described by a `RequestSpec` on `client`.
+///
+/// The blocking and async request builders have the same methods but no common trait.
+macro_rules! build_request {
+    ($client:expr, $spec:expr) => {{
+        let spec = $spec;
+        let mut builder = $client.request(spec.method.clone(), &spec.url);
+        if !spec.query.is_empty() {
+            builder = builder.query(&spec.query);
+        }
+        if let Some(body) = &spec.body {
+            let has_content_type = spec.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+            if let (Some(content_type), false) = (body.content_type, has_content_type) {
+                builder = builder.header(CONTENT_TYPE, content_type);
+            }
+            builder = builder.body(body.content.clone());
+        }
+        for (name, value) in &spec.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(timeout) = spec.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder
+    }};
+}
+
+/// A request with every argument converted to Rust values, so it can be sent with the GIL
+/// released.
+#[derive(Clone)]
+struct RequestSpec {
+    method: Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    body: Option<Body>,
+    timeout: Option<Duration>,
+    retry: Option<Retry>,
+}
+
+#[derive(Clone)]
+struct Body {
+    content: Vec<u8>,
+    content_type: Option<&'static str>,
+}
 
-        let resp = client
-            .get(&url)
-            .send()
-            .map_err(|e| format!("Request failed: {}", e))?;
+impl RequestSpec {
+    fn new<'py>(
+        method: &str,
+        url: String,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Option<f64>,
+    ) -> PyResult<Self> {
+        let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+            .map_err(|_| PyValueError::new_err(format!("Invalid HTTP method: {:?}", method)))?;
+        let headers = match headers {
+            Some(headers) => pairs(&headers, "headers")?,
+            None => Vec::new(),
+        };
+        let query = match params {
+            Some(params) => pairs(&params, "params")?,
+            None => Vec::new(),
+        };
+        let body = match (json, data) {
+            (Some(_), Some(_)) => return Err(PyValueError::new_err("Pass either `json` or `data`, not both")),
+            (Some(obj), None) => Some(Body {
+                content: json::serialize_json(obj, None, false, false, None, None, false, false, true)?.into_bytes(),
+                content_type: Some("application/json"),
+            }),
+            (None, Some(data)) => Some(Body::from_data(&data)?),
+            (None, None) => None,
+        };
+        let timeout = timeout.map(seconds).transpose()?;
+        Ok(RequestSpec { method, url, headers, query, body, timeout, retry: None })
+    }
 
-        if !resp.status().is_success() {
-            return Err(format!("Status code: {}", resp.status()));
+    /// Sends the request on `client`, retrying as `retry` allows, and reads the whole response.
+    fn send(&self, client: &reqwest::blocking::Client) -> Result<ResponseData, Failure> {
+        let mut attempts = Vec::new();
+        loop {
+            let start = Instant::now();
+            let outcome = build_request!(client, self)
+                .send()
+                .map_err(|e| AttemptError::new("Request failed", e))
+                .and_then(ResponseData::read);
+            match self.next_attempt(&mut attempts, start, outcome) {
+                ControlFlow::Continue(delay) => std::thread::sleep(delay),
+                ControlFlow::Break(result) => return result,
+            }
         }
+    }
 
-        resp.text().map_err(|e| format!("Ошибка чтения тела: {}", e))
-    })
-    .map_err(|e| PyValueError::new_err(e))?;
+    /// Like `send`, but on an async client.
+    async fn send_async(&self, client: &reqwest::Client) -> Result<ResponseData, Failure> {
+        let mut attempts = Vec::new();
+        loop {
+            let start = Instant::now();
+            let outcome = match build_request!(client, self).send().await {
+                Ok(resp) => ResponseData::read_async(resp).await,
+                Err(e) => Err(AttemptError::new("Request failed", e)),
+            };
+            match self.next_attempt(&mut attempts, start, outcome) {
+                ControlFlow::Continue(delay) => tokio::time::sleep(delay).await,
+                ControlFlow::Break(result) => return result,
+            }
+        }
+    }
+
+    /// Records the attempt that started at `start` and ended with `outcome`, then returns
+    /// how long to wait before the next one, or the final result if there is none.
+    fn next_attempt(
+        &self,
+        attempts: &mut Vec<Attempt>,
+        start: Instant,
+        outcome: Result<ResponseData, AttemptError>,
+    ) -> ControlFlow<Result<ResponseData, Failure>, Duration> {
+        let number = attempts.len() as u32 + 1;
+        let delay = self.retry.as_ref().and_then(|retry| retry.delay(&self.method, number, &outcome));
+        attempts.push(Attempt::new(&outcome, start.elapsed(), delay.unwrap_or_default()));
+        if let Some(delay) = delay {
+            return ControlFlow::Continue(delay);
+        }
+        let attempts = std::mem::take(attempts);
+        ControlFlow::Break(match outcome {
+            Ok(data) => Ok(ResponseData { attempts, ..data }),
+            Err(err) => Err(Failure { message: err.message, attempts }),
+        })
+    }
+}
+
+impl Body {
+    fn from_data(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(text) = data.downcast::<PyString>() {
+            return Ok(Body { content: text.to_str()?.as_bytes().to_vec(), content_type: None });
+        }
+        if let Ok(bytes) = data.downcast::<PyBytes>() {
+            return Ok(Body { content: bytes.as_bytes().to_vec(), content_type: None });
+        }
+        if let Ok(bytes) = data.downcast::<PyByteArray>() {
+            return Ok(Body { content: bytes.to_vec(), content_type: None });
+        }
+        let form = pairs(data, "data")?
+            .iter()
+            .map(|(name, value)| format!("{}={}", form_encode(name), form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        Ok(Body { content: form.into_bytes(), content_type: Some("application/x-www-form-urlencoded") })
+    }
+}
+
+/// A client builder with the settings every request shares.
+///
+/// The blocking client applies a 30 second timeout unless told otherwise, so it is switched
+/// off here and each `RequestSpec` carries its own.
+fn client_builder() -> reqwest::blocking::ClientBuilder {
+    reqwest::blocking::Client::builder().timeout(None)
+}
+
+fn build_client(builder: reqwest::blocking::ClientBuilder) -> Result<reqwest::blocking::Client, String> {
+    builder.build().map_err(|e| format!("Can't build client: {}", e))
+}
+
+/// Percent-encodes a form field, with spaces written as `+`.
+fn form_encode(text: &str) -> String {
+    urlencoding::encode(text).replace("%20", "+")
+}
+
+/// Converts a dict or a list of `(name, value)` pairs into string pairs.
+///
+/// A list or tuple value adds one pair per item, and `None` values are left out.
+fn pairs(obj: &Bound<'_, PyAny>, arg: &str) -> PyResult<Vec<(String, String)>> {
+    let items: Vec<(Bound<'_, PyAny>, Bound<'_, PyAny>)> = if let Ok(dict) = obj.downcast::<PyDict>() {
+        dict.iter().collect()
+    } else {
+        let iter = match obj.try_iter() {
+            Ok(iter) if !obj.is_instance_of::<PyString>() && !obj.is_instance_of::<PyBytes>() => iter,
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "`{}` must be a dict or a list of pairs, not {}",
+                    arg,
+                    obj.get_type().name()?
+                )))
+            }
+        };
+        iter.map(|item| item?.extract()).collect::<PyResult<_>>().map_err(|_| {
+            PyTypeError::new_err(format!("`{}` must be a dict or a list of pairs", arg))
+        })?
+    };
+
+    let mut pairs = Vec::with_capacity(items.len());
+    for (name, value) in items {
+        let name = name.str()?.to_string();
+        if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+            for item in value.try_iter()? {
+                let item = item?;
+                if !item.is_none() {
+                    pairs.push((name.clone(), item.str()?.to_string()));
+                }
+            }
+        } else if !value.is_none() {
+            pairs.push((name, value.str()?.to_string()));
+        }
+    }
+    Ok(pairs)
+}
 
-    Ok(body)
+/// A timeout in seconds as a `Duration`.
+fn seconds(timeout: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(timeout)
+        .ok()
+        .filter(|duration| !duration.is_zero())
+        .ok_or_else(|| PyValueError::new_err(format!("Timeout must be a positive number of seconds, got {}", timeout)))
 }
@@ -0,0 +1,317 @@
+//! Asyncio versions of `request` and `Client`.
+//!
+//! Requests run on the tokio runtime shared by the whole extension and are handed to Python
+//! as asyncio futures. Cancelling the future drops the Rust side of the request, which
+//! closes its connection.
+
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::OnceCell;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_async_runtimes::tokio::future_into_py;
+
+use super::client::{Session, Timeout};
+use super::{RequestSpec, Response, Retry, DEFAULT_TIMEOUT};
+
+static SHARED_CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
+
+/// Sends an HTTP request without blocking the event loop; await the result to get the
+/// `Response`.
+///
+/// Takes the same arguments as `request`. Requests made this way share one pool of
+/// connections. Cancelling the awaiting task aborts the request, including any retries
+/// it is waiting to make.
+///
+/// # Returns
+/// * An awaitable that resolves to a `Response`
+///
+/// # Raises
+/// * `ValueError` - For the same reasons as `request`
+/// * `RequestError` - When awaited, if the request fails without a response
+/// * `RuntimeError` - If called outside a running event loop
+///
+/// # Examples
+/// ```python
+/// import asyncio
+/// import fastpy_rs
+///
+/// async def main():
+///     response = await fastpy_rs.http.arequest("POST", "https://httpbin.org/post", json={"id": 7})
+///     print(response.json()["json"])  # Output: {'id': 7}
+///
+/// asyncio.run(main())
+/// ```
+#[pyfunction]
+#[pyo3(signature = (method, url, *, headers = None, params = None, json = None, data = None, timeout = Some(DEFAULT_TIMEOUT), retry = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn arequest<'py>(
+    py: Python<'py>,
+    method: &str,
+    url: String,
+    headers: Option<Bound<'py, PyDict>>,
+    params: Option<Bound<'py, PyAny>>,
+    json: Option<Bound<'py, PyAny>>,
+    data: Option<Bound<'py, PyAny>>,
+    timeout: Option<f64>,
+    retry: Option<Retry>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let spec = RequestSpec { retry, ..RequestSpec::new(method, url, headers, params, json, data, timeout)? };
+    send(py, shared_client()?, spec)
+}
+
+/// Sends a GET request without blocking the event loop; the same as `arequest("GET", url, ...)`.
+///
+/// # Examples
+/// ```python
+/// import asyncio
+/// import fastpy_rs
+///
+/// async def main():
+///     urls = ["https://httpbin.org/get", "https://httpbin.org/ip"]
+///     responses = await asyncio.gather(*(fastpy_rs.http.aget(url) for url in urls))
+///     print([r.status for r in responses])  # Output: [200, 200]
+///
+/// asyncio.run(main())
+/// ```
+#[pyfunction]
+#[pyo3(signature = (url, *, headers = None, params = None, timeout = Some(DEFAULT_TIMEOUT), retry = None))]
+pub fn aget<'py>(
+    py: Python<'py>,
+    url: String,
+    headers: Option<Bound<'py, PyDict>>,
+    params: Option<Bound<'py, PyAny>>,
+    timeout: Option<f64>,
+    retry: Option<Retry>,
+) -> PyResult<Bound<'py, PyAny>> {
+    arequest(py, "GET", url, headers, params, None, None, timeout, retry)
+}
+
+/// The asyncio counterpart of `Client`.
+///
+/// Takes the same arguments as `Client`, and has the same request methods, except that
+/// each returns an awaitable. Use it as an async context manager, or await `aclose()`,
+/// to release the connections when done.
+///
+/// # Examples
+/// ```python
+/// import asyncio
+/// import fastpy_rs
+///
+/// async def main():
+///     async with fastpy_rs.http.AsyncClient(base_url="https://api.example.com/v1") as client:
+///         pages = await asyncio.gather(*(client.get("users", params={"page": n}) for n in range(1, 4)))
+///         users = [user for page in pages for user in page.json()]
+///
+/// asyncio.run(main())
+/// ```
+#[pyclass(module = "fastpy_rs.http", frozen)]
+pub struct AsyncClient {
+    /// The connection pool, or `None` once the client is closed.
+    client: Mutex<Option<reqwest::Client>>,
+    session: Session,
+}
+
+#[pymethods]
+impl AsyncClient {
+    #[new]
+    #[pyo3(signature = (*, base_url = None, headers = None, timeout = Some(DEFAULT_TIMEOUT), cookies = None, retry = None))]
+    fn new<'py>(
+        base_url: Option<&str>,
+        headers: Option<Bound<'py, PyDict>>,
+        timeout: Option<f64>,
+        cookies: Option<Bound<'py, PyDict>>,
+        retry: Option<Retry>,
+    ) -> PyResult<Self> {
+        let (session, cookies) = Session::new(base_url, headers, timeout, cookies, retry)?;
+        let client = build_client(reqwest::Client::builder().cookie_provider(Arc::new(cookies)))
+            .map_err(PyValueError::new_err)?;
+        Ok(AsyncClient { client: Mutex::new(Some(client)), session })
+    }
+
+    /// Sends a request through the client, like `Client.request` does, and returns an
+    /// awaitable that resolves to the `Response`. As there, `timeout=None` waits forever even
+    /// if the client has a timeout.
+    ///
+    /// # Raises
+    /// * `ValueError` - If the client is closed, or for the same reasons as `Client.request`
+    #[pyo3(signature = (method, url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn request<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self
+            .client
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| PyValueError::new_err("Client is closed"))?;
+        let spec = self.session.spec(method, url, headers, params, json, data, timeout, retry)?;
+        send(py, client, spec)
+    }
+
+    /// Sends a GET request; the same as `request("GET", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, timeout = Timeout::Unset, retry = None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.request(py, "GET", url, headers, params, None, None, timeout, retry)
+    }
+
+    /// Sends a POST request; the same as `request("POST", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn post<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.request(py, "POST", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a PUT request; the same as `request("PUT", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn put<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.request(py, "PUT", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a PATCH request; the same as `request("PATCH", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn patch<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.request(py, "PATCH", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a DELETE request; the same as `request("DELETE", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn delete<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.request(py, "DELETE", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a HEAD request; the same as `request("HEAD", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, timeout = Timeout::Unset, retry = None))]
+    fn head<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.request(py, "HEAD", url, headers, params, None, None, timeout, retry)
+    }
+
+    /// Closes the client's connections. Requests made afterwards raise `ValueError`;
+    /// requests still in flight complete normally.
+    ///
+    /// Returns an awaitable, for symmetry with the other methods; the client is closed
+    /// as soon as this is called.
+    fn aclose<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.client.lock().unwrap().take();
+        future_into_py(py, async { Ok(()) })
+    }
+
+    /// Whether `aclose()` has been called.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.client.lock().unwrap().is_none()
+    }
+
+    fn __aenter__<'py>(slf: Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let client = slf.clone().unbind();
+        future_into_py(slf.py(), async move { Ok(client) })
+    }
+
+    fn __aexit__<'py>(
+        &self,
+        py: Python<'py>,
+        _exc_type: Option<Bound<'py, PyAny>>,
+        _exc_value: Option<Bound<'py, PyAny>>,
+        _traceback: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.client.lock().unwrap().take();
+        future_into_py(py, async { Ok(false) })
+    }
+
+    fn __repr__(&self) -> String {
+        self.session.repr("AsyncClient")
+    }
+}
+
+/// The connection pool shared by `arequest`, `aget` and `get_many`.
+pub(super) fn shared_client() -> PyResult<reqwest::Client> {
+    SHARED_CLIENT
+        .get_or_try_init(|| build_client(reqwest::Client::builder()))
+        .cloned()
+        .map_err(PyValueError::new_err)
+}
+
+fn build_client(builder: reqwest::ClientBuilder) -> Result<reqwest::Client, String> {
+    builder.build().map_err(|e| format!("Can't build client: {}", e))
+}
+
+/// Starts `spec` on the shared runtime and returns the asyncio future for its `Response`.
+fn send(py: Python<'_>, client: reqwest::Client, spec: RequestSpec) -> PyResult<Bound<'_, PyAny>> {
+    future_into_py(py, async move {
+        let data = match spec.send_async(&client).await {
+            Ok(data) => data,
+            Err(failure) => return Err(Python::with_gil(|py| failure.into_pyerr(py))),
+        };
+        Python::with_gil(|py| Py::new(py, Response::new(py, data)?))
+    })
+}
@@ -0,0 +1,117 @@
+//! `get_many`, which fetches a batch of URLs concurrently.
+
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use tokio::task::JoinHandle;
+
+use super::asyncio::shared_client;
+use super::{RequestSpec, Response, Retry, DEFAULT_TIMEOUT};
+
+/// How often a running batch stops waiting to let Python handle signals such as Ctrl+C.
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Fetches many URLs concurrently with GET requests and returns the results in input order.
+///
+/// The requests run on the extension's tokio runtime with the GIL released, at most
+/// `concurrency` at a time. A request that fails does not stop the others: its place in the
+/// result holds the `RequestError` it would have raised instead of a `Response`. As with
+/// `request`, a response with an error status is still a `Response`.
+///
+/// # Arguments
+/// * `urls` - An iterable of URLs
+/// * `concurrency` - The maximum number of requests in flight at once
+/// * `timeout` - The timeout for each request, in seconds, or `None` to wait forever
+/// * `headers` - Headers sent with every request
+/// * `retry` - A `Retry` policy applied to each request
+///
+/// # Returns
+/// * A list with a `Response` or a `RequestError` for each URL, in the order of `urls`
+///
+/// # Raises
+/// * `ValueError` - If `concurrency` is 0 or `timeout` is not positive
+/// * `KeyboardInterrupt` - If interrupted; requests still running are cancelled
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// urls = [f"https://httpbin.org/status/{code}" for code in (200, 404)] + ["http://nonexistent.invalid/"]
+/// for url, result in zip(urls, fastpy_rs.http.get_many(urls, concurrency=8, timeout=5)):
+///     if isinstance(result, Exception):
+///         print(url, "failed:", result)
+///     else:
+///         print(url, result.status)
+/// # Output:
+/// # https://httpbin.org/status/200 200
+/// # https://httpbin.org/status/404 404
+/// # http://nonexistent.invalid/ failed: Request failed: ...
+/// ```
+#[pyfunction]
+#[pyo3(signature = (urls, *, concurrency = 32, timeout = Some(DEFAULT_TIMEOUT), headers = None, retry = None))]
+pub fn get_many<'py>(
+    py: Python<'py>,
+    urls: Bound<'py, PyAny>,
+    concurrency: usize,
+    timeout: Option<f64>,
+    headers: Option<Bound<'py, PyDict>>,
+    retry: Option<Retry>,
+) -> PyResult<Bound<'py, PyList>> {
+    if concurrency == 0 {
+        return Err(PyValueError::new_err("concurrency must be at least 1"));
+    }
+    let template = RequestSpec { retry, ..RequestSpec::new("GET", String::new(), headers, None, None, None, timeout)? };
+    let specs = urls
+        .try_iter()?
+        .map(|url| Ok(RequestSpec { url: url?.extract()?, ..template.clone() }))
+        .collect::<PyResult<Vec<_>>>()?;
+    let client = shared_client()?;
+
+    let count = specs.len();
+    let task = pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
+        let mut results: Vec<Option<Result<_, _>>> = (0..count).map(|_| None).collect();
+        let mut responses = stream::iter(specs.into_iter().enumerate())
+            .map(|(index, spec)| {
+                let client = client.clone();
+                async move { (index, spec.send_async(&client).await) }
+            })
+            .buffer_unordered(concurrency);
+        while let Some((index, result)) = responses.next().await {
+            results[index] = Some(result);
+        }
+        results
+    });
+
+    let results = PyList::empty(py);
+    for result in wait(py, task)?.into_iter().flatten() {
+        match result {
+            Ok(data) => results.append(Py::new(py, Response::new(py, data)?)?)?,
+            Err(failure) => results.append(failure.into_pyerr(py).into_value(py))?,
+        }
+    }
+    Ok(results)
+}
+
+/// Blocks until `task` finishes, with the GIL released, checking for signals now and then.
+///
+/// If a signal handler raises, the task is cancelled and the exception is returned.
+fn wait<T: Send + 'static>(py: Python<'_>, mut task: JoinHandle<T>) -> PyResult<T> {
+    let runtime = pyo3_async_runtimes::tokio::get_runtime();
+    loop {
+        let finished = py.allow_threads(|| {
+            runtime.block_on(async { tokio::time::timeout(SIGNAL_CHECK_INTERVAL, &mut task).await })
+        });
+        match finished {
+            Ok(result) => return result.map_err(|e| PyRuntimeError::new_err(format!("Batch failed: {}", e))),
+            Err(_) => {
+                if let Err(err) = py.check_signals() {
+                    task.abort();
+                    return Err(err);
+                }
+            }
+        }
+    }
+}
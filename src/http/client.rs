@@ -0,0 +1,381 @@
+//! `Client`, a session that keeps connections, default settings and cookies between requests.
+
+use std::sync::{Arc, Mutex};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+
+use super::{build_client, client_builder, pairs, seconds, RequestSpec, Response, Retry, DEFAULT_TIMEOUT};
+
+/// A reusable HTTP session.
+///
+/// The client keeps a pool of open connections, so consecutive requests to the same host
+/// skip the TCP and TLS handshakes. Cookies set by responses are stored and sent back on
+/// later requests. A client can be shared between threads; requests run with the GIL
+/// released and do not block each other.
+///
+/// Use it as a context manager, or call `close()`, to release the connections when done.
+///
+/// # Arguments
+/// * `base_url` - A URL that relative request URLs are appended to
+/// * `headers` - Headers sent with every request; headers given to a request take precedence
+/// * `timeout` - The default timeout for each request, in seconds, or `None` to wait forever
+/// * `cookies` - Cookies sent with every request to the host of `base_url`, or to every host
+///   when there is no `base_url`, as a dict of names to values
+/// * `retry` - A `Retry` policy applied to every request; a request's own `retry` takes precedence
+///
+/// # Raises
+/// * `ValueError` - If `base_url` is not a valid URL or `timeout` is not positive
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// with fastpy_rs.http.Client(base_url="https://api.example.com/v1", headers={"Authorization": "Bearer token"}) as client:
+///     users = client.get("users", params={"page": 1}).json()
+///     client.post("users", json={"name": "Alice"}).raise_for_status()
+/// ```
+#[pyclass(module = "fastpy_rs.http", frozen)]
+pub struct Client {
+    /// The connection pool, or `None` once the client is closed.
+    client: Mutex<Option<reqwest::blocking::Client>>,
+    session: Session,
+}
+
+#[pymethods]
+impl Client {
+    #[new]
+    #[pyo3(signature = (*, base_url = None, headers = None, timeout = Some(DEFAULT_TIMEOUT), cookies = None, retry = None))]
+    fn new<'py>(
+        py: Python<'py>,
+        base_url: Option<&str>,
+        headers: Option<Bound<'py, PyDict>>,
+        timeout: Option<f64>,
+        cookies: Option<Bound<'py, PyDict>>,
+        retry: Option<Retry>,
+    ) -> PyResult<Self> {
+        let (session, cookies) = Session::new(base_url, headers, timeout, cookies, retry)?;
+        let client = py
+            .allow_threads(|| build_client(client_builder().cookie_provider(Arc::new(cookies))))
+            .map_err(PyValueError::new_err)?;
+        Ok(Client { client: Mutex::new(Some(client)), session })
+    }
+
+    /// Sends a request through the client, like `fastpy_rs.http.request` does.
+    ///
+    /// `url` may be relative to `base_url`. `timeout` and `retry` override the client's
+    /// settings when given, so `timeout=None` waits forever even if the client has a timeout;
+    /// the other arguments are the same as for `fastpy_rs.http.request`.
+    ///
+    /// # Raises
+    /// * `ValueError` - If the client is closed, or for the same reasons as `fastpy_rs.http.request`
+    /// * `RequestError` - If the request fails without a response
+    #[pyo3(signature = (method, url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn request<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        let client = self
+            .client
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| PyValueError::new_err("Client is closed"))?;
+        let spec = self.session.spec(method, url, headers, params, json, data, timeout, retry)?;
+        let data = py.allow_threads(|| spec.send(&client)).map_err(|failure| failure.into_pyerr(py))?;
+        Response::new(py, data)
+    }
+
+    /// Sends a GET request; the same as `request("GET", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, timeout = Timeout::Unset, retry = None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        self.request(py, "GET", url, headers, params, None, None, timeout, retry)
+    }
+
+    /// Sends a POST request; the same as `request("POST", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn post<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        self.request(py, "POST", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a PUT request; the same as `request("PUT", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn put<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        self.request(py, "PUT", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a PATCH request; the same as `request("PATCH", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn patch<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        self.request(py, "PATCH", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a DELETE request; the same as `request("DELETE", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, json = None, data = None, timeout = Timeout::Unset, retry = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn delete<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        self.request(py, "DELETE", url, headers, params, json, data, timeout, retry)
+    }
+
+    /// Sends a HEAD request; the same as `request("HEAD", url, ...)`.
+    #[pyo3(signature = (url, *, headers = None, params = None, timeout = Timeout::Unset, retry = None))]
+    fn head<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<Response> {
+        self.request(py, "HEAD", url, headers, params, None, None, timeout, retry)
+    }
+
+    /// Closes the client's connections. Requests made afterwards raise `ValueError`.
+    ///
+    /// Closing a client that is already closed does nothing.
+    fn close(&self, py: Python<'_>) {
+        let client = self.client.lock().unwrap().take();
+        // Dropping the last handle joins the client's worker thread.
+        py.allow_threads(|| drop(client));
+    }
+
+    /// Whether `close()` has been called.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.client.lock().unwrap().is_none()
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: Option<Bound<'_, PyAny>>,
+        _exc_value: Option<Bound<'_, PyAny>>,
+        _traceback: Option<Bound<'_, PyAny>>,
+    ) -> bool {
+        self.close(py);
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        self.session.repr("Client")
+    }
+}
+
+/// The settings a client applies to each of its requests.
+pub(super) struct Session {
+    base_url: Option<Url>,
+    headers: Vec<(String, String)>,
+    timeout: Option<f64>,
+    retry: Option<Retry>,
+}
+
+impl Session {
+    /// Checks the client constructor's arguments; the cookies go to the client's builder.
+    pub(super) fn new<'py>(
+        base_url: Option<&str>,
+        headers: Option<Bound<'py, PyDict>>,
+        timeout: Option<f64>,
+        cookies: Option<Bound<'py, PyDict>>,
+        retry: Option<Retry>,
+    ) -> PyResult<(Self, Cookies)> {
+        let base_url = base_url
+            .map(|url| Url::parse(url).map_err(|e| PyValueError::new_err(format!("Invalid base_url {:?}: {}", url, e))))
+            .transpose()?;
+        let headers = match headers {
+            Some(headers) => pairs(&headers, "headers")?,
+            None => Vec::new(),
+        };
+        timeout.map(seconds).transpose()?;
+        let cookies = Cookies {
+            host: base_url.as_ref().and_then(|url| url.host_str()).map(str::to_ascii_lowercase),
+            fixed: match cookies {
+                Some(cookies) => pairs(&cookies, "cookies")?,
+                None => Vec::new(),
+            },
+            jar: Jar::default(),
+        };
+        Ok((Session { base_url, headers, timeout, retry }, cookies))
+    }
+
+    /// The request for a client method call, with the client's defaults filled in.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn spec<'py>(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Option<Bound<'py, PyDict>>,
+        params: Option<Bound<'py, PyAny>>,
+        json: Option<Bound<'py, PyAny>>,
+        data: Option<Bound<'py, PyAny>>,
+        timeout: Timeout,
+        retry: Option<Retry>,
+    ) -> PyResult<RequestSpec> {
+        let timeout = match timeout {
+            Timeout::Unset => self.timeout,
+            Timeout::Never => None,
+            Timeout::Seconds(timeout) => Some(timeout),
+        };
+        let mut spec = RequestSpec::new(method, self.resolve(url), headers, params, json, data, timeout)?;
+        spec.headers = merge_headers(&self.headers, spec.headers);
+        spec.retry = retry.or_else(|| self.retry.clone());
+        Ok(spec)
+    }
+
+    /// Resolves `url` against `base_url`.
+    ///
+    /// An absolute URL is used as it is. A relative one is appended to the base URL's path,
+    /// so `"users"` and `"/users"` both resolve to `https://host/v1/users` for a base URL of
+    /// `https://host/v1`.
+    fn resolve(&self, url: &str) -> String {
+        let Some(base) = &self.base_url else {
+            return url.to_string();
+        };
+        if Url::parse(url).is_ok() {
+            return url.to_string();
+        }
+        let base = base.as_str();
+        let base = base.strip_suffix('/').unwrap_or(base);
+        format!("{}/{}", base, url.trim_start_matches('/'))
+    }
+
+    pub(super) fn repr(&self, class: &str) -> String {
+        match &self.base_url {
+            Some(url) => format!("<{} base_url={:?}>", class, url.as_str()),
+            None => format!("<{}>", class),
+        }
+    }
+}
+
+/// The `timeout` of a client method, which tells leaving it out apart from passing `None`.
+#[derive(Clone, Copy)]
+pub(super) enum Timeout {
+    /// Not given: the client's timeout applies.
+    Unset,
+    /// `None`: wait forever.
+    Never,
+    Seconds(f64),
+}
+
+impl<'py> FromPyObject<'py> for Timeout {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if obj.is_none() {
+            return Ok(Timeout::Never);
+        }
+        Ok(Timeout::Seconds(obj.extract()?))
+    }
+}
+
+/// The client's default headers followed by the request's, leaving out defaults that the
+/// request sets itself.
+fn merge_headers(defaults: &[(String, String)], headers: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut merged: Vec<_> = defaults
+        .iter()
+        .filter(|(name, _)| !headers.iter().any(|(other, _)| other.eq_ignore_ascii_case(name)))
+        .cloned()
+        .collect();
+    merged.extend(headers);
+    merged
+}
+
+/// The cookies passed to the constructor, which go to the host of the base URL (or to
+/// every host without one), plus whatever the responses set.
+pub(super) struct Cookies {
+    host: Option<String>,
+    fixed: Vec<(String, String)>,
+    jar: Jar,
+}
+
+impl CookieStore for Cookies {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        self.jar.set_cookies(cookie_headers, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let received = self.jar.cookies(url);
+        let received = received.as_ref().and_then(|value| value.to_str().ok()).unwrap_or("");
+        let mut cookies: Vec<String> = received.split("; ").filter(|c| !c.is_empty()).map(String::from).collect();
+        let fixed = match &self.host {
+            Some(host) => url.host_str().is_some_and(|other| other.eq_ignore_ascii_case(host)),
+            None => true,
+        };
+        for (name, value) in self.fixed.iter().filter(|_| fixed) {
+            let set = cookies.iter().any(|c| c.split_once('=').is_some_and(|(n, _)| n == name));
+            if !set {
+                cookies.push(format!("{}={}", name, value));
+            }
+        }
+        if cookies.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&cookies.join("; ")).ok()
+    }
+}
@@ -0,0 +1,156 @@
+//! The `Response` returned by `request` and its shortcuts.
+
+use pyo3::exceptions::{PyLookupError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList, PyString};
+use reqwest::StatusCode;
+
+use super::retry::{attempt_list, Attempt, AttemptError};
+use crate::json;
+
+/// Everything read from a response, collected without the GIL.
+pub(super) struct ResponseData {
+    pub(super) status: u16,
+    pub(super) url: String,
+    pub(super) headers: Vec<(String, String)>,
+    pub(super) content: Vec<u8>,
+    pub(super) attempts: Vec<Attempt>,
+}
+
+impl ResponseData {
+    pub(super) fn read(resp: reqwest::blocking::Response) -> Result<Self, AttemptError> {
+        let status = resp.status().as_u16();
+        let url = resp.url().to_string();
+        let headers = header_pairs(resp.headers());
+        let content = resp.bytes().map_err(|e| AttemptError::new("Failed to read response body", e))?.to_vec();
+        Ok(ResponseData { status, url, headers, content, attempts: Vec::new() })
+    }
+
+    pub(super) async fn read_async(resp: reqwest::Response) -> Result<Self, AttemptError> {
+        let status = resp.status().as_u16();
+        let url = resp.url().to_string();
+        let headers = header_pairs(resp.headers());
+        let content = resp.bytes().await.map_err(|e| AttemptError::new("Failed to read response body", e))?.to_vec();
+        Ok(ResponseData { status, url, headers, content, attempts: Vec::new() })
+    }
+}
+
+/// The headers as name/value pairs, with values that are not valid UTF-8 decoded lossily.
+pub(super) fn header_pairs(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect()
+}
+
+/// An HTTP response.
+///
+/// `headers` is a dict with lower-case names; a header sent several times has its values
+/// joined with `", "`. The body is read completely before the response is returned.
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// response = fastpy_rs.http.post("https://httpbin.org/post", json={"id": 7})
+/// print(response.status)                   # Output: 200
+/// print(response.headers["content-type"])  # Output: application/json
+/// print(response.json()["json"])           # Output: {'id': 7}
+/// ```
+#[pyclass(module = "fastpy_rs.http", frozen)]
+pub struct Response {
+    /// The status code, such as `200`.
+    #[pyo3(get)]
+    status: u16,
+    /// The final URL, after any redirects.
+    #[pyo3(get)]
+    url: String,
+    /// The response headers, with lower-case names.
+    #[pyo3(get)]
+    headers: Py<PyDict>,
+    /// The body as `bytes`.
+    #[pyo3(get)]
+    content: Py<PyBytes>,
+    /// An `Attempt` for every time the request was sent; more than one if it was retried.
+    #[pyo3(get)]
+    attempts: Py<PyList>,
+}
+
+impl Response {
+    pub(super) fn new(py: Python<'_>, data: ResponseData) -> PyResult<Self> {
+        let headers = PyDict::new(py);
+        for (name, value) in &data.headers {
+            match headers.get_item(name)? {
+                Some(previous) => headers.set_item(name, format!("{}, {}", previous, value))?,
+                None => headers.set_item(name, value)?,
+            }
+        }
+        Ok(Response {
+            status: data.status,
+            url: data.url,
+            headers: headers.unbind(),
+            content: PyBytes::new(py, &data.content).unbind(),
+            attempts: attempt_list(py, data.attempts)?.unbind(),
+        })
+    }
+
+    /// The charset named by the `Content-Type` header, if any.
+    fn charset(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        let Some(content_type) = self.headers.bind(py).get_item("content-type")? else {
+            return Ok(None);
+        };
+        let content_type = content_type.extract::<String>()?;
+        Ok(content_type.split(';').skip(1).find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("charset")
+                .then(|| value.trim().trim_matches('"').to_string())
+        }))
+    }
+}
+
+#[pymethods]
+impl Response {
+    /// The body decoded as text, using the charset from `Content-Type` or UTF-8.
+    ///
+    /// Bytes that are invalid in that encoding are replaced with U+FFFD.
+    #[getter]
+    pub(super) fn text<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let content = self.content.bind(py);
+        if let Some(charset) = self.charset(py)? {
+            match content.call_method1("decode", (charset, "replace")) {
+                Err(err) if err.is_instance_of::<PyLookupError>(py) => {}
+                result => return result,
+            }
+        }
+        Ok(PyString::from_object(content, "utf-8", "replace")?.into_any())
+    }
+
+    /// Parses the body as JSON, with the same parser as `fastpy_rs.json.parse_json`.
+    ///
+    /// # Raises
+    /// * `JSONDecodeError` - If the body is not valid JSON
+    fn json(&self, py: Python<'_>) -> PyResult<PyObject> {
+        json::parse_json(py, self.content.bind(py))
+    }
+
+    /// Raises `ValueError` if the status is a client or server error (400 and above).
+    fn raise_for_status(&self) -> PyResult<()> {
+        if self.status < 400 {
+            return Ok(());
+        }
+        Err(PyValueError::new_err(format!("Status code: {}", status_line(self.status))))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Response [{}]>", self.status)
+    }
+}
+
+/// `404 Not Found` for 404, or just the number for codes without a known reason.
+pub(super) fn status_line(status: u16) -> String {
+    match StatusCode::from_u16(status) {
+        Ok(code) => code.to_string(),
+        Err(_) => status.to_string(),
+    }
+}
@@ -0,0 +1,290 @@
+//! Retrying failed requests: the `Retry` policy, the `Attempt` history and `RequestError`.
+
+use std::time::{Duration, SystemTime};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyDict, PyList, PyType};
+use reqwest::Method;
+
+use super::response::ResponseData;
+
+/// Status codes retried by default: rate limiting and transient server errors.
+const DEFAULT_STATUSES: [u16; 5] = [429, 500, 502, 503, 504];
+
+/// Methods retried by default: the ones RFC 9110 defines as idempotent.
+const DEFAULT_METHODS: [&str; 6] = ["GET", "HEAD", "OPTIONS", "PUT", "DELETE", "TRACE"];
+
+/// When and how often a failed request is sent again.
+///
+/// A request is retried when it fails to connect, times out or is cut off, or when the
+/// response status is one of `statuses`, as long as its method is one of `methods` and fewer
+/// than `max_attempts` attempts have been made. Errors that would happen again, such as an
+/// invalid URL or too many redirects, are raised at once. Before attempt `n + 1` the request waits
+/// `backoff * 2 ** (n - 1)` seconds, at most `max_backoff`; with `jitter` the wait is
+/// drawn at random from the upper half of that, so that clients failing together do not
+/// retry together.
+///
+/// A `Retry-After` header on a retried response, in seconds or as an HTTP date, replaces
+/// the computed wait. If it asks for longer than `max_backoff`, the response is returned
+/// without retrying.
+///
+/// When the attempts run out, the last response is returned, or the last error raised.
+/// Either way `attempts` on it lists what happened.
+///
+/// # Arguments
+/// * `max_attempts` - The total number of attempts, including the first one
+/// * `backoff` - The wait before the first retry, in seconds; it doubles for every retry after that
+/// * `max_backoff` - The longest wait between attempts, in seconds
+/// * `jitter` - Randomize each wait between half and all of its computed length
+/// * `statuses` - Response status codes that are retried
+/// * `methods` - HTTP methods that are retried; others are sent once
+/// * `respect_retry_after` - Wait as long as the `Retry-After` header asks
+///
+/// # Raises
+/// * `ValueError` - If `max_attempts` is 0, a wait is negative or a method is invalid
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// retry = fastpy_rs.http.Retry(max_attempts=5, backoff=0.2, statuses=[429, 503])
+/// response = fastpy_rs.http.request("GET", "https://httpbin.org/status/503", retry=retry)
+/// print(response.status)         # Output: 503
+/// print(len(response.attempts))  # Output: 5
+///
+/// with fastpy_rs.http.Client(base_url="https://api.example.com", retry=fastpy_rs.http.Retry()) as client:
+///     client.get("/flaky")
+/// ```
+#[pyclass(module = "fastpy_rs.http", frozen)]
+#[derive(Clone)]
+pub struct Retry {
+    /// The total number of attempts, including the first one.
+    #[pyo3(get)]
+    max_attempts: u32,
+    /// The wait before the first retry, in seconds.
+    #[pyo3(get)]
+    backoff: f64,
+    /// The longest wait between attempts, in seconds.
+    #[pyo3(get)]
+    max_backoff: f64,
+    /// Whether waits are randomized.
+    #[pyo3(get)]
+    jitter: bool,
+    /// Response status codes that are retried.
+    #[pyo3(get)]
+    statuses: Vec<u16>,
+    methods: Vec<Method>,
+    /// Whether `Retry-After` headers are honored.
+    #[pyo3(get)]
+    respect_retry_after: bool,
+}
+
+#[pymethods]
+impl Retry {
+    #[new]
+    #[pyo3(signature = (max_attempts = 3, *, backoff = 0.5, max_backoff = 30.0, jitter = true, statuses = None, methods = None, respect_retry_after = true))]
+    fn new(
+        max_attempts: u32,
+        backoff: f64,
+        max_backoff: f64,
+        jitter: bool,
+        statuses: Option<Vec<u16>>,
+        methods: Option<Vec<String>>,
+        respect_retry_after: bool,
+    ) -> PyResult<Self> {
+        if max_attempts == 0 {
+            return Err(PyValueError::new_err("max_attempts must be at least 1"));
+        }
+        for (name, value) in [("backoff", backoff), ("max_backoff", max_backoff)] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(PyValueError::new_err(format!("{} must be a non-negative number of seconds, got {}", name, value)));
+            }
+        }
+        let methods = match methods {
+            Some(methods) => methods
+                .iter()
+                .map(|method| {
+                    Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                        .map_err(|_| PyValueError::new_err(format!("Invalid HTTP method: {:?}", method)))
+                })
+                .collect::<PyResult<_>>()?,
+            None => DEFAULT_METHODS.iter().map(|method| Method::from_bytes(method.as_bytes()).unwrap()).collect(),
+        };
+        Ok(Retry {
+            max_attempts,
+            backoff,
+            max_backoff,
+            jitter,
+            statuses: statuses.unwrap_or_else(|| DEFAULT_STATUSES.to_vec()),
+            methods,
+            respect_retry_after,
+        })
+    }
+
+    /// HTTP methods that are retried.
+    #[getter]
+    fn methods(&self) -> Vec<&str> {
+        self.methods.iter().map(Method::as_str).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Retry(max_attempts={}, backoff={:?}, max_backoff={:?}, jitter={}, statuses={:?}, methods={:?}, respect_retry_after={})",
+            self.max_attempts,
+            self.backoff,
+            self.max_backoff,
+            if self.jitter { "True" } else { "False" },
+            self.statuses,
+            self.methods(),
+            if self.respect_retry_after { "True" } else { "False" },
+        )
+    }
+}
+
+impl Retry {
+    /// How long to wait before sending `method` again after attempt number `attempt`
+    /// ended with `outcome`, or `None` if it should not be sent again.
+    pub(super) fn delay(&self, method: &Method, attempt: u32, outcome: &Result<ResponseData, AttemptError>) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.methods.contains(method) {
+            return None;
+        }
+        match outcome {
+            Ok(data) => {
+                if !self.statuses.contains(&data.status) {
+                    return None;
+                }
+                if let Some(wait) = self.respect_retry_after.then(|| retry_after(data)).flatten() {
+                    return (wait.as_secs_f64() <= self.max_backoff).then_some(wait);
+                }
+            }
+            Err(err) if !err.transient => return None,
+            Err(_) => {}
+        }
+        let exponent = attempt.saturating_sub(1).min(64) as i32;
+        let mut wait = (self.backoff * 2f64.powi(exponent)).min(self.max_backoff);
+        if self.jitter {
+            wait *= 0.5 + fastrand::f64() * 0.5;
+        }
+        Some(Duration::from_secs_f64(wait))
+    }
+}
+
+/// The wait a `Retry-After` header asks for, given as seconds or as an HTTP date.
+fn retry_after(data: &ResponseData) -> Option<Duration> {
+    let (_, value) = data.headers.iter().find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))?;
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+/// Why an attempt ended without a response.
+pub(super) struct AttemptError {
+    pub(super) message: String,
+    /// Whether the next attempt could succeed: the connection failed, timed out or broke off.
+    /// Builder, redirect and decode errors would only happen again.
+    transient: bool,
+}
+
+impl AttemptError {
+    /// The error `err`, described as `context` (such as `"Request failed"`).
+    pub(super) fn new(context: &str, err: reqwest::Error) -> Self {
+        let transient = err.is_connect() || err.is_timeout() || err.is_request() || err.is_body();
+        AttemptError { message: format!("{}: {}", context, err), transient }
+    }
+}
+
+/// One attempt at sending a request, as listed in `Response.attempts` and `RequestError.attempts`.
+#[pyclass(module = "fastpy_rs.http", frozen)]
+#[derive(Clone)]
+pub struct Attempt {
+    /// The response status, or `None` if the attempt failed without a response.
+    #[pyo3(get)]
+    status: Option<u16>,
+    /// Why the attempt failed without a response, or `None` if there was one.
+    #[pyo3(get)]
+    error: Option<String>,
+    /// How long the attempt took, in seconds.
+    #[pyo3(get)]
+    elapsed: f64,
+    /// How long the request waited after this attempt before the next one, in seconds; 0 for the last attempt.
+    #[pyo3(get)]
+    delay: f64,
+}
+
+#[pymethods]
+impl Attempt {
+    fn __repr__(&self) -> String {
+        match (&self.status, &self.error) {
+            (Some(status), _) => format!("<Attempt status={} elapsed={:.3}s delay={:.3}s>", status, self.elapsed, self.delay),
+            (None, error) => format!(
+                "<Attempt error={:?} elapsed={:.3}s delay={:.3}s>",
+                error.as_deref().unwrap_or_default(),
+                self.elapsed,
+                self.delay
+            ),
+        }
+    }
+}
+
+impl Attempt {
+    pub(super) fn new(outcome: &Result<ResponseData, AttemptError>, elapsed: Duration, delay: Duration) -> Self {
+        let (status, error) = match outcome {
+            Ok(data) => (Some(data.status), None),
+            Err(err) => (None, Some(err.message.clone())),
+        };
+        Attempt { status, error, elapsed: elapsed.as_secs_f64(), delay: delay.as_secs_f64() }
+    }
+}
+
+/// A request that failed on every attempt, with the history of those attempts.
+pub(super) struct Failure {
+    pub(super) message: String,
+    pub(super) attempts: Vec<Attempt>,
+}
+
+impl Failure {
+    /// The `RequestError` to raise for this failure.
+    pub(super) fn into_pyerr(self, py: Python<'_>) -> PyErr {
+        let exc = request_error_type(py).and_then(|class| {
+            let exc = class.call1((&self.message,))?;
+            exc.setattr("attempts", attempt_list(py, self.attempts)?)?;
+            Ok(exc)
+        });
+        match exc {
+            Ok(exc) => PyErr::from_value(exc),
+            Err(err) => err,
+        }
+    }
+}
+
+pub(super) fn attempt_list(py: Python<'_>, attempts: Vec<Attempt>) -> PyResult<Bound<'_, PyList>> {
+    PyList::new(py, attempts.into_iter().map(|attempt| Py::new(py, attempt)).collect::<PyResult<Vec<_>>>()?)
+}
+
+static REQUEST_ERROR: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
+/// Returns `fastpy_rs.http.RequestError`, creating it on first use.
+pub fn request_error_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    REQUEST_ERROR
+        .get_or_try_init(py, || {
+            let namespace = PyDict::new(py);
+            namespace.set_item("__module__", "fastpy_rs.http")?;
+            namespace.set_item(
+                "__doc__",
+                "Subclass of ValueError raised when a request fails without a response, such as\n\
+                 when it cannot connect or times out.\n\n\
+                 `attempts` lists an `Attempt` for every time the request was sent; there is more\n\
+                 than one when a `Retry` policy retried it.",
+            )?;
+            let class = py
+                .get_type::<PyType>()
+                .call1(("RequestError", (py.get_type::<PyValueError>(),), namespace))?;
+            Ok(class.downcast_into::<PyType>()?.unbind())
+        })
+        .map(|class| class.bind(py))
+}
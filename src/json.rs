@@ -1,22 +1,71 @@
-use pyo3::exceptions::PyValueError;
+mod canonical;
+mod decoder;
+mod files;
+mod infer;
+mod jmespath;
+mod json5;
+mod jsonl;
+mod jsonpath;
+mod lenient;
+mod normalize;
+mod patch;
+mod queries;
+mod schema;
+mod stream;
+mod typed;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io;
+use ahash::RandomState;
+use once_cell::sync::Lazy;
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyRecursionError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::IntoPyObjectExt;
-use serde_json::{Value};
-use serde_json::ser::{CompactFormatter, Serializer};
-use pyo3::types::{PyAnyMethods, PyDict, PyList, PyString};
+use serde_json::ser::{Formatter, Serializer};
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{
+    PyAnyMethods, PyBool, PyByteArray, PyBytes, PyDate, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PyMemoryView, PySet,
+    PyString, PyTime, PyTuple, PyType,
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::value::RawValue;
 use serde::ser::{Error, SerializeMap, SerializeSeq};
 use serde::ser::{Serialize, Serializer as SerTrait};
 
-/// Parses a JSON string into a Python dictionary.
+pub use canonical::canonicalize;
+use decoder::{DecodeError, DecodeHooks};
+pub use files::{dump_file, load_file};
+pub use infer::infer_schema;
+pub use json5::parse_json5;
+pub use jsonl::{dump_jsonl, parse_jsonl, JsonLinesReader};
+pub use lenient::{parse_lenient_llm, repair};
+pub use normalize::{flatten, to_rows, unflatten};
+pub use patch::{apply_patch, diff, merge_patch, pointer_get, pointer_set};
+pub use queries::{compile, query, Query};
+pub use schema::{validation_error_type, Validator};
+pub use stream::{iter_items, ItemsIterator};
+pub use typed::decode;
+
+/// Parses a JSON document into the corresponding Python object.
+///
+/// Any JSON value is accepted at the top level, so the result can be a `dict`, `list`,
+/// `str`, `int`, `float`, `bool` or `None`.
+///
+/// Ints of any size stay exact, and strings may hold lone surrogates just like with
+/// the stdlib. `NaN` and `Infinity` are not JSON and are rejected; use `loads` for those.
 ///
 /// # Arguments
-/// * `json_str` - A string containing valid JSON data
+/// * `json_str` - The JSON document as `str`, `bytes`, `bytearray` or `memoryview`
 ///
 /// # Returns
-/// * A Python dictionary representing the parsed JSON data
+/// * A Python object representing the parsed JSON data
 ///
 /// # Raises
-/// * `ValueError` - If the input string is not valid JSON or if the JSON is not an object at the top level
+/// * `JSONDecodeError` - If the input is not valid JSON; a subclass of both `ValueError` and
+///   the stdlib `json.JSONDecodeError`, carrying `msg`, `doc`, `pos`, `lineno` and `colno`
+/// * `RecursionError` - If the document is nested more than 1000 levels deep
+/// * `TypeError` - If the input is not a `str` or a bytes-like object
 ///
 /// # Examples
 /// ```python
@@ -30,71 +79,354 @@ use serde::ser::{Serialize, Serializer as SerTrait};
 /// # Parse JSON with nested structures
 /// nested = fastpy_rs.json.parse_json('{"users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}]}')
 /// print(nested['users'][0]['name'])  # Output: Alice
+///
+/// # Top-level arrays and scalars, straight from bytes
+/// print(fastpy_rs.json.parse_json(b'[1, 2, 3]'))  # Output: [1, 2, 3]
+/// print(fastpy_rs.json.parse_json(b'"hello"'))    # Output: hello
+///
+/// # Find out where a document is broken
+/// try:
+///     fastpy_rs.json.parse_json('{"a": 1,\n "b": }')
+/// except fastpy_rs.json.JSONDecodeError as e:
+///     print(e.lineno, e.colno, e.pos)  # Output: 2 7 15
 /// ```
 #[pyfunction]
-pub fn parse_json(py: Python, json_str: &str) -> PyResult<PyObject> {
-    let value: Value = serde_json::from_str(json_str)
-        .map_err(|e| PyValueError::new_err(format!("Invalid JSON: {}", e)))?;
-
-    fn value_to_pyobject(val: &Value, py: Python) -> PyResult<PyObject> {
-        match val {
-            Value::Null => Ok(py.None()),
-            Value::Bool(b) => Ok(b.into_py_any(py)?),
-            Value::String(s) => Ok(s.into_py_any(py)?),
-            Value::Array(arr) => {
-                let list = PyList::empty(py);
-                for elem in arr {
-                    list.append(value_to_pyobject(elem, py)?)?;
-                }
-                Ok(list.into_py_any(py)?)
-            }
-            Value::Object(map) => {
-                let dict = PyDict::new(py);
-                for (k, v) in map {
-                    dict.set_item(k, value_to_pyobject(v, py)?)?;
-                }
-                Ok(dict.into_py_any(py)?)
-            }
-            Value::Number(n) => {
-                if let Some(i) = n.as_i64() {
-                    Ok(i.into_py_any(py)?)
-                } else if let Some(u) = n.as_u64() {
-                    Ok(u.into_py_any(py)?)
-                } else if let Some(f) = n.as_f64() {
-                    Ok(f.into_py_any(py)?)
-                } else {
-                    Err(PyValueError::new_err("Number out of range"))
-                }
+pub fn parse_json(py: Python, json_str: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let value = with_json_bytes(json_str, |bytes| {
+        parse_slice(py, bytes).map_err(|e| parse_error(json_str, bytes, e))
+    })??;
+    Ok(value.unbind())
+}
+
+/// Deserializes a JSON document to a Python object, as a drop-in replacement for `json.loads`.
+///
+/// Both run the same decoder, but this one follows the stdlib to the letter: `NaN`,
+/// `Infinity` and `-Infinity` are accepted, errors carry the stdlib's messages and the
+/// hooks receive the same arguments they would from the stdlib decoder.
+///
+/// # Arguments
+/// * `s` - The JSON document as `str`, `bytes`, `bytearray` or `memoryview`
+/// * `cls` - Only `None` is supported; custom decoder classes are rejected
+/// * `object_hook` - Called with every decoded `dict`; its return value replaces the dict
+/// * `parse_float` - Called with the text of every JSON float (e.g. `decimal.Decimal`)
+/// * `parse_int` - Called with the text of every JSON int
+/// * `parse_constant` - Called with `"NaN"`, `"Infinity"` or `"-Infinity"`
+/// * `object_pairs_hook` - Called with the list of `(key, value)` pairs of every object; takes priority over `object_hook`
+///
+/// # Returns
+/// * The decoded Python object
+///
+/// # Raises
+/// * `JSONDecodeError` - If the input is not valid JSON, with the stdlib's messages and positions
+/// * `TypeError` - If `cls` is given or the input is not a `str` or a bytes-like object
+///
+/// # Examples
+/// ```python
+/// import decimal
+/// import fastpy_rs.json as json
+///
+/// data = json.loads('{"price": 1.10, "qty": 3}', parse_float=decimal.Decimal)
+/// print(data)  # Output: {'price': Decimal('1.10'), 'qty': 3}
+/// ```
+#[pyfunction]
+#[pyo3(signature = (s, *, cls = None, object_hook = None, parse_float = None, parse_int = None, parse_constant = None, object_pairs_hook = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn loads<'py>(
+    s: &Bound<'py, PyAny>,
+    cls: Option<Bound<'py, PyAny>>,
+    object_hook: Option<Bound<'py, PyAny>>,
+    parse_float: Option<Bound<'py, PyAny>>,
+    parse_int: Option<Bound<'py, PyAny>>,
+    parse_constant: Option<Bound<'py, PyAny>>,
+    object_pairs_hook: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    reject_cls(cls)?;
+    let hooks = DecodeHooks { object_hook, object_pairs_hook, parse_float, parse_int, parse_constant };
+    with_json_bytes(s, |bytes| {
+        if s.is_instance_of::<PyString>() {
+            if bytes.starts_with(UTF8_BOM) {
+                return Err(raise_decode_error(s, bytes, "Unexpected UTF-8 BOM (decode using utf-8-sig)", 0));
             }
+            return decoder::decode(s, bytes, &hooks);
         }
+        decoder::decode(s, bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes), &hooks)
+    })?
+}
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Deserializes a JSON document read from a file-like object, as a drop-in replacement for `json.load`.
+///
+/// # Arguments
+/// * `fp` - An object with a `read()` method returning `str` or `bytes`
+/// * Keyword arguments are the same as for `loads`
+///
+/// # Returns
+/// * The decoded Python object
+///
+/// # Raises
+/// * `JSONDecodeError` - If the content is not valid JSON
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// with open("config.json", "rb") as f:
+///     config = json.load(f)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (fp, *, cls = None, object_hook = None, parse_float = None, parse_int = None, parse_constant = None, object_pairs_hook = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn load<'py>(
+    fp: &Bound<'py, PyAny>,
+    cls: Option<Bound<'py, PyAny>>,
+    object_hook: Option<Bound<'py, PyAny>>,
+    parse_float: Option<Bound<'py, PyAny>>,
+    parse_int: Option<Bound<'py, PyAny>>,
+    parse_constant: Option<Bound<'py, PyAny>>,
+    object_pairs_hook: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let content = fp.call_method0("read")?;
+    loads(&content, cls, object_hook, parse_float, parse_int, parse_constant, object_pairs_hook)
+}
+
+fn reject_cls(cls: Option<Bound<'_, PyAny>>) -> PyResult<()> {
+    match cls {
+        Some(cls) if !cls.is_none() => Err(PyTypeError::new_err(
+            "fastpy_rs.json does not support custom cls; use the hook arguments instead",
+        )),
+        _ => Ok(()),
     }
+}
 
-    if let Value::Object(map) = value {
-        let dict = PyDict::new(py);
-        for (key, val) in map {
-            dict.set_item(key, value_to_pyobject(&val, py)?)?;
-        }
+static JSON_DECODE_ERROR: GILOnceCell<Py<PyType>> = GILOnceCell::new();
 
-        let any: PyObject = dict.into_py_any(py)?;
+/// Returns `fastpy_rs.json.JSONDecodeError`, creating it on first use.
+///
+/// The class is derived from the stdlib `json.JSONDecodeError` (itself a `ValueError`),
+/// so existing `except json.JSONDecodeError` handlers catch it unchanged.
+pub fn json_decode_error_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    JSON_DECODE_ERROR
+        .get_or_try_init(py, || {
+            let base = py.import("json")?.getattr("JSONDecodeError")?;
+            let namespace = PyDict::new(py);
+            namespace.set_item("__module__", "fastpy_rs.json")?;
+            namespace.set_item(
+                "__doc__",
+                "Subclass of ValueError and json.JSONDecodeError raised for invalid JSON documents.",
+            )?;
+            let class = py
+                .get_type::<PyType>()
+                .call1(("JSONDecodeError", (base,), namespace))?;
+            Ok(class.downcast_into::<PyType>()?.unbind())
+        })
+        .map(|class| class.bind(py))
+}
 
-        Ok(any)
+/// Builds a `JSONDecodeError` for a [`parse_slice`] failure while parsing `bytes`.
+fn parse_error(doc: &Bound<'_, PyAny>, bytes: &[u8], err: DecodeError) -> PyErr {
+    match parse_error_location(err) {
+        Ok((msg, offset)) => raise_decode_error(doc, bytes, &msg, offset),
+        Err(err) => err,
+    }
+}
+
+/// The message of a [`parse_slice`] syntax error and the byte offset it refers to, or
+/// the Python exception that stopped parsing.
+fn parse_error_location(err: DecodeError) -> PyResult<(String, usize)> {
+    match err {
+        DecodeError::Syntax(msg, offset) => Ok((format!("Invalid JSON: {msg}"), offset)),
+        DecodeError::Python(err) => Err(err),
+    }
+}
+
+/// Builds a `JSONDecodeError` for a serde_json failure while parsing `bytes`.
+fn decode_error(doc: &Bound<'_, PyAny>, bytes: &[u8], err: &serde_json::Error) -> PyErr {
+    let (msg, offset) = serde_error_location(bytes, err);
+    raise_decode_error(doc, bytes, &msg, offset)
+}
+
+/// The message of a serde_json failure and the byte offset into `bytes` it refers to.
+///
+/// serde_json reports a 1-based line and byte column; the stdlib expects a character
+/// offset into the document and derives `lineno`/`colno` from it.
+fn serde_error_location(bytes: &[u8], err: &serde_json::Error) -> (String, usize) {
+    let offset = if err.is_eof() {
+        bytes.len()
     } else {
-        Err(PyValueError::new_err(
-            "JSON must be an object at the top level",
-        ))
+        byte_offset(bytes, err.line(), err.column())
+    };
+    let message = err.to_string();
+    let location = format!(" at line {} column {}", err.line(), err.column());
+    let msg = format!("Invalid JSON: {}", message.strip_suffix(&location).unwrap_or(&message));
+    (msg, offset)
+}
+
+/// Builds a `JSONDecodeError` pointing at byte `offset` of the document `bytes`.
+fn raise_decode_error(doc: &Bound<'_, PyAny>, bytes: &[u8], msg: &str, offset: usize) -> PyErr {
+    let py = doc.py();
+    let doc = match doc.downcast::<PyString>() {
+        Ok(s) => s.clone(),
+        Err(_) => PyString::new(py, &String::from_utf8_lossy(bytes)),
+    };
+    let pos = char_count(&bytes[..offset.min(bytes.len())]);
+
+    match json_decode_error_type(py).and_then(|class| class.call1((msg, doc, pos))) {
+        Ok(exc) => PyErr::from_value(exc),
+        Err(e) => e,
+    }
+}
+
+/// Counts the code points in `bytes`, which may hold surrogates encoded like any other
+/// code point; every byte that is not a continuation byte starts one.
+fn char_count(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| b & 0xc0 != 0x80).count()
+}
+
+/// Builds a `JSONDecodeError` whose location is given explicitly instead of derived from `doc`,
+/// for input that is never held in memory as a whole.
+fn relocated_decode_error(
+    py: Python<'_>,
+    msg: &str,
+    doc: &str,
+    pos: usize,
+    lineno: usize,
+    colno: usize,
+) -> PyErr {
+    let exc = json_decode_error_type(py).and_then(|class| {
+        let exc = class.call1((msg, doc, pos))?;
+        exc.setattr("lineno", lineno)?;
+        exc.setattr("colno", colno)?;
+        exc.setattr("args", (format!("{msg}: line {lineno} column {colno}"),))?;
+        Ok(exc)
+    });
+    match exc {
+        Ok(exc) => PyErr::from_value(exc),
+        Err(e) => e,
+    }
+}
+
+fn byte_offset(bytes: &[u8], line: usize, column: usize) -> usize {
+    let line_start = bytes
+        .split_inclusive(|&b| b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(<[u8]>::len)
+        .sum::<usize>();
+    (line_start + column.saturating_sub(1)).min(bytes.len())
+}
+
+/// Parses a complete strict JSON document into Python objects.
+///
+/// This is the one parser behind `parse_json`, `load_file`, `parse_jsonl` and
+/// `parse_lenient_llm`; `loads` runs the same decoder with its hooks and `NaN` support.
+fn parse_slice<'py>(py: Python<'py>, bytes: &[u8]) -> Result<Bound<'py, PyAny>, DecodeError> {
+    decoder::parse(py, bytes)
+}
+
+/// Keys longer than this are not worth caching.
+const KEY_CACHE_MAX_LEN: usize = 64;
+const KEY_CACHE_SIZE: usize = 2048;
+
+type KeyCacheEntry = Option<(Box<str>, Py<PyString>)>;
+
+static KEY_HASHER: Lazy<RandomState> = Lazy::new(RandomState::new);
+
+thread_local! {
+    /// Direct-mapped cache of recently seen object keys.
+    ///
+    /// JSON documents repeat the same keys over and over, so reusing the Python
+    /// string saves both the UTF-8 decode and the allocation.
+    static KEY_CACHE: RefCell<Vec<KeyCacheEntry>> =
+        RefCell::new(std::iter::repeat_with(|| None).take(KEY_CACHE_SIZE).collect());
+}
+
+fn cached_key<'py>(py: Python<'py>, key: &str) -> Bound<'py, PyString> {
+    if key.len() > KEY_CACHE_MAX_LEN {
+        return PyString::new(py, key);
     }
+    let slot = (KEY_HASHER.hash_one(key) as usize) % KEY_CACHE_SIZE;
+    KEY_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        match &cache[slot] {
+            Some((cached, string)) if **cached == *key => string.bind(py).clone(),
+            _ => {
+                let string = PyString::new(py, key);
+                cache[slot] = Some((key.into(), string.clone().unbind()));
+                string
+            }
+        }
+    })
+}
+
+/// Runs `f` over the raw bytes of a JSON document passed from Python.
+///
+/// `str` is read as UTF-8, `bytes` is borrowed in place and `bytearray`, `memoryview`
+/// or any other object exporting the buffer protocol is borrowed through the buffer
+/// when contiguous and copied otherwise.
+///
+/// A `str` holding lone surrogates has no UTF-8 form, so it is encoded with
+/// `surrogatepass` instead and the parser keeps the surrogates, as the stdlib does.
+fn with_json_bytes<R>(obj: &Bound<'_, PyAny>, f: impl FnOnce(&[u8]) -> R) -> PyResult<R> {
+    if let Ok(s) = obj.downcast::<PyString>() {
+        return match s.to_str() {
+            Ok(text) => Ok(f(text.as_bytes())),
+            Err(_) => {
+                let encoded = s.call_method1("encode", ("utf-8", "surrogatepass"))?;
+                Ok(f(encoded.downcast::<PyBytes>()?.as_bytes()))
+            }
+        };
+    }
+    if let Ok(b) = obj.downcast::<PyBytes>() {
+        return Ok(f(b.as_bytes()));
+    }
+    if let Ok(buf) = PyBuffer::<u8>::get(obj) {
+        if buf.is_c_contiguous() {
+            // SAFETY: the buffer is contiguous and stays alive until `buf` is dropped. Holding
+            // the export also pins it: a `bytearray` refuses to resize while it is exported,
+            // even if building the parsed objects runs Python code.
+            let bytes = unsafe {
+                std::slice::from_raw_parts(buf.buf_ptr() as *const u8, buf.len_bytes())
+            };
+            return Ok(f(bytes));
+        }
+        return Ok(f(&buf.to_vec(obj.py())?));
+    }
+    Err(PyTypeError::new_err(format!(
+        "the JSON object must be str, bytes, bytearray or memoryview, not {}",
+        obj.get_type().name()?
+    )))
 }
 
 /// Serializes a Python object to a JSON string.
 ///
+/// The keyword arguments mirror `json.dumps`, so `indent=2, sort_keys=True, ensure_ascii=True`
+/// gives byte-for-byte the same text as the stdlib. Without any options the output stays compact.
+///
+/// Besides the JSON primitives, tuples and sets become arrays, `datetime`/`date`/`time` become
+/// ISO 8601 strings, `uuid.UUID` its canonical string, `Decimal` a number with all of its digits,
+/// `Enum` members their value, dataclasses objects of their fields and `bytes` base64 strings.
+/// Ints of any size are written exactly, and dict keys that are ints, floats, bools or `None`
+/// are converted to strings the same way `json.dumps` does.
+///
 /// # Arguments
-/// * `obj` - A Python object to serialize (dict, list, str, int, float, bool, None)
+/// * `obj` - A Python object to serialize (dict, list, str, int, float, bool, None and the types above)
+/// * `indent` - Pretty-print with this many spaces (or this string) per level; `None` keeps everything on one line
+/// * `sort_keys` - Write dictionary keys in sorted order
+/// * `ensure_ascii` - Escape every non-ASCII character as `\uXXXX`
+/// * `separators` - `(item_separator, key_separator)` tuple; defaults to `(",", ":")`, or `(",", ": ")` when `indent` is set
+/// * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+/// * `skipkeys` - Silently drop dict keys that are not str, int, float, bool or `None` instead of raising
+/// * `allow_nan` - Write `NaN`, `Infinity` and `-Infinity` for non-finite floats instead of raising
+/// * `check_circular` - Detect reference cycles and raise `ValueError` instead of `RecursionError`
 ///
 /// # Returns
 /// * A JSON string representation of the input object
 ///
 /// # Raises
-/// * `ValueError` - If the object contains types that cannot be serialized to JSON
+/// * `ValueError` - If the object contains types that cannot be serialized to JSON, or a reference cycle
+/// * `RecursionError` - If the object is nested too deeply
+/// * `TypeError` - If `indent` is neither an int, a str nor `None`
+/// * Any exception raised by `default`, unchanged
 ///
 /// # Examples
 /// ```python
@@ -106,96 +438,833 @@ pub fn parse_json(py: Python, json_str: &str) -> PyResult<PyObject> {
 /// print(json_str)  # Output: {"name":"John","age":30,"active":true}
 ///
 /// # Pretty-print the JSON
-/// pretty_json = fastpy_rs.json.serialize_json(data, pretty=True)
+/// pretty_json = fastpy_rs.json.serialize_json(data, indent=2, sort_keys=True)
 /// print(pretty_json)
 /// # Output:
 /// # {
-/// #   "name": "John",
+/// #   "active": true,
 /// #   "age": 30,
-/// #   "active": true
+/// #   "name": "John"
 /// # }
+///
+/// # Fall back to a custom conversion
+/// json_str = fastpy_rs.json.serialize_json({"tags": {"a"}, "obj": object()}, default=repr)
 /// ```
-/// Serializes a Python object (dict, list, str, int, float, bool, None)
-/// to a JSON string.
+#[pyfunction]
+#[pyo3(signature = (obj, *, indent = None, sort_keys = false, ensure_ascii = false, separators = None, default = None, skipkeys = false, allow_nan = false, check_circular = true))]
+#[allow(clippy::too_many_arguments)]
+pub fn serialize_json<'py>(
+    obj: Bound<'py, PyAny>,
+    indent: Option<Bound<'py, PyAny>>,
+    sort_keys: bool,
+    ensure_ascii: bool,
+    separators: Option<(String, String)>,
+    default: Option<Bound<'py, PyAny>>,
+    skipkeys: bool,
+    allow_nan: bool,
+    check_circular: bool,
+) -> PyResult<String> {
+    let indent = indent.map(|indent| indent_string(&indent)).transpose()?;
+    let separators = separators.unwrap_or_else(|| match indent {
+        Some(_) => (",".to_string(), ": ".to_string()),
+        None => (",".to_string(), ":".to_string()),
+    });
+    let opts = SerializeOptions::new(sort_keys, ensure_ascii, default, skipkeys, allow_nan, check_circular);
+    to_json(obj, indent.as_deref(), &separators, &opts)
+}
 
+/// Serializes a Python object to a JSON string, as a drop-in replacement for `json.dumps`.
+///
+/// Defaults are the stdlib's (`ensure_ascii=True`, `", "` and `": "` separators, `NaN` allowed),
+/// so the output is identical to `json.dumps`. Everything `serialize_json` accepts is
+/// supported here as well.
+///
+/// # Arguments
+/// * `obj` - A Python object to serialize
+/// * `skipkeys` - Silently drop dict keys that are not str, int, float, bool or `None` instead of raising
+/// * `ensure_ascii` - Escape every non-ASCII character as `\uXXXX`
+/// * `check_circular` - Detect reference cycles and raise `ValueError` instead of recursing until `RecursionError`
+/// * `allow_nan` - Write `NaN`, `Infinity` and `-Infinity` for non-finite floats instead of raising
+/// * `cls` - Only `None` is supported; custom encoder classes are rejected
+/// * `indent` - Pretty-print with this many spaces (or this string) per level
+/// * `separators` - `(item_separator, key_separator)` tuple; defaults to `(", ", ": ")`, or `(",", ": ")` when `indent` is set
+/// * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+/// * `sort_keys` - Write dictionary keys in sorted order
+///
+/// # Returns
+/// * A JSON string representation of the input object
+///
+/// # Raises
+/// * `ValueError` - For unserializable objects, circular references or non-finite floats with `allow_nan=False`
+/// * `RecursionError` - If the object is nested too deeply
+/// * `TypeError` - If `cls` is given or `indent` is neither an int, a str nor `None`
+/// * Any exception raised by `default`, unchanged
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// print(json.dumps({"b": [1, 2.5, None], "a": "é"}, sort_keys=True))
+/// # Output: {"a": "\u00e9", "b": [1, 2.5, null]}
+/// ```
 #[pyfunction]
-pub fn serialize_json(_py: Python<'_>, obj: Bound<'_, PyAny>) -> PyResult<String> {
-    // Буфер, который умеет `std::io::Write`.
+#[pyo3(signature = (obj, *, skipkeys = false, ensure_ascii = true, check_circular = true, allow_nan = true, cls = None, indent = None, separators = None, default = None, sort_keys = false))]
+#[allow(clippy::too_many_arguments)]
+pub fn dumps<'py>(
+    obj: Bound<'py, PyAny>,
+    skipkeys: bool,
+    ensure_ascii: bool,
+    check_circular: bool,
+    allow_nan: bool,
+    cls: Option<Bound<'py, PyAny>>,
+    indent: Option<Bound<'py, PyAny>>,
+    separators: Option<(String, String)>,
+    default: Option<Bound<'py, PyAny>>,
+    sort_keys: bool,
+) -> PyResult<String> {
+    reject_cls(cls)?;
+    let indent = indent.map(|indent| indent_string(&indent)).transpose()?;
+    let separators = separators.unwrap_or_else(|| match indent {
+        Some(_) => (",".to_string(), ": ".to_string()),
+        None => (", ".to_string(), ": ".to_string()),
+    });
+    let opts = SerializeOptions::new(sort_keys, ensure_ascii, default, skipkeys, allow_nan, check_circular);
+    to_json(obj, indent.as_deref(), &separators, &opts)
+}
+
+/// Serializes a Python object as JSON to a file-like object, as a drop-in replacement for `json.dump`.
+///
+/// # Arguments
+/// * `obj` - A Python object to serialize
+/// * `fp` - An object with a `write()` method accepting `str`
+/// * Keyword arguments are the same as for `dumps`
+///
+/// # Raises
+/// * The same exceptions as `dumps`, plus anything raised by `fp.write`
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// with open("config.json", "w") as f:
+///     json.dump({"debug": False}, f, indent=2)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (obj, fp, *, skipkeys = false, ensure_ascii = true, check_circular = true, allow_nan = true, cls = None, indent = None, separators = None, default = None, sort_keys = false))]
+#[allow(clippy::too_many_arguments)]
+pub fn dump<'py>(
+    obj: Bound<'py, PyAny>,
+    fp: &Bound<'py, PyAny>,
+    skipkeys: bool,
+    ensure_ascii: bool,
+    check_circular: bool,
+    allow_nan: bool,
+    cls: Option<Bound<'py, PyAny>>,
+    indent: Option<Bound<'py, PyAny>>,
+    separators: Option<(String, String)>,
+    default: Option<Bound<'py, PyAny>>,
+    sort_keys: bool,
+) -> PyResult<()> {
+    let text = dumps(
+        obj, skipkeys, ensure_ascii, check_circular, allow_nan, cls, indent, separators, default, sort_keys,
+    )?;
+    fp.call_method1("write", (text,))?;
+    Ok(())
+}
+
+/// Shared implementation of `serialize_json` and `dumps`.
+fn to_json<'py>(
+    obj: Bound<'py, PyAny>,
+    indent: Option<&str>,
+    separators: &(String, String),
+    opts: &SerializeOptions<'py>,
+) -> PyResult<String> {
     let mut buf = Vec::<u8>::with_capacity(256);
+    write_json(&mut buf, obj, indent, separators, opts)?;
 
-    {
-        // Компактный форматтер; можно поменять на PrettyFormatter по желанию.
-        let mut ser = Serializer::with_formatter(&mut buf, CompactFormatter {});
-        // Всё тяжёлое — без GIL.
+    // SAFETY: serde_json and PyFormatter only ever write valid UTF-8.
+    Ok(unsafe { String::from_utf8_unchecked(buf) })
+}
+
+/// Writes the JSON text of `obj` to `writer`.
+fn write_json<'py, W: io::Write>(
+    writer: W,
+    obj: Bound<'py, PyAny>,
+    indent: Option<&str>,
+    (item_separator, key_separator): &(String, String),
+    opts: &SerializeOptions<'py>,
+) -> PyResult<()> {
+    let formatter = PyFormatter {
+        indent: indent.map(str::as_bytes),
+        item_separator: item_separator.as_bytes(),
+        key_separator: key_separator.as_bytes(),
+        ensure_ascii: opts.ensure_ascii,
+        raw: &opts.raw,
+        depth: 0,
+        has_value: false,
+    };
+    let mut ser = Serializer::with_formatter(writer, formatter);
+    PyAnySerializer { inner: obj, opts, default_depth: 0 }
+        .serialize(&mut ser)
+        .map_err(|e| match opts.error.take() {
+            Some(err) => err,
+            None => PyValueError::new_err(e.to_string()),
+        })
+}
+
+/// Converts `obj` to a `serde_json::Value`, or parses it when it is JSON text
+/// (`str`, `bytes`, `bytearray` or `memoryview`) without creating any Python objects.
+fn to_value(obj: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    if is_json_text(obj) {
+        return with_json_bytes(obj, |bytes| {
+            serde_json::from_slice(bytes).map_err(|e| decode_error(obj, bytes, &e))
+        })?;
+    }
+    object_to_value(obj)
+}
+
+/// Whether `obj` is taken as JSON text (rather than as a parsed document) where both are accepted.
+fn is_json_text(obj: &Bound<'_, PyAny>) -> bool {
+    obj.is_instance_of::<PyString>()
+        || obj.is_instance_of::<PyBytes>()
+        || obj.is_instance_of::<PyByteArray>()
+        || obj.is_instance_of::<PyMemoryView>()
+}
+
+/// Converts `obj` to a `serde_json::Value` as `serialize_json` would see it, so `str` and
+/// `bytes` become strings rather than being parsed.
+fn object_to_value(obj: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    let opts = SerializeOptions::new(false, false, None, false, false, true);
+    serde_json::to_value(PyAnySerializer { inner: obj.clone(), opts: &opts, default_depth: 0 })
+        .map_err(|e| match opts.error.take() {
+            Some(err) => err,
+            None => PyValueError::new_err(e.to_string()),
+        })
+}
+
+/// Converts the `indent` argument the way `json.dumps` does: an int is a number of
+/// spaces (negative counts as zero) and a str is used verbatim.
+fn indent_string(indent: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(s) = indent.downcast::<PyString>() {
+        return Ok(s.to_str()?.to_string());
+    }
+    if let Ok(n) = indent.extract::<isize>() {
+        return Ok(" ".repeat(n.max(0) as usize));
+    }
+    Err(PyTypeError::new_err(format!(
+        "indent must be an int, a str or None, not {}",
+        indent.get_type().name()?
+    )))
+}
 
-        // py.allow_threads(|| PyAnySerializer { inner: obj }.serialize(&mut ser)).map_err(|e| PyValueError::new_err(format!("UTF-8 error: {e}")))?
-        PyAnySerializer { inner: obj }.serialize(&mut ser).map_err(|e| PyValueError::new_err(format!("UTF-8 error: {e}")))?
+/// Serialization settings that affect how objects are walked rather than how tokens are written.
+struct SerializeOptions<'py> {
+    sort_keys: bool,
+    ensure_ascii: bool,
+    default: Option<Bound<'py, PyAny>>,
+    skipkeys: bool,
+    allow_nan: bool,
+    check_circular: bool,
+    /// Sort keys by their UTF-16 code units, as RFC 8785 requires, rather than by code point.
+    utf16_key_order: bool,
+    /// Addresses of the containers currently being written, outermost first.
+    containers: RefCell<Vec<usize>>,
+    /// The Python exception behind a failed serialization, if there was one.
+    ///
+    /// `serde_json::Error` can only carry a message, so the original exception is
+    /// parked here and re-raised unchanged once serialization unwinds.
+    error: RefCell<Option<PyErr>>,
+    /// Text such as `NaN` to write verbatim in place of the next value.
+    ///
+    /// serde_json writes non-finite floats as `null` and `RawValue` only accepts valid JSON,
+    /// so the token is parked here and [`PyFormatter::write_null`] writes it instead.
+    raw: RefCell<Option<Cow<'static, str>>>,
+}
+
+impl<'py> SerializeOptions<'py> {
+    fn new(
+        sort_keys: bool,
+        ensure_ascii: bool,
+        default: Option<Bound<'py, PyAny>>,
+        skipkeys: bool,
+        allow_nan: bool,
+        check_circular: bool,
+    ) -> Self {
+        SerializeOptions {
+            sort_keys,
+            ensure_ascii,
+            default,
+            skipkeys,
+            allow_nan,
+            check_circular,
+            utf16_key_order: false,
+            containers: RefCell::new(Vec::new()),
+            error: RefCell::new(None),
+            raw: RefCell::new(None),
+        }
+    }
+
+    fn py_error<E: Error>(&self, err: PyErr) -> E {
+        let msg = err.to_string();
+        self.error.replace(Some(err));
+        E::custom(msg)
     }
+}
+
+/// How many times in a row `default` may return another unsupported object.
+const MAX_DEFAULT_DEPTH: usize = 254;
 
-    // Безопасно, потому что serde_json всегда пишет валидный UTF-8.
-    Ok(String::from_utf8(buf)
-        .map_err(|e| PyValueError::new_err(format!("UTF-8 error: {e}")))?)
+/// Nesting depth at which serialization gives up.
+///
+/// Every level costs a few serde frames on the native stack, so this stays well below
+/// CPython's recursion limit to be safe on threads with a 1 MiB stack.
+const MAX_DEPTH: usize = 512;
+
+/// Pops the innermost container off `SerializeOptions::containers` once it has been written.
+struct Nesting<'a> {
+    containers: &'a RefCell<Vec<usize>>,
 }
 
-/// Обёртка, которая делает любой PyAny сериализуемым.
-struct PyAnySerializer<'py> {
+impl Drop for Nesting<'_> {
+    fn drop(&mut self) {
+        self.containers.borrow_mut().pop();
+    }
+}
+
+/// The containers between the root of a document and the value being walked.
+///
+/// Recursive walks over parsed documents enter every dict and list they descend into, so a
+/// container that holds itself raises instead of recursing forever, and so does nesting
+/// deeper than `parse_json` would ever produce.
+#[derive(Default)]
+struct Ancestors(Vec<usize>);
+
+impl Ancestors {
+    fn enter(&mut self, container: &Bound<'_, PyAny>) -> PyResult<()> {
+        if self.0.len() >= decoder::MAX_DEPTH {
+            return Err(PyRecursionError::new_err(
+                "maximum recursion depth exceeded while walking a JSON document",
+            ));
+        }
+        let id = container.as_ptr() as usize;
+        if self.0.contains(&id) {
+            return Err(PyValueError::new_err("Circular reference detected"));
+        }
+        self.0.push(id);
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.0.pop();
+    }
+}
+
+/// Quotes little-endian UTF-16 text as an ASCII-only JSON string, escaping like `json.dumps`.
+fn escape_utf16_ascii(units: &[u8]) -> String {
+    let mut out = String::with_capacity(units.len() + 2);
+    out.push('"');
+    for unit in units.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])) {
+        match unit {
+            0x22 => out.push_str("\\\""),
+            0x5c => out.push_str("\\\\"),
+            0x0a => out.push_str("\\n"),
+            0x0d => out.push_str("\\r"),
+            0x09 => out.push_str("\\t"),
+            0x08 => out.push_str("\\b"),
+            0x0c => out.push_str("\\f"),
+            0x20..=0x7e => out.push(unit as u8 as char),
+            _ => out.push_str(&format!("\\u{:04x}", unit)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The spelling of a non-finite float, as written by `json.dumps`.
+fn non_finite_token(f: f64) -> &'static str {
+    if f.is_nan() {
+        "NaN"
+    } else if f > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+/// Wrapper that makes any PyAny serializable.
+struct PyAnySerializer<'a, 'py> {
     inner: Bound<'py, PyAny>,
+    opts: &'a SerializeOptions<'py>,
+    default_depth: usize,
 }
 
-impl<'py> Serialize for PyAnySerializer<'py> {
+impl<'a, 'py> PyAnySerializer<'a, 'py> {
+    fn child(&self, inner: Bound<'py, PyAny>) -> Self {
+        PyAnySerializer { inner, opts: self.opts, default_depth: 0 }
+    }
+}
+
+impl<'a, 'py> Serialize for PyAnySerializer<'a, 'py> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: SerTrait,
     {
         let obj = &self.inner;
+        let py = obj.py();
 
-        // None -------------------------------------------------------
+        // Exact built-in types are recognised by their type pointer alone, so the
+        // common case never pays for a failed extraction.
+        if let Ok(s) = obj.downcast_exact::<PyString>() {
+            return self.serialize_str(s, serializer);
+        }
+        if let Ok(i) = obj.downcast_exact::<PyInt>() {
+            return serialize_int(i, serializer);
+        }
+        if let Ok(f) = obj.downcast_exact::<PyFloat>() {
+            return self.serialize_float(f.value(), serializer);
+        }
+        if let Ok(d) = obj.downcast_exact::<PyDict>() {
+            return self.serialize_dict(d, serializer);
+        }
+        if let Ok(list) = obj.downcast_exact::<PyList>() {
+            return self.serialize_seq(list.len(), list.iter(), serializer);
+        }
         if obj.is_none() {
             return serializer.serialize_unit();
         }
-        // Bool -------------------------------------------------------
+        if let Ok(b) = obj.downcast_exact::<PyBool>() {
+            return serializer.serialize_bool(b.is_true());
+        }
+
+        // Subclasses and foreign types ----------------------------------
         if let Ok(b) = obj.extract::<bool>() {
             return serializer.serialize_bool(b);
         }
-        // Int --------------------------------------------------------
-        if let Ok(i) = obj.extract::<i64>() {
-            return serializer.serialize_i64(i);
+        if obj.is_instance(self.import_type(py, &ENUM_TYPE, "enum", "Enum")?)
+            .map_err(|e| self.opts.py_error(e))?
+        {
+            let value = obj.getattr("value").map_err(|e| self.opts.py_error(e))?;
+            return self.child(value).serialize(serializer);
         }
-        // Float ------------------------------------------------------
-        if let Ok(f) = obj.extract::<f64>() {
-            if !f.is_finite() {
-                return Err(S::Error::custom("Float out of range"));
-            }
-            return serializer.serialize_f64(f);
+        if let Ok(i) = obj.downcast::<PyInt>() {
+            return serialize_int(i, serializer);
         }
-        // Str --------------------------------------------------------
-        if let Ok(s) = obj.extract::<&str>() {
-            return serializer.serialize_str(s);
+        if let Ok(s) = obj.downcast::<PyString>() {
+            return self.serialize_str(s, serializer);
+        }
+        if let Ok(f) = obj.downcast::<PyFloat>() {
+            return self.serialize_float(f.value(), serializer);
         }
-        // List -------------------------------------------------------
         if let Ok(list) = obj.downcast::<PyList>() {
-            let mut seq = serializer.serialize_seq(Some(list.len()))?;
-            for item in list.iter() {
-                seq.serialize_element(&PyAnySerializer { inner: item })?;
-            }
-            return seq.end();
+            return self.serialize_seq(list.len(), list.iter(), serializer);
         }
-        // Dict -------------------------------------------------------
         if let Ok(d) = obj.downcast::<PyDict>() {
-            let mut map = serializer.serialize_map(Some(d.len()))?;
-            for (k, v) in d.iter() {
-                let key: &str = k
-                    .extract()
-                    .map_err(|_| S::Error::custom("Dict keys must be str"))?;
-                map.serialize_entry(key, &PyAnySerializer { inner: v })?;
+            return self.serialize_dict(d, serializer);
+        }
+        if let Ok(tuple) = obj.downcast::<PyTuple>() {
+            return self.serialize_seq(tuple.len(), tuple.iter(), serializer);
+        }
+        if let Ok(set) = obj.downcast::<PySet>() {
+            return self.serialize_seq(set.len(), set.iter(), serializer);
+        }
+        if let Ok(set) = obj.downcast::<PyFrozenSet>() {
+            return self.serialize_seq(set.len(), set.iter(), serializer);
+        }
+        if obj.downcast::<PyDate>().is_ok() || obj.downcast::<PyTime>().is_ok() {
+            let text = obj.call_method0("isoformat").map_err(|e| self.opts.py_error(e))?;
+            return self.child(text).serialize(serializer);
+        }
+        if obj.is_instance(self.import_type(py, &UUID_TYPE, "uuid", "UUID")?)
+            .map_err(|e| self.opts.py_error(e))?
+        {
+            let text = obj.str().map_err(|e| self.opts.py_error(e))?;
+            return serializer.serialize_str(text.to_str().map_err(S::Error::custom)?);
+        }
+        if obj.is_instance(self.import_type(py, &DECIMAL_TYPE, "decimal", "Decimal")?)
+            .map_err(|e| self.opts.py_error(e))?
+        {
+            // Written as a bare number so no digits are lost to a float conversion.
+            let text = obj.str().map_err(|e| self.opts.py_error(e))?.to_string();
+            let number = RawValue::from_string(text)
+                .map_err(|_| S::Error::custom("Decimal out of range"))?;
+            return number.serialize(serializer);
+        }
+        if let Ok(b) = obj.downcast::<PyBytes>() {
+            return serializer.serialize_str(&general_purpose::STANDARD.encode(b.as_bytes()));
+        }
+        if let Ok(b) = obj.downcast::<PyByteArray>() {
+            return serializer.serialize_str(&general_purpose::STANDARD.encode(b.to_vec()));
+        }
+        if obj.get_type().hasattr("__dataclass_fields__").unwrap_or(false) {
+            return self.serialize_dataclass(serializer);
+        }
+        if let Ok(i) = obj.extract::<i64>() {
+            return serializer.serialize_i64(i);
+        }
+        if let Ok(f) = obj.extract::<f64>() {
+            return self.serialize_float(f, serializer);
+        }
+        if let Some(default) = &self.opts.default {
+            if self.default_depth >= MAX_DEFAULT_DEPTH {
+                return Err(S::Error::custom("default serializer exceeds recursion limit"));
+            }
+            let value = default.call1((obj,)).map_err(|e| self.opts.py_error(e))?;
+            return PyAnySerializer {
+                inner: value,
+                opts: self.opts,
+                default_depth: self.default_depth + 1,
             }
-            return map.end();
+            .serialize(serializer);
         }
 
-        // Всё остальное — ошибка.
         Err(S::Error::custom(format!(
             "Type `{}` is not JSON-serializable",
             obj.get_type().name().unwrap_or(PyString::new(obj.py(), "<unknown>"))
         )))
     }
-}
\ No newline at end of file
+}
+
+static ENUM_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static UUID_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static DECIMAL_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static DATACLASS_FIELDS: GILOnceCell<Py<PyAny>> = GILOnceCell::new();
+
+impl<'a, 'py> PyAnySerializer<'a, 'py> {
+    fn import_type<'c, E: Error>(
+        &self,
+        py: Python<'py>,
+        cell: &'c GILOnceCell<Py<PyType>>,
+        module: &str,
+        name: &str,
+    ) -> Result<&'c Bound<'py, PyType>, E> {
+        cell.import(py, module, name).map_err(|e| self.opts.py_error(e))
+    }
+
+    /// Marks `self.inner` as being written, rejecting reference cycles and runaway nesting.
+    fn enter<E: Error>(&self) -> Result<Nesting<'a>, E> {
+        let containers = &self.opts.containers;
+        let mut stack = containers.borrow_mut();
+        if stack.len() >= MAX_DEPTH {
+            return Err(self.opts.py_error(PyRecursionError::new_err(
+                "maximum recursion depth exceeded while encoding a JSON object",
+            )));
+        }
+        let id = self.inner.as_ptr() as usize;
+        if self.opts.check_circular && stack.contains(&id) {
+            return Err(E::custom("Circular reference detected"));
+        }
+        stack.push(id);
+        Ok(Nesting { containers })
+    }
+
+    fn serialize_str<S: SerTrait>(&self, s: &Bound<'py, PyString>, serializer: S) -> Result<S::Ok, S::Error> {
+        match s.to_str() {
+            Ok(text) => serializer.serialize_str(text),
+            // Lone surrogates have no UTF-8 form, but json.dumps escapes them under ensure_ascii.
+            Err(_) if self.opts.ensure_ascii => {
+                let units = s
+                    .call_method1("encode", ("utf-16-le", "surrogatepass"))
+                    .and_then(|b| Ok(b.downcast_into::<PyBytes>()?))
+                    .map_err(|e| self.opts.py_error(e))?;
+                self.serialize_raw(escape_utf16_ascii(units.as_bytes()).into(), serializer)
+            }
+            Err(e) => Err(self.opts.py_error(e)),
+        }
+    }
+
+    fn serialize_float<S: SerTrait>(&self, f: f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if f.is_finite() {
+            return serializer.serialize_f64(f);
+        }
+        if !self.opts.allow_nan {
+            return Err(S::Error::custom("Float out of range"));
+        }
+        self.serialize_raw(non_finite_token(f).into(), serializer)
+    }
+
+    /// Writes `token` verbatim as the next value.
+    ///
+    /// Only reachable with `allow_nan` or `ensure_ascii` set, which only `write_json` and its
+    /// [`PyFormatter`] use.
+    fn serialize_raw<S: SerTrait>(&self, token: Cow<'static, str>, serializer: S) -> Result<S::Ok, S::Error> {
+        self.opts.raw.replace(Some(token));
+        serializer.serialize_unit()
+    }
+
+    /// The string form of a dict key, or `None` if `skipkeys` says to drop it.
+    fn key<'k, E: Error>(&self, key: &'k Bound<'py, PyAny>) -> Result<Option<Cow<'k, str>>, E> {
+        match dict_key(key, self.opts.allow_nan)? {
+            Some(key) => Ok(Some(key)),
+            None if self.opts.skipkeys => Ok(None),
+            None => Err(E::custom(format!(
+                "Dict keys must be str, int, float, bool or None, not {}",
+                key.get_type().name().unwrap_or(PyString::new(key.py(), "<unknown>"))
+            ))),
+        }
+    }
+
+    fn serialize_seq<S, I>(&self, len: usize, items: I, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: SerTrait,
+        I: Iterator<Item = Bound<'py, PyAny>>,
+    {
+        let _nesting = self.enter()?;
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        for item in items {
+            seq.serialize_element(&self.child(item))?;
+        }
+        seq.end()
+    }
+
+    fn serialize_dict<S: SerTrait>(&self, d: &Bound<'py, PyDict>, serializer: S) -> Result<S::Ok, S::Error> {
+        let _nesting = self.enter()?;
+        if self.opts.sort_keys {
+            let mut entries = Vec::with_capacity(d.len());
+            for (k, v) in d.iter() {
+                if let Some(key) = self.key::<S::Error>(&k)? {
+                    entries.push((key.into_owned(), v));
+                }
+            }
+            return self.serialize_entries(entries, serializer);
+        }
+        let mut map = serializer.serialize_map(Some(d.len()))?;
+        for (k, v) in d.iter() {
+            if let Some(key) = self.key::<S::Error>(&k)? {
+                map.serialize_entry(&key, &self.child(v))?;
+            }
+        }
+        map.end()
+    }
+
+    /// Serializes a dataclass instance as an object of its fields, in definition order.
+    fn serialize_dataclass<S: SerTrait>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let _nesting = self.enter()?;
+        let obj = &self.inner;
+        let py = obj.py();
+        let fields = DATACLASS_FIELDS
+            .get_or_try_init(py, || Ok::<_, PyErr>(py.import("dataclasses")?.getattr("fields")?.unbind()))
+            .and_then(|fields| fields.call1(py, (obj,)))
+            .map_err(|e| self.opts.py_error(e))?;
+        let entries = fields
+            .bind(py)
+            .try_iter()
+            .and_then(|fields| {
+                fields
+                    .map(|field| {
+                        let name = field?.getattr("name")?.extract::<String>()?;
+                        let value = obj.getattr(name.as_str())?;
+                        Ok((name, value))
+                    })
+                    .collect::<PyResult<Vec<_>>>()
+            })
+            .map_err(|e| self.opts.py_error(e))?;
+        self.serialize_entries(entries, serializer)
+    }
+
+    fn serialize_entries<S: SerTrait>(
+        &self,
+        mut entries: Vec<(String, Bound<'py, PyAny>)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if self.opts.utf16_key_order {
+            entries.sort_unstable_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+        } else if self.opts.sort_keys {
+            entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        }
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (k, v) in entries {
+            map.serialize_entry(&k, &self.child(v))?;
+        }
+        map.end()
+    }
+}
+
+/// Coerces a dict key to a string the way `json.dumps` does, or `None` for unsupported key types.
+fn dict_key<'a, E: Error>(key: &'a Bound<'_, PyAny>, allow_nan: bool) -> Result<Option<Cow<'a, str>>, E> {
+    if let Ok(s) = key.downcast::<PyString>() {
+        return s.to_str().map(|s| Some(Cow::Borrowed(s))).map_err(E::custom);
+    }
+    if let Ok(b) = key.downcast::<PyBool>() {
+        return Ok(Some(Cow::Borrowed(if b.is_true() { "true" } else { "false" })));
+    }
+    if key.is_none() {
+        return Ok(Some(Cow::Borrowed("null")));
+    }
+    if let Ok(i) = key.downcast::<PyInt>() {
+        return Ok(Some(Cow::Owned(int_text(i).map_err(E::custom)?)));
+    }
+    if let Ok(f) = key.downcast::<PyFloat>() {
+        let f = f.value();
+        if !f.is_finite() {
+            if !allow_nan {
+                return Err(E::custom("Float out of range"));
+            }
+            return Ok(Some(Cow::Borrowed(non_finite_token(f))));
+        }
+        let mut text = Vec::new();
+        write_py_float(&mut text, f).map_err(E::custom)?;
+        // SAFETY: write_py_float only writes ASCII.
+        return Ok(Some(Cow::Owned(unsafe { String::from_utf8_unchecked(text) })));
+    }
+    Ok(None)
+}
+
+/// Decimal digits of an int, exact at any size.
+fn int_text(i: &Bound<'_, PyInt>) -> PyResult<String> {
+    if let Ok(i) = i.extract::<i64>() {
+        return Ok(i.to_string());
+    }
+    // `int.__repr__` rather than `str()`, so subclasses such as IntEnum give their digits.
+    i.py().get_type::<PyInt>().call_method1("__repr__", (i,))?.extract()
+}
+
+fn serialize_int<S: SerTrait>(i: &Bound<'_, PyInt>, serializer: S) -> Result<S::Ok, S::Error> {
+    if let Ok(i) = i.extract::<i64>() {
+        return serializer.serialize_i64(i);
+    }
+    if let Ok(u) = i.extract::<u64>() {
+        return serializer.serialize_u64(u);
+    }
+    // Beyond 64 bits the digits are written verbatim, so nothing is lost to a float.
+    let number = RawValue::from_string(int_text(i).map_err(S::Error::custom)?).map_err(S::Error::custom)?;
+    number.serialize(serializer)
+}
+
+/// serde_json formatter that writes the same text as Python's `json.dumps`.
+///
+/// Layout follows serde_json's `PrettyFormatter`, with stdlib-style separators,
+/// `repr`-compatible floats, optional `ensure_ascii` escaping and the verbatim tokens
+/// parked in [`SerializeOptions::raw`] on top.
+struct PyFormatter<'a> {
+    indent: Option<&'a [u8]>,
+    item_separator: &'a [u8],
+    key_separator: &'a [u8],
+    ensure_ascii: bool,
+    raw: &'a RefCell<Option<Cow<'static, str>>>,
+    depth: usize,
+    has_value: bool,
+}
+
+impl<'a> PyFormatter<'a> {
+    fn write_newline<W: ?Sized + io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        if let Some(indent) = self.indent {
+            writer.write_all(b"\n")?;
+            for _ in 0..self.depth {
+                writer.write_all(indent)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_separator<W: ?Sized + io::Write>(&self, writer: &mut W, first: bool) -> io::Result<()> {
+        if !first {
+            writer.write_all(self.item_separator)?;
+        }
+        self.write_newline(writer)
+    }
+}
+
+impl<'a> Formatter for PyFormatter<'a> {
+    fn write_null<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        match self.raw.take() {
+            Some(token) => writer.write_all(token.as_bytes()),
+            None => writer.write_all(b"null"),
+        }
+    }
+
+    fn write_f64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        write_py_float(writer, value)
+    }
+
+    fn write_string_fragment<W: ?Sized + io::Write>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()> {
+        if !self.ensure_ascii || fragment.bytes().all(|b| b < 0x7f) {
+            return writer.write_all(fragment.as_bytes());
+        }
+        let mut units = [0u16; 2];
+        for ch in fragment.chars() {
+            if (ch as u32) < 0x7f {
+                writer.write_all(&[ch as u8])?;
+            } else {
+                for unit in ch.encode_utf16(&mut units) {
+                    write!(writer, "\\u{:04x}", unit)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(b"[")
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth -= 1;
+        if self.has_value {
+            self.write_newline(writer)?;
+        }
+        writer.write_all(b"]")
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        self.write_separator(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth -= 1;
+        if self.has_value {
+            self.write_newline(writer)?;
+        }
+        writer.write_all(b"}")
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        self.write_separator(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.key_separator)
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+}
+
+/// Writes a finite float exactly as Python's `repr` would.
+///
+/// ryu already produces the shortest round-tripping digits; only the notation differs:
+/// Python switches to exponent form below `1e-4` and always writes a signed,
+/// two-digit exponent (`1e-05`, `1e+16`).
+fn write_py_float<W: ?Sized + io::Write>(writer: &mut W, value: f64) -> io::Result<()> {
+    let mut buf = ryu::Buffer::new();
+    let s = buf.format_finite(value);
+    if let Some((mantissa, exponent)) = s.split_once('e') {
+        let exponent: i32 = exponent.parse().unwrap_or(0);
+        let sign = if exponent < 0 { '-' } else { '+' };
+        return write!(writer, "{}e{}{:02}", mantissa, sign, exponent.abs());
+    }
+    // ryu keeps [1e-5, 1e-4) in positional notation, repr does not.
+    let (sign, digits) = s.strip_prefix('-').map_or(("", s), |d| ("-", d));
+    match digits.strip_prefix("0.0000") {
+        Some(frac) if !frac.starts_with('0') => {
+            let (first, rest) = frac.split_at(1);
+            let point = if rest.is_empty() { "" } else { "." };
+            write!(writer, "{}{}{}{}e-05", sign, first, point, rest)
+        }
+        _ => writer.write_all(s.as_bytes()),
+    }
+}
@@ -0,0 +1,131 @@
+//! Canonical JSON as defined by RFC 8785, the JSON Canonicalization Scheme (JCS).
+//!
+//! Objects are walked by the same `PyAnySerializer` as `serialize_json`, with keys sorted by
+//! UTF-16 code units; only the number formatting differs, which `JcsFormatter` takes care of.
+
+use std::io;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::Serialize;
+use serde_json::ser::{CompactFormatter, Formatter, Serializer};
+
+use super::{PyAnySerializer, SerializeOptions};
+
+/// Largest magnitude below which every integer has an exact IEEE 754 double.
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+/// Serializes a Python object to its canonical JSON form (RFC 8785).
+///
+/// The output has no whitespace, object keys sorted by their UTF-16 code units, numbers
+/// written the way ECMAScript's `Number.prototype.toString` writes them and strings with only
+/// the escapes JSON requires. Equal data always gives the same bytes, so the result can be
+/// hashed or signed and checked against any other JCS implementation.
+///
+/// The same types as `serialize_json` are accepted. JCS numbers are IEEE 754 doubles: floats
+/// and `Decimal`s are rounded to the nearest double, while ints that a double cannot hold
+/// exactly are rejected rather than silently changed.
+///
+/// # Arguments
+/// * `obj` - A Python object to serialize
+/// * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+///
+/// # Returns
+/// * The canonical UTF-8 encoded JSON text as `bytes`
+///
+/// # Raises
+/// * `ValueError` - For unserializable objects, circular references, `NaN`/`Infinity`, ints that
+///   are not exactly representable as a double, or strings containing lone surrogates
+/// * `RecursionError` - If the object is nested too deeply
+/// * Any exception raised by `default`, unchanged
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// data = {"b": [1.0, 1e21, 0.000001], "a": "€\n"}
+/// print(fastpy_rs.json.canonicalize(data))
+/// # Output: b'{"a":"\xe2\x82\xac\\n","b":[1,1e+21,0.000001]}'
+///
+/// # A content hash that matches other JCS implementations
+/// digest = fastpy_rs.crypto.sha256(fastpy_rs.json.canonicalize(data))
+/// ```
+#[pyfunction]
+#[pyo3(signature = (obj, *, default = None))]
+pub fn canonicalize<'py>(obj: Bound<'py, PyAny>, default: Option<Bound<'py, PyAny>>) -> PyResult<Bound<'py, PyBytes>> {
+    let py = obj.py();
+    let mut opts = SerializeOptions::new(true, false, default, false, false, true);
+    opts.utf16_key_order = true;
+    let mut buf = Vec::<u8>::with_capacity(256);
+    let mut ser = Serializer::with_formatter(&mut buf, JcsFormatter);
+    PyAnySerializer { inner: obj, opts: &opts, default_depth: 0 }
+        .serialize(&mut ser)
+        .map_err(|e| match opts.error.take() {
+            Some(err) => err,
+            None => PyValueError::new_err(e.to_string()),
+        })?;
+    Ok(PyBytes::new(py, &buf))
+}
+
+/// serde_json formatter that writes numbers as RFC 8785 requires.
+///
+/// Layout and string escaping are already canonical in `CompactFormatter`: no whitespace,
+/// and only `"`, `\` and control characters escaped, with the short forms where JSON has them.
+struct JcsFormatter;
+
+impl Formatter for JcsFormatter {
+    fn write_i64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: i64) -> io::Result<()> {
+        if value.unsigned_abs() <= MAX_SAFE_INTEGER {
+            return CompactFormatter.write_i64(writer, value);
+        }
+        write_integer(writer, &value.to_string())
+    }
+
+    fn write_u64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: u64) -> io::Result<()> {
+        if value <= MAX_SAFE_INTEGER {
+            return CompactFormatter.write_u64(writer, value);
+        }
+        write_integer(writer, &value.to_string())
+    }
+
+    fn write_f64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        write_number(writer, value)
+    }
+
+    /// Ints beyond 64 bits and `Decimal`s arrive here as their literal digits.
+    fn write_raw_fragment<W: ?Sized + io::Write>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()> {
+        if fragment.bytes().all(|b| b == b'-' || b.is_ascii_digit()) {
+            return write_integer(writer, fragment);
+        }
+        match fragment.parse::<f64>() {
+            Ok(value) if value.is_finite() => write_number(writer, value),
+            _ => Err(invalid(format!("Number {fragment} is out of range for JSON canonicalization"))),
+        }
+    }
+}
+
+/// Writes a finite double the way ECMAScript's `Number.prototype.toString` does.
+fn write_number<W: ?Sized + io::Write>(writer: &mut W, value: f64) -> io::Result<()> {
+    if !value.is_finite() {
+        return Err(invalid("Float out of range".to_string()));
+    }
+    writer.write_all(ryu_js::Buffer::new().format_finite(value).as_bytes())
+}
+
+/// Writes an integer given as decimal digits, provided a double holds it exactly.
+fn write_integer<W: ?Sized + io::Write>(writer: &mut W, digits: &str) -> io::Result<()> {
+    let value = digits.parse::<f64>().map_err(|e| invalid(e.to_string()))?;
+    if !value.is_finite() {
+        return Err(invalid("Integer out of range for an IEEE 754 double".to_string()));
+    }
+    // `{:.0}` prints every digit of the double, so it only matches when nothing was rounded.
+    if format!("{value:.0}").trim_start_matches('-') != digits.trim_start_matches('-') {
+        return Err(invalid(format!("Integer {digits} cannot be represented exactly as an IEEE 754 double")));
+    }
+    write_number(writer, value)
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,490 @@
+//! Hand-written JSON decoder behind `parse_json` and `json.loads`.
+//!
+//! serde_json only hands numbers over after converting them, so a serde-based parser
+//! cannot give the original text to `parse_float`/`parse_int`, keep ints beyond 64 bits
+//! exact or accept `NaN`/`Infinity`. This decoder works on the raw bytes instead, reports
+//! errors with the stdlib's messages and positions, and calls the user hooks at the same
+//! points the stdlib does. Every entry point that parses a whole document into Python
+//! objects goes through it, so they all agree on numbers, strings and nesting depth.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyRecursionError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+
+use super::{cached_key, raise_decode_error};
+
+/// Nesting depth at which decoding gives up, roughly matching CPython's recursion limit.
+pub(super) const MAX_DEPTH: usize = 1000;
+
+/// The optional callbacks accepted by `json.loads`.
+#[derive(Default)]
+pub(crate) struct DecodeHooks<'py> {
+    pub object_hook: Option<Bound<'py, PyAny>>,
+    pub object_pairs_hook: Option<Bound<'py, PyAny>>,
+    pub parse_float: Option<Bound<'py, PyAny>>,
+    pub parse_int: Option<Bound<'py, PyAny>>,
+    pub parse_constant: Option<Bound<'py, PyAny>>,
+}
+
+pub(super) enum DecodeError {
+    /// Malformed input: stdlib-style message and the byte offset it refers to.
+    Syntax(&'static str, usize),
+    /// An exception raised by a hook or while building an object.
+    Python(PyErr),
+}
+
+impl From<PyErr> for DecodeError {
+    fn from(err: PyErr) -> Self {
+        DecodeError::Python(err)
+    }
+}
+
+pub(super) type DecodeResult<T> = Result<T, DecodeError>;
+
+/// Decodes a complete document from `input` the way `json.loads` does, raising
+/// `JSONDecodeError` for malformed input.
+///
+/// `doc` is the original Python object and is only used to build the exception.
+pub(crate) fn decode<'py>(
+    doc: &Bound<'py, PyAny>,
+    input: &[u8],
+    hooks: &DecodeHooks<'py>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut decoder = Decoder { py: doc.py(), input, pos: 0, depth: 0, hooks, allow_constants: true, float_text: None };
+    decoder.document().map_err(|err| match err {
+        DecodeError::Syntax(msg, offset) => raise_decode_error(doc, input, msg, offset),
+        DecodeError::Python(err) => err,
+    })
+}
+
+/// Parses a complete document from `input` as strict JSON: no hooks, and `NaN`,
+/// `Infinity` and `-Infinity` are rejected.
+pub(super) fn parse<'py>(py: Python<'py>, input: &[u8]) -> DecodeResult<Bound<'py, PyAny>> {
+    let hooks = DecodeHooks::default();
+    Decoder { py, input, pos: 0, depth: 0, hooks: &hooks, allow_constants: false, float_text: None }.document()
+}
+
+/// The text each float of a parsed document was written as, by the address of its `float`.
+///
+/// Addresses are reused once objects are freed, so this is only meaningful while the
+/// document it came with is alive.
+pub(super) type FloatText<'a> = HashMap<usize, &'a str>;
+
+/// Parses like [`parse`], and also returns the text of every float, so that `1.10` can still
+/// become `Decimal('1.10')` rather than going through the nearest `float`.
+pub(super) fn parse_keeping_float_text<'a, 'py>(
+    py: Python<'py>,
+    input: &'a [u8],
+) -> DecodeResult<(Bound<'py, PyAny>, FloatText<'a>)> {
+    let hooks = DecodeHooks::default();
+    let mut decoder =
+        Decoder { py, input, pos: 0, depth: 0, hooks: &hooks, allow_constants: false, float_text: Some(HashMap::new()) };
+    let document = decoder.document()?;
+    Ok((document, decoder.float_text.unwrap_or_default()))
+}
+
+struct Decoder<'a, 'h, 'py> {
+    py: Python<'py>,
+    input: &'a [u8],
+    pos: usize,
+    depth: usize,
+    hooks: &'h DecodeHooks<'py>,
+    allow_constants: bool,
+    float_text: Option<FloatText<'a>>,
+}
+
+impl<'a, 'py> Decoder<'a, '_, 'py> {
+    fn document(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        self.skip_whitespace();
+        let value = self.value()?;
+        self.skip_whitespace();
+        if self.pos != self.input.len() {
+            return Err(DecodeError::Syntax("Extra data", self.pos));
+        }
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        match self.peek() {
+            Some(b'"') => Ok(self.string()?.into_any()),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'n') if self.input[self.pos..].starts_with(b"null") => {
+                self.pos += 4;
+                Ok(self.py.None().into_bound(self.py))
+            }
+            Some(b't') if self.input[self.pos..].starts_with(b"true") => {
+                self.pos += 4;
+                Ok(PyBool::new(self.py, true).to_owned().into_any())
+            }
+            Some(b'f') if self.input[self.pos..].starts_with(b"false") => {
+                self.pos += 5;
+                Ok(PyBool::new(self.py, false).to_owned().into_any())
+            }
+            Some(b'N') if self.allow_constants && self.input[self.pos..].starts_with(b"NaN") => self.constant("NaN"),
+            Some(b'I') if self.allow_constants && self.input[self.pos..].starts_with(b"Infinity") => {
+                self.constant("Infinity")
+            }
+            _ => Err(DecodeError::Syntax("Expecting value", self.pos)),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> DecodeResult<Bound<'py, PyAny>>) -> DecodeResult<Bound<'py, PyAny>> {
+        if self.depth >= MAX_DEPTH {
+            return Err(PyRecursionError::new_err("maximum recursion depth exceeded while decoding a JSON document").into());
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn constant(&mut self, name: &'static str) -> DecodeResult<Bound<'py, PyAny>> {
+        self.pos += name.len();
+        if let Some(hook) = &self.hooks.parse_constant {
+            return Ok(hook.call1((name,))?);
+        }
+        let value = match name {
+            "NaN" => f64::NAN,
+            "Infinity" => f64::INFINITY,
+            _ => f64::NEG_INFINITY,
+        };
+        Ok(PyFloat::new(self.py, value).into_any())
+    }
+
+    fn number(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+            if self.allow_constants && self.input[self.pos..].starts_with(b"Infinity") {
+                self.pos = start;
+                return self.constant("-Infinity");
+            }
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(DecodeError::Syntax("Expecting value", start)),
+        }
+        let mut is_float = false;
+        if self.peek() == Some(b'.') && self.input.get(self.pos + 1).is_some_and(u8::is_ascii_digit) {
+            is_float = true;
+            self.pos += 1;
+            self.skip_digits();
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            let mut end = self.pos + 1;
+            if let Some(b'+' | b'-') = self.input.get(end) {
+                end += 1;
+            }
+            if self.input.get(end).is_some_and(u8::is_ascii_digit) {
+                is_float = true;
+                self.pos = end;
+                self.skip_digits();
+            }
+        }
+
+        let input = self.input;
+        // SAFETY: the number grammar only admits ASCII.
+        let text = unsafe { std::str::from_utf8_unchecked(&input[start..self.pos]) };
+        if is_float {
+            if let Some(hook) = &self.hooks.parse_float {
+                return Ok(hook.call1((text,))?);
+            }
+            let value = PyFloat::new(self.py, text.parse().unwrap_or(f64::NAN));
+            if let Some(float_text) = &mut self.float_text {
+                float_text.insert(value.as_ptr() as usize, text);
+            }
+            return Ok(value.into_any());
+        }
+        if let Some(hook) = &self.hooks.parse_int {
+            return Ok(hook.call1((text,))?);
+        }
+        match text.parse::<i64>() {
+            Ok(value) => Ok(PyInt::new(self.py, value).into_any()),
+            // Too big for 64 bits: let Python build the arbitrary-precision int.
+            Err(_) => Ok(self.py.get_type::<PyInt>().call1((text,))?),
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+    }
+
+    fn string(&mut self) -> DecodeResult<Bound<'py, PyString>> {
+        let (text, escaped) = self.string_contents()?;
+        Ok(match escaped {
+            Some(text) => text.into_pystring(self.py)?,
+            None => PyString::new(self.py, text),
+        })
+    }
+
+    fn key(&mut self) -> DecodeResult<Bound<'py, PyString>> {
+        let (text, escaped) = self.string_contents()?;
+        Ok(match escaped {
+            Some(text) => text.into_pystring(self.py)?,
+            None => cached_key(self.py, text),
+        })
+    }
+
+    /// Scans a string starting at the opening quote.
+    ///
+    /// Strings without escapes are returned as a borrowed slice of the input; the rest
+    /// are unescaped into an owned buffer.
+    fn string_contents(&mut self) -> DecodeResult<(&'a str, Option<Unescaped>)> {
+        let quote = self.pos;
+        self.pos += 1;
+        let start = self.pos;
+        let input = self.input;
+        loop {
+            match input.get(self.pos) {
+                None => return Err(DecodeError::Syntax("Unterminated string starting at", quote)),
+                Some(b'"') => {
+                    if let Ok(text) = std::str::from_utf8(&input[start..self.pos]) {
+                        self.pos += 1;
+                        return Ok((text, None));
+                    }
+                    break;
+                }
+                Some(b'\\') => break,
+                Some(0..=0x1f) => return Err(DecodeError::Syntax("Invalid control character at", self.pos)),
+                Some(_) => self.pos += 1,
+            }
+        }
+
+        let mut out = Unescaped::String(String::with_capacity(self.pos - start + 16));
+        self.push_text(&mut out, start, self.pos, quote)?;
+        loop {
+            let chunk = self.pos;
+            while let Some(&b) = input.get(self.pos) {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            self.push_text(&mut out, chunk, self.pos, quote)?;
+            match input.get(self.pos) {
+                None => return Err(DecodeError::Syntax("Unterminated string starting at", quote)),
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(("", Some(out)));
+                }
+                Some(b'\\') => self.escape(&mut out, quote)?,
+                Some(_) => return Err(DecodeError::Syntax("Invalid control character at", self.pos)),
+            }
+        }
+    }
+
+    /// Appends the UTF-8 text `input[start..end]` to `out`.
+    ///
+    /// Lone surrogates encoded like ordinary code points, as `str.encode("utf-8",
+    /// "surrogatepass")` writes them, are kept; the stdlib decodes its input the same way.
+    fn push_text(&self, out: &mut Unescaped, mut start: usize, end: usize, quote: usize) -> DecodeResult<()> {
+        loop {
+            let err = match std::str::from_utf8(&self.input[start..end]) {
+                Ok(text) => {
+                    out.push_str(text);
+                    return Ok(());
+                }
+                Err(err) => err,
+            };
+            let invalid = start + err.valid_up_to();
+            // SAFETY: `from_utf8` just validated everything before `invalid`.
+            out.push_str(unsafe { std::str::from_utf8_unchecked(&self.input[start..invalid]) });
+            match self.input[invalid..end] {
+                [0xed, hi @ 0xa0..=0xbf, lo @ 0x80..=0xbf, ..] => {
+                    out.push_code_point(0xd000 | (u32::from(hi & 0x3f) << 6) | u32::from(lo & 0x3f));
+                    start = invalid + 3;
+                }
+                _ if err.error_len().is_some() => return Err(DecodeError::Syntax("Invalid UTF-8 in string at", invalid)),
+                _ => return Err(DecodeError::Syntax("Unterminated string starting at", quote)),
+            }
+        }
+    }
+
+    /// Unescapes the escape sequence at `pos` into `out`; `quote` is where the string starts.
+    fn escape(&mut self, out: &mut Unescaped, quote: usize) -> DecodeResult<()> {
+        let escape = self.pos;
+        let ch = match self.input.get(self.pos + 1) {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let unit = self.hex4(escape + 2)?;
+                self.pos = escape + 6;
+                if (0xd800..0xdc00).contains(&unit) && self.input[self.pos..].starts_with(b"\\u") {
+                    let low = self.hex4(self.pos + 2)?;
+                    if (0xdc00..0xe000).contains(&low) {
+                        self.pos += 6;
+                        let code = 0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00);
+                        out.push_code_point(code);
+                        return Ok(());
+                    }
+                }
+                out.push_code_point(unit);
+                return Ok(());
+            }
+            None => return Err(DecodeError::Syntax("Unterminated string starting at", quote)),
+            Some(_) => return Err(DecodeError::Syntax("Invalid \\escape", escape)),
+        };
+        self.pos += 2;
+        out.push_code_point(ch as u32);
+        Ok(())
+    }
+
+    fn hex4(&self, at: usize) -> DecodeResult<u32> {
+        self.input
+            .get(at..at + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or(DecodeError::Syntax("Invalid \\uXXXX escape", at - 1))
+    }
+
+    fn object(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        self.pos += 1;
+        let mut pairs = Vec::new();
+        let dict = PyDict::new(self.py);
+        let collect_pairs = self.hooks.object_pairs_hook.is_some();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                if self.peek() != Some(b'"') {
+                    return Err(DecodeError::Syntax(
+                        "Expecting property name enclosed in double quotes",
+                        self.pos,
+                    ));
+                }
+                let key = self.key()?;
+                self.skip_whitespace();
+                if self.peek() != Some(b':') {
+                    return Err(DecodeError::Syntax("Expecting ':' delimiter", self.pos));
+                }
+                self.pos += 1;
+                self.skip_whitespace();
+                let value = self.value()?;
+                if collect_pairs {
+                    pairs.push(PyTuple::new(self.py, [key.into_any(), value])?);
+                } else {
+                    dict.set_item(key, value)?;
+                }
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => {
+                        self.pos += 1;
+                        self.skip_whitespace();
+                    }
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(DecodeError::Syntax("Expecting ',' delimiter", self.pos)),
+                }
+            }
+        }
+
+        if let Some(hook) = &self.hooks.object_pairs_hook {
+            return Ok(hook.call1((PyList::new(self.py, pairs)?,))?);
+        }
+        if let Some(hook) = &self.hooks.object_hook {
+            return Ok(hook.call1((dict,))?);
+        }
+        Ok(dict.into_any())
+    }
+
+    fn array(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                items.push(self.value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => {
+                        self.pos += 1;
+                        self.skip_whitespace();
+                    }
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(DecodeError::Syntax("Expecting ',' delimiter", self.pos)),
+                }
+            }
+        }
+        Ok(PyList::new(self.py, items)?.into_any())
+    }
+}
+
+/// Buffer for a string with escapes.
+///
+/// JSON may contain lone surrogates (`"\ud800"`), which Python strings can hold but Rust
+/// strings cannot, so the buffer switches to raw code points once one shows up.
+pub(super) enum Unescaped {
+    String(String),
+    CodePoints(Vec<u32>),
+}
+
+impl Unescaped {
+    pub(super) fn push_str(&mut self, s: &str) {
+        match self {
+            Unescaped::String(out) => out.push_str(s),
+            Unescaped::CodePoints(out) => out.extend(s.chars().map(u32::from)),
+        }
+    }
+
+    pub(super) fn push_code_point(&mut self, code: u32) {
+        match (&mut *self, char::from_u32(code)) {
+            (Unescaped::String(out), Some(ch)) => out.push(ch),
+            (Unescaped::String(out), None) => {
+                let mut points: Vec<u32> = out.chars().map(u32::from).collect();
+                points.push(code);
+                *self = Unescaped::CodePoints(points);
+            }
+            (Unescaped::CodePoints(out), _) => out.push(code),
+        }
+    }
+
+    pub(super) fn into_pystring(self, py: Python<'_>) -> PyResult<Bound<'_, PyString>> {
+        match self {
+            Unescaped::String(s) => Ok(PyString::new(py, &s)),
+            Unescaped::CodePoints(points) => {
+                // SAFETY: `points` is a valid UCS-4 buffer of `points.len()` code points.
+                unsafe {
+                    let ptr = pyo3::ffi::PyUnicode_FromKindAndData(
+                        pyo3::ffi::PyUnicode_4BYTE_KIND as _,
+                        points.as_ptr().cast(),
+                        points.len() as pyo3::ffi::Py_ssize_t,
+                    );
+                    Ok(Bound::from_owned_ptr_or_err(py, ptr)?.downcast_into_unchecked())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Reading and writing JSON files by path.
+//!
+//! Files are memory-mapped for reading and gzip input is recognised by its magic bytes, so
+//! only building Python objects (or serializing them) happens with the GIL held; opening,
+//! reading and decompressing run with it released, and compressing and writing happen on
+//! a separate thread.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::thread::{self, JoinHandle};
+
+use flate2::write::GzEncoder;
+use memmap2::{Mmap, MmapOptions};
+use pyo3::prelude::*;
+
+use super::decoder::DecodeError;
+use super::{
+    char_count, indent_string, parse_error_location, parse_slice, relocated_decode_error, write_json, SerializeOptions,
+    UTF8_BOM,
+};
+use crate::datatools::{gunzip, gzip_level};
+
+/// How much serialized output is collected before it is handed to the writer thread.
+const FLUSH_BYTES: usize = 1 << 20;
+
+/// How many chunks may wait for the writer thread before serialization waits for it.
+const CHUNKS_IN_FLIGHT: usize = 4;
+
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+/// Parses the JSON file at `path` into the corresponding Python object.
+///
+/// The file is memory-mapped, or decompressed first when it is gzip, with the GIL released;
+/// parsing then works directly on the mapped bytes, without first copying them into a
+/// Python `str` the way `parse_json(open(path).read())` does. A UTF-8 BOM is skipped.
+///
+/// # Arguments
+/// * `path` - The file to read, as `str` or `os.PathLike`; gzip files (such as `*.json.gz`) are decompressed transparently
+///
+/// # Returns
+/// * The parsed document, as `parse_json` would return it
+///
+/// # Raises
+/// * `JSONDecodeError` - If the file is not valid JSON; its `doc` is empty rather than a copy of the
+///   file, while `pos`, `lineno` and `colno` still point into it
+/// * `OSError` - If the file cannot be read, or is gzip data that is corrupt
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// data = json.load_file("dump.json")
+/// events = json.load_file("events.json.gz")
+/// ```
+#[pyfunction]
+pub fn load_file(py: Python<'_>, path: PathBuf) -> PyResult<PyObject> {
+    let data = py.allow_threads(|| FileData::read(&path))?;
+    let bytes = data.strip_prefix(UTF8_BOM).unwrap_or(&data);
+    match parse_slice(py, bytes) {
+        Ok(value) => Ok(value.unbind()),
+        Err(err) => Err(file_error(py, bytes, err)),
+    }
+}
+
+/// A `JSONDecodeError` for a file that does not parse.
+///
+/// Files can be huge, so `doc` is left empty instead of holding a copy of the content;
+/// `pos`, `lineno` and `colno` are worked out from the bytes.
+fn file_error(py: Python<'_>, bytes: &[u8], err: DecodeError) -> PyErr {
+    let (msg, offset) = match parse_error_location(err) {
+        Ok(location) => location,
+        Err(err) => return err,
+    };
+    let before = &bytes[..offset];
+    let lineno = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let line_start = before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    relocated_decode_error(py, &msg, "", char_count(before), lineno, char_count(&before[line_start..]) + 1)
+}
+
+/// Serializes `obj` as JSON into the file at `path`.
+///
+/// Output is serialized exactly like `serialize_json` does and streamed to the file in
+/// chunks as it is produced, so the whole document is never held in memory. The GIL is
+/// held while `obj` is being walked, so other threads cannot change it halfway through,
+/// while a background thread compresses and writes the finished chunks without it.
+/// A path ending in `.gz` is written gzip-compressed.
+///
+/// # Arguments
+/// * `obj` - A Python object to serialize
+/// * `path` - Where to write, as `str` or `os.PathLike`; an existing file is overwritten
+/// * `compress_level` - The gzip compression level from 0 to 9, used for `.gz` paths
+/// * The other keyword arguments are the same as for `serialize_json`
+///
+/// # Raises
+/// * `ValueError` - For unserializable objects, circular references, non-finite floats with
+///   `allow_nan=False` or a bad `compress_level`; the file is left partially written
+/// * `OSError` - If the file cannot be written
+/// * Any exception raised by `default`, unchanged
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// json.dump_file({"users": users}, "users.json", indent=2)
+/// json.dump_file(events, "events.json.gz")
+/// ```
+#[pyfunction]
+#[pyo3(signature = (obj, path, *, indent = None, sort_keys = false, ensure_ascii = false, separators = None, default = None, skipkeys = false, allow_nan = false, check_circular = true, compress_level = 9))]
+#[allow(clippy::too_many_arguments)]
+pub fn dump_file<'py>(
+    py: Python<'py>,
+    obj: Bound<'py, PyAny>,
+    path: PathBuf,
+    indent: Option<Bound<'py, PyAny>>,
+    sort_keys: bool,
+    ensure_ascii: bool,
+    separators: Option<(String, String)>,
+    default: Option<Bound<'py, PyAny>>,
+    skipkeys: bool,
+    allow_nan: bool,
+    check_circular: bool,
+    compress_level: i32,
+) -> PyResult<()> {
+    let indent = indent.map(|indent| indent_string(&indent)).transpose()?;
+    let separators = separators.unwrap_or_else(|| match indent {
+        Some(_) => (",".to_string(), ": ".to_string()),
+        None => (",".to_string(), ":".to_string()),
+    });
+    let opts = SerializeOptions::new(sort_keys, ensure_ascii, default, skipkeys, allow_nan, check_circular);
+    let gzip = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    let level = gzip.then(|| gzip_level(compress_level)).transpose()?;
+    let file = py.allow_threads(|| File::create(&path))?;
+    let sink = match level {
+        Some(level) => Sink::Gzip(GzEncoder::new(file, level)),
+        None => Sink::Plain(file),
+    };
+
+    let mut writer = ChunkedWriter::new(sink);
+    let result = write_json(&mut writer, obj, indent.as_deref(), &separators, &opts);
+    // A failed write stops serialization too, and is the error worth reporting.
+    py.allow_threads(|| writer.finish())?;
+    result
+}
+
+/// The contents of a file: mapped as it is, or decompressed.
+enum FileData {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl FileData {
+    fn read(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(FileData::Owned(Vec::new()));
+        }
+        // SAFETY: the map is only read, and only while this call owns it; as with any
+        // memory-mapped file, a concurrent writer could change what the parser sees.
+        // `populate` faults the pages in now, while the GIL is released.
+        let map = unsafe { MmapOptions::new().populate().map(&file)? };
+        if map.starts_with(GZIP_MAGIC) {
+            return Ok(FileData::Owned(gunzip(&map)?));
+        }
+        Ok(FileData::Mapped(map))
+    }
+}
+
+impl Deref for FileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileData::Mapped(map) => map,
+            FileData::Owned(data) => data,
+        }
+    }
+}
+
+enum Sink {
+    Plain(File),
+    Gzip(GzEncoder<File>),
+}
+
+impl Sink {
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Sink::Plain(mut file) => file.flush(),
+            Sink::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(file) => file.write(data),
+            Sink::Gzip(encoder) => encoder.write(data),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(file) => file.flush(),
+            Sink::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Collects serialized output and hands it in `FLUSH_BYTES` chunks to a thread that writes
+/// them to the sink.
+///
+/// Serialization keeps the GIL, as another thread must not get the chance to mutate
+/// containers halfway through them; compressing and writing do not need it.
+struct ChunkedWriter {
+    chunks: Option<SyncSender<Vec<u8>>>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+    buf: Vec<u8>,
+}
+
+impl ChunkedWriter {
+    fn new(mut sink: Sink) -> Self {
+        let (chunks, received) = sync_channel::<Vec<u8>>(CHUNKS_IN_FLIGHT);
+        let thread = thread::spawn(move || {
+            for chunk in received {
+                sink.write_all(&chunk)?;
+            }
+            sink.finish()
+        });
+        ChunkedWriter { chunks: Some(chunks), thread: Some(thread), buf: Vec::with_capacity(FLUSH_BYTES) }
+    }
+
+    fn send(&mut self) -> io::Result<()> {
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(FLUSH_BYTES));
+        match &self.chunks {
+            Some(chunks) if chunks.send(chunk).is_ok() => Ok(()),
+            // The thread only hangs up after a failed write; `finish` reports that error.
+            _ => Err(io::Error::other("writing the file failed")),
+        }
+    }
+
+    /// Sends what is left, then waits for the thread to write it out.
+    fn finish(&mut self) -> io::Result<()> {
+        let sent = self.send();
+        self.chunks = None;
+        let written = match self.thread.take() {
+            Some(thread) => thread.join().unwrap_or_else(|_| Err(io::Error::other("the writer thread panicked"))),
+            None => Ok(()),
+        };
+        written.and(sent)
+    }
+}
+
+impl Write for ChunkedWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= FLUSH_BYTES {
+            self.send()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+//! JSON Schema inference from sample documents.
+//!
+//! Samples are merged into one [`Node`] tree that records which JSON types were seen at
+//! each location, which object properties were always present, and the distinct values
+//! and common format of strings. The tree is then written out as a 2020-12 schema.
+
+use std::collections::BTreeMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList, PyString};
+use serde_json::Value;
+
+use super::{object_to_value, to_value};
+
+const SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Infers a JSON Schema (draft 2020-12) that every sample document validates against.
+///
+/// Types are merged across samples, so a value that is sometimes `null` gets
+/// `"type": ["string", "null"]`, and ints and floats together become `number`. Object
+/// properties present in every sample are `required`. A string field becomes an `enum`
+/// when it has at most `max_enum` distinct values and each value occurs twice on average;
+/// either way it also gets a `format` when all its values are `date-time`, `date`, `time`,
+/// `uuid`, `email`, `uri` or `ipv4`.
+///
+/// # Arguments
+/// * `samples` - An iterable of parsed documents, or JSON text holding an array of documents
+/// * `max_enum` - Most distinct values a string field may have to become an `enum`; `0` disables enums
+/// * `detect_formats` - Whether to detect string formats
+///
+/// # Returns
+/// * The schema as a dict, ready for `serialize_json` or `Validator`
+///
+/// # Raises
+/// * `ValueError` - If there are no samples, or `samples` is JSON text that is not an array
+/// * `JSONDecodeError` - If `samples` is JSON text that does not parse
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// samples = [
+///     {"id": 1, "status": "active", "email": "ann@example.com", "tags": ["a"]},
+///     {"id": 2, "status": "active", "email": "bob@example.com", "note": None},
+///     {"id": 3, "status": "closed", "email": "eve@example.com", "note": "late"},
+///     {"id": 4, "status": "active", "email": "joe@example.com", "note": None},
+/// ]
+/// schema = json.infer_schema(samples)
+/// print(schema["required"])                 # Output: ['email', 'id', 'status']
+/// print(schema["properties"]["status"])     # Output: {'type': 'string', 'enum': ['active', 'closed']}
+/// print(schema["properties"]["email"])      # Output: {'type': 'string', 'format': 'email'}
+/// print(schema["properties"]["note"])       # Output: {'type': ['string', 'null']}
+/// ```
+#[pyfunction]
+#[pyo3(signature = (samples, *, max_enum = 10, detect_formats = true))]
+pub fn infer_schema<'py>(
+    samples: &Bound<'py, PyAny>,
+    max_enum: usize,
+    detect_formats: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let py = samples.py();
+    let mut root = Node::default();
+    let mut count = 0;
+    if samples.is_instance_of::<PyString>() || samples.is_instance_of::<PyBytes>() {
+        let Value::Array(items) = to_value(samples)? else {
+            return Err(PyValueError::new_err("JSON text passed as samples must hold an array of documents"));
+        };
+        for item in &items {
+            root.add(item, max_enum, detect_formats);
+        }
+        count = items.len();
+    } else {
+        for sample in samples.try_iter()? {
+            root.add(&object_to_value(&sample?)?, max_enum, detect_formats);
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Err(PyValueError::new_err("infer_schema needs at least one sample"));
+    }
+    let schema = PyDict::new(py);
+    schema.set_item("$schema", SCHEMA_DRAFT)?;
+    root.write(&schema, max_enum)?;
+    Ok(schema)
+}
+
+/// Everything seen at one location across the samples.
+#[derive(Default)]
+struct Node {
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    number: bool,
+    string: Option<Strings>,
+    /// The merged items of every array seen here.
+    array: Option<Box<Node>>,
+    object: Option<Object>,
+}
+
+struct Strings {
+    count: usize,
+    /// Distinct values, until there are more than `max_enum` of them.
+    values: Option<Vec<String>>,
+    /// The format every value so far has.
+    format: Option<&'static str>,
+}
+
+#[derive(Default)]
+struct Object {
+    count: usize,
+    /// Properties by name, so the schema lists them in a stable order.
+    properties: BTreeMap<String, Property>,
+}
+
+#[derive(Default)]
+struct Property {
+    node: Node,
+    /// How many of the objects had this property.
+    count: usize,
+}
+
+impl Node {
+    fn add(&mut self, value: &Value, max_enum: usize, detect_formats: bool) {
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(n) if n.is_f64() => self.number = true,
+            Value::Number(_) => self.integer = true,
+            Value::String(s) => {
+                let strings = self.string.get_or_insert_with(|| Strings {
+                    count: 0,
+                    values: Some(Vec::new()),
+                    format: if detect_formats { detect_format(s) } else { None },
+                });
+                strings.count += 1;
+                if let Some(values) = &mut strings.values {
+                    if !values.contains(s) {
+                        values.push(s.clone());
+                    }
+                    if values.len() > max_enum {
+                        strings.values = None;
+                    }
+                }
+                if strings.format.is_some_and(|format| !has_format(format, s)) {
+                    strings.format = None;
+                }
+            }
+            Value::Array(items) => {
+                let node = self.array.get_or_insert_with(Box::default);
+                for item in items {
+                    node.add(item, max_enum, detect_formats);
+                }
+            }
+            Value::Object(map) => {
+                let object = self.object.get_or_insert_with(Object::default);
+                object.count += 1;
+                for (key, item) in map {
+                    let property = object.properties.entry(key.clone()).or_default();
+                    property.count += 1;
+                    property.node.add(item, max_enum, detect_formats);
+                }
+            }
+        }
+    }
+
+    fn write(&self, schema: &Bound<'_, PyDict>, max_enum: usize) -> PyResult<()> {
+        let py = schema.py();
+        let mut types = Vec::new();
+        if self.object.is_some() {
+            types.push("object");
+        }
+        if self.array.is_some() {
+            types.push("array");
+        }
+        if self.string.is_some() {
+            types.push("string");
+        }
+        if self.number {
+            types.push("number");
+        } else if self.integer {
+            types.push("integer");
+        }
+        if self.boolean {
+            types.push("boolean");
+        }
+        if self.null {
+            types.push("null");
+        }
+        match types.as_slice() {
+            [] => return Ok(()),
+            [single] => schema.set_item("type", single)?,
+            _ => schema.set_item("type", PyList::new(py, &types)?)?,
+        }
+
+        if let Some(strings) = &self.string {
+            // `enum` constrains every type, so it is only used when the field is a string or null.
+            let only_strings = types.iter().all(|t| *t == "string" || *t == "null");
+            match &strings.values {
+                Some(values) if only_strings && max_enum > 0 && strings.count >= 2 * values.len() => {
+                    let mut values: Vec<&str> = values.iter().map(String::as_str).collect();
+                    values.sort_unstable();
+                    let values = PyList::new(py, values)?;
+                    if self.null {
+                        values.append(py.None())?;
+                    }
+                    schema.set_item("enum", values)?;
+                }
+                _ => {}
+            }
+            if let Some(format) = strings.format {
+                schema.set_item("format", format)?;
+            }
+        }
+        if let Some(items) = &self.array {
+            let items_schema = PyDict::new(py);
+            items.write(&items_schema, max_enum)?;
+            if !items_schema.is_empty() {
+                schema.set_item("items", items_schema)?;
+            }
+        }
+        if let Some(object) = &self.object {
+            let properties = PyDict::new(py);
+            let required = PyList::empty(py);
+            for (name, property) in &object.properties {
+                let property_schema = PyDict::new(py);
+                property.node.write(&property_schema, max_enum)?;
+                properties.set_item(name, property_schema)?;
+                if property.count == object.count {
+                    required.append(name)?;
+                }
+            }
+            schema.set_item("properties", properties)?;
+            if !required.is_empty() {
+                schema.set_item("required", required)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+type FormatCheck = fn(&str) -> bool;
+
+/// Formats tried in order; the first that matches a field's first value is kept while
+/// every later value matches it too.
+const FORMATS: [(&str, FormatCheck); 7] = [
+    ("date-time", is_date_time),
+    ("date", is_date),
+    ("time", is_time),
+    ("uuid", is_uuid),
+    ("email", is_email),
+    ("uri", is_uri),
+    ("ipv4", is_ipv4),
+];
+
+fn detect_format(s: &str) -> Option<&'static str> {
+    FORMATS.iter().find(|(_, check)| check(s)).map(|(name, _)| *name)
+}
+
+fn has_format(format: &str, s: &str) -> bool {
+    FORMATS.iter().any(|(name, check)| *name == format && check(s))
+}
+
+/// Parses `s` as an unsigned decimal of exactly `s.len()` digits.
+fn number(s: &[u8]) -> Option<u32> {
+    if s.is_empty() || !s.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(s.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+}
+
+/// RFC 3339 `full-date`, e.g. `2024-02-29`.
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day)) = (number(&b[..4]), number(&b[5..7]), number(&b[8..])) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// RFC 3339 `full-time`, e.g. `12:30:00.5Z` or `12:30:00+02:00`.
+fn is_time(s: &str) -> bool {
+    let b = s.as_bytes();
+    if b.len() < 9 || b[2] != b':' || b[5] != b':' {
+        return false;
+    }
+    let (Some(hour), Some(minute), Some(second)) = (number(&b[..2]), number(&b[3..5]), number(&b[6..8])) else {
+        return false;
+    };
+    if hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+    let mut rest = &b[8..];
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        let digits = fraction.iter().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return false;
+        }
+        rest = &fraction[digits..];
+    }
+    match rest {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', h1, h2, b':', m1, m2] => {
+            matches!((number(&[*h1, *h2]), number(&[*m1, *m2])), (Some(h), Some(m)) if h <= 23 && m <= 59)
+        }
+        _ => false,
+    }
+}
+
+/// RFC 3339 `date-time`, e.g. `2024-05-01T12:30:00Z`.
+fn is_date_time(s: &str) -> bool {
+    s.len() > 11 && s.is_ascii() && matches!(s.as_bytes()[10], b'T' | b't') && is_date(&s[..10]) && is_time(&s[11..])
+}
+
+/// A hyphenated UUID, e.g. `12345678-1234-5678-1234-567812345678`.
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.bytes().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == b'-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// A plain `local@domain.tld` address.
+fn is_email(s: &str) -> bool {
+    let Some((local, domain)) = s.rsplit_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !local.contains('@')
+        && !local.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-'))
+}
+
+/// An absolute URI with an authority, e.g. `https://example.com/a?b`.
+fn is_uri(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once("://") else {
+        return false;
+    };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && !rest.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// A dotted-quad IPv4 address, e.g. `192.168.0.1`.
+fn is_ipv4(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('.').collect();
+    parts.len() == 4
+        && parts.iter().all(|part| {
+            part.len() <= 3
+                && (part.len() == 1 || !part.starts_with('0'))
+                && number(part.as_bytes()).is_some_and(|n| n <= 255)
+        })
+}
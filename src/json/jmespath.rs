@@ -0,0 +1,1033 @@
+//! JMESPath (<https://jmespath.org/specification.html>) evaluated over Python objects.
+//!
+//! The parser is a Pratt parser with the binding powers of the reference implementation,
+//! so precedence and projection boundaries behave exactly like `jmespath.search`.
+
+use std::cmp::Ordering;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString};
+
+use super::queries::{elements, json_eq, kind, slice_indices, Kind};
+use super::stream::number_kind;
+use super::{parse_json, to_json, SerializeOptions};
+
+/// How deeply expressions may nest before parsing gives up.
+const MAX_NESTING: usize = 256;
+
+/// A parsed JMESPath expression.
+pub(super) enum Ast {
+    Identity,
+    Field(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Subexpr(Box<Ast>, Box<Ast>),
+    Projection(Box<Ast>, Box<Ast>),
+    ValueProjection(Box<Ast>, Box<Ast>),
+    FilterProjection(Box<Ast>, Box<Ast>, Box<Ast>),
+    Flatten(Box<Ast>),
+    Pipe(Box<Ast>, Box<Ast>),
+    MultiList(Vec<Ast>),
+    MultiHash(Vec<(String, Ast)>),
+    Literal(Py<PyAny>),
+    Not(Box<Ast>),
+    And(Box<Ast>, Box<Ast>),
+    Or(Box<Ast>, Box<Ast>),
+    Compare(Comparator, Box<Ast>, Box<Ast>),
+    Function(Function, Vec<Ast>),
+    ExpRef(Box<Ast>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+/// Parses `expression`, raising `ValueError` with the position of the first problem.
+pub(super) fn parse(py: Python<'_>, expression: &str) -> PyResult<Ast> {
+    let tokens = lex(expression).map_err(|(msg, pos)| syntax_error(expression, &msg, pos))?;
+    let mut parser = Parser { py, tokens, index: 0, depth: 0 };
+    let ast = parser
+        .expression(0)
+        .and_then(|ast| match parser.peek() {
+            Token::Eof => Ok(ast),
+            _ => Err(parser.unexpected()),
+        })
+        .map_err(|err| match err {
+            ParseError::Syntax(msg, pos) => syntax_error(expression, &msg, pos),
+            ParseError::Python(err) => err,
+        })?;
+    Ok(ast)
+}
+
+fn syntax_error(expression: &str, msg: &str, pos: usize) -> PyErr {
+    PyValueError::new_err(format!("Invalid JMESPath expression {expression:?}: {msg} at position {pos}"))
+}
+
+// Lexer --------------------------------------------------------------------
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Dot,
+    Star,
+    Flatten,
+    Filter,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Expref,
+    Current,
+    Compare(Comparator),
+    Identifier(String),
+    QuotedIdentifier(String),
+    Number(i64),
+    RawString(String),
+    /// JSON text between backticks.
+    Literal(String),
+    Eof,
+}
+
+impl Token {
+    fn binding_power(&self) -> u8 {
+        match self {
+            Token::Pipe => 1,
+            Token::Or => 2,
+            Token::And => 3,
+            Token::Compare(_) => 5,
+            Token::Flatten => 9,
+            Token::Star => 20,
+            Token::Filter => 21,
+            Token::Dot => 40,
+            Token::Not => 45,
+            Token::LBrace => 50,
+            Token::LBracket => 55,
+            Token::LParen => 60,
+            _ => 0,
+        }
+    }
+}
+
+/// Tokens that end a projection have a binding power below this.
+const PROJECTION_STOP: u8 = 10;
+
+type LexError = (String, usize);
+
+fn lex(expression: &str) -> Result<Vec<(Token, usize)>, LexError> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let next = chars.get(i + 1).copied();
+        let token = match chars[i] {
+            ' ' | '\t' | '\n' | '\r' => {
+                i += 1;
+                continue;
+            }
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            ']' => Token::RBracket,
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '@' => Token::Current,
+            '[' => match next {
+                Some(']') => {
+                    i += 1;
+                    Token::Flatten
+                }
+                Some('?') => {
+                    i += 1;
+                    Token::Filter
+                }
+                _ => Token::LBracket,
+            },
+            '|' if next == Some('|') => {
+                i += 1;
+                Token::Or
+            }
+            '|' => Token::Pipe,
+            '&' if next == Some('&') => {
+                i += 1;
+                Token::And
+            }
+            '&' => Token::Expref,
+            '!' if next == Some('=') => {
+                i += 1;
+                Token::Compare(Comparator::Ne)
+            }
+            '!' => Token::Not,
+            '<' if next == Some('=') => {
+                i += 1;
+                Token::Compare(Comparator::Lte)
+            }
+            '<' => Token::Compare(Comparator::Lt),
+            '>' if next == Some('=') => {
+                i += 1;
+                Token::Compare(Comparator::Gte)
+            }
+            '>' => Token::Compare(Comparator::Gt),
+            '=' if next == Some('=') => {
+                i += 1;
+                Token::Compare(Comparator::Eq)
+            }
+            '=' => return Err(("unexpected '=', did you mean '=='?".to_string(), start)),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                while chars.get(i + 1).is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    i += 1;
+                }
+                Token::Identifier(chars[start..=i].iter().collect())
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                while chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+                    i += 1;
+                }
+                let text: String = chars[start..=i].iter().collect();
+                let number = text.parse().map_err(|_| (format!("invalid number {text:?}"), start))?;
+                Token::Number(number)
+            }
+            quote @ ('"' | '\'' | '`') => {
+                let text = delimited(&chars, &mut i, quote)?;
+                match quote {
+                    '"' => Token::QuotedIdentifier(
+                        serde_json::from_str(&format!("\"{text}\""))
+                            .map_err(|_| (format!("invalid quoted identifier \"{text}\""), start))?,
+                    ),
+                    '\'' => Token::RawString(text.replace("\\'", "'")),
+                    _ => Token::Literal(text.replace("\\`", "`")),
+                }
+            }
+            c => return Err((format!("unexpected character {c:?}"), start)),
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+    tokens.push((Token::Eof, chars.len()));
+    Ok(tokens)
+}
+
+/// The text between the quote at `chars[*i]` and its unescaped closing quote, which `*i` is left on.
+fn delimited(chars: &[char], i: &mut usize, quote: char) -> Result<String, LexError> {
+    let start = *i;
+    *i += 1;
+    while let Some(&c) = chars.get(*i) {
+        if c == quote {
+            return Ok(chars[start + 1..*i].iter().collect());
+        }
+        if c == '\\' {
+            *i += 1;
+        }
+        *i += 1;
+    }
+    Err((format!("unterminated {quote}"), start))
+}
+
+// Parser -------------------------------------------------------------------
+
+enum ParseError {
+    Syntax(String, usize),
+    Python(PyErr),
+}
+
+impl From<PyErr> for ParseError {
+    fn from(err: PyErr) -> Self {
+        ParseError::Python(err)
+    }
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+struct Parser<'py> {
+    py: Python<'py>,
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    depth: usize,
+}
+
+impl<'py> Parser<'py> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.index + offset).min(last)].0
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.index].0.clone();
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn unexpected(&self) -> ParseError {
+        let (token, pos) = &self.tokens[self.index];
+        let msg = match token {
+            Token::Eof => "unexpected end of expression".to_string(),
+            _ => "unexpected token".to_string(),
+        };
+        ParseError::Syntax(msg, *pos)
+    }
+
+    fn expect(&mut self, token: Token) -> ParseResult<()> {
+        if *self.peek() != token {
+            return Err(self.unexpected());
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn expression(&mut self, binding_power: u8) -> ParseResult<Ast> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(ParseError::Syntax("expression is nested too deeply".to_string(), self.tokens[self.index].1));
+        }
+        let token = self.advance();
+        let mut left = self.nud(token)?;
+        while binding_power < self.peek().binding_power() {
+            let token = self.advance();
+            left = self.led(token, left)?;
+        }
+        self.depth -= 1;
+        Ok(left)
+    }
+
+    fn nud(&mut self, token: Token) -> ParseResult<Ast> {
+        Ok(match token {
+            Token::Literal(text) => Ast::Literal(self.literal(&text)?),
+            Token::RawString(text) => Ast::Literal(PyString::new(self.py, &text).into_any().unbind()),
+            Token::Identifier(name) => Ast::Field(name),
+            Token::QuotedIdentifier(name) => {
+                if *self.peek() == Token::LParen {
+                    return Err(ParseError::Syntax(
+                        "quoted identifiers cannot be function names".to_string(),
+                        self.tokens[self.index].1,
+                    ));
+                }
+                Ast::Field(name)
+            }
+            Token::Star => {
+                let right = match self.peek() {
+                    Token::RBracket => Ast::Identity,
+                    _ => self.projection_rhs(Token::Star.binding_power())?,
+                };
+                Ast::ValueProjection(Box::new(Ast::Identity), Box::new(right))
+            }
+            Token::Filter => self.filter(Ast::Identity)?,
+            Token::LBrace => self.multi_select_hash()?,
+            Token::LParen => {
+                let expression = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                expression
+            }
+            Token::Flatten => {
+                let right = self.projection_rhs(Token::Flatten.binding_power())?;
+                Ast::Projection(Box::new(Ast::Flatten(Box::new(Ast::Identity))), Box::new(right))
+            }
+            Token::Not => Ast::Not(Box::new(self.expression(Token::Not.binding_power())?)),
+            Token::LBracket => match (self.peek(), self.peek_at(1)) {
+                (Token::Number(_) | Token::Colon, _) => {
+                    let index = self.index_expression()?;
+                    self.project_if_slice(Ast::Identity, index)?
+                }
+                (Token::Star, Token::RBracket) => {
+                    self.advance();
+                    self.advance();
+                    let right = self.projection_rhs(Token::Star.binding_power())?;
+                    Ast::Projection(Box::new(Ast::Identity), Box::new(right))
+                }
+                _ => self.multi_select_list()?,
+            },
+            Token::Current => Ast::Identity,
+            Token::Expref => Ast::ExpRef(Box::new(self.expression(Token::Expref.binding_power())?)),
+            _ => {
+                self.index -= 1;
+                return Err(self.unexpected());
+            }
+        })
+    }
+
+    fn led(&mut self, token: Token, left: Ast) -> ParseResult<Ast> {
+        Ok(match token {
+            Token::Dot => {
+                if *self.peek() == Token::Star {
+                    self.advance();
+                    let right = self.projection_rhs(Token::Dot.binding_power())?;
+                    Ast::ValueProjection(Box::new(left), Box::new(right))
+                } else {
+                    let right = self.dot_rhs(Token::Dot.binding_power())?;
+                    Ast::Subexpr(Box::new(left), Box::new(right))
+                }
+            }
+            Token::Pipe => Ast::Pipe(Box::new(left), Box::new(self.expression(Token::Pipe.binding_power())?)),
+            Token::Or => Ast::Or(Box::new(left), Box::new(self.expression(Token::Or.binding_power())?)),
+            Token::And => Ast::And(Box::new(left), Box::new(self.expression(Token::And.binding_power())?)),
+            Token::LParen => {
+                let Ast::Field(name) = left else {
+                    return Err(ParseError::Syntax(
+                        "invalid function name".to_string(),
+                        self.tokens[self.index - 1].1,
+                    ));
+                };
+                let name_pos = self.tokens[self.index.saturating_sub(2)].1;
+                let mut args = Vec::new();
+                while *self.peek() != Token::RParen {
+                    args.push(self.expression(0)?);
+                    if *self.peek() == Token::Comma {
+                        self.advance();
+                    } else if *self.peek() != Token::RParen {
+                        return Err(self.unexpected());
+                    }
+                }
+                self.expect(Token::RParen)?;
+                let function = Function::resolve(&name, args.len())
+                    .map_err(|msg| ParseError::Syntax(msg, name_pos))?;
+                Ast::Function(function, args)
+            }
+            Token::Filter => self.filter(left)?,
+            Token::Compare(comparator) => {
+                let right = self.expression(Token::Compare(comparator).binding_power())?;
+                Ast::Compare(comparator, Box::new(left), Box::new(right))
+            }
+            Token::Flatten => {
+                let right = self.projection_rhs(Token::Flatten.binding_power())?;
+                Ast::Projection(Box::new(Ast::Flatten(Box::new(left))), Box::new(right))
+            }
+            Token::LBracket => match self.peek() {
+                Token::Number(_) | Token::Colon => {
+                    let index = self.index_expression()?;
+                    self.project_if_slice(left, index)?
+                }
+                _ => {
+                    self.expect(Token::Star)?;
+                    self.expect(Token::RBracket)?;
+                    let right = self.projection_rhs(Token::Star.binding_power())?;
+                    Ast::Projection(Box::new(left), Box::new(right))
+                }
+            },
+            _ => {
+                self.index -= 1;
+                return Err(self.unexpected());
+            }
+        })
+    }
+
+    fn literal(&self, text: &str) -> ParseResult<Py<PyAny>> {
+        let position = self.tokens[self.index - 1].1;
+        parse_json(self.py, PyString::new(self.py, text).as_any())
+            .map_err(|_| ParseError::Syntax(format!("invalid JSON literal `{text}`"), position))
+    }
+
+    fn filter(&mut self, left: Ast) -> ParseResult<Ast> {
+        let condition = self.expression(0)?;
+        self.expect(Token::RBracket)?;
+        let right = match self.peek() {
+            Token::Flatten => Ast::Identity,
+            _ => self.projection_rhs(Token::Filter.binding_power())?,
+        };
+        Ok(Ast::FilterProjection(Box::new(left), Box::new(right), Box::new(condition)))
+    }
+
+    /// Parses `[n]` or `[start:end:step]` after the opening bracket.
+    fn index_expression(&mut self) -> ParseResult<Ast> {
+        if *self.peek() != Token::Colon && *self.peek_at(1) != Token::Colon {
+            let Token::Number(n) = self.advance() else {
+                self.index -= 1;
+                return Err(self.unexpected());
+            };
+            self.expect(Token::RBracket)?;
+            return Ok(Ast::Index(n));
+        }
+        let mut parts = [None; 3];
+        let mut part = 0;
+        loop {
+            match self.peek() {
+                Token::RBracket => break,
+                Token::Colon if part < 2 => {
+                    part += 1;
+                    self.advance();
+                }
+                Token::Number(n) if parts[part].is_none() => {
+                    parts[part] = Some(*n);
+                    self.advance();
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+        self.expect(Token::RBracket)?;
+        if parts[2] == Some(0) {
+            return Err(ParseError::Syntax("slice step cannot be 0".to_string(), self.tokens[self.index - 1].1));
+        }
+        Ok(Ast::Slice(parts[0], parts[1], parts[2]))
+    }
+
+    fn project_if_slice(&mut self, left: Ast, index: Ast) -> ParseResult<Ast> {
+        let is_slice = matches!(index, Ast::Slice(..));
+        let indexed = match left {
+            Ast::Identity => index,
+            left => Ast::Subexpr(Box::new(left), Box::new(index)),
+        };
+        if !is_slice {
+            return Ok(indexed);
+        }
+        let right = self.projection_rhs(Token::Star.binding_power())?;
+        Ok(Ast::Projection(Box::new(indexed), Box::new(right)))
+    }
+
+    fn multi_select_list(&mut self) -> ParseResult<Ast> {
+        let mut items = Vec::new();
+        loop {
+            items.push(self.expression(0)?);
+            if *self.peek() == Token::RBracket {
+                break;
+            }
+            self.expect(Token::Comma)?;
+        }
+        self.expect(Token::RBracket)?;
+        Ok(Ast::MultiList(items))
+    }
+
+    fn multi_select_hash(&mut self) -> ParseResult<Ast> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match self.advance() {
+                Token::Identifier(key) | Token::QuotedIdentifier(key) => key,
+                _ => {
+                    self.index -= 1;
+                    return Err(self.unexpected());
+                }
+            };
+            self.expect(Token::Colon)?;
+            pairs.push((key, self.expression(0)?));
+            match self.advance() {
+                Token::Comma => {}
+                Token::RBrace => break,
+                _ => {
+                    self.index -= 1;
+                    return Err(self.unexpected());
+                }
+            }
+        }
+        Ok(Ast::MultiHash(pairs))
+    }
+
+    fn projection_rhs(&mut self, binding_power: u8) -> ParseResult<Ast> {
+        match self.peek() {
+            token if token.binding_power() < PROJECTION_STOP => Ok(Ast::Identity),
+            Token::LBracket | Token::Filter => self.expression(binding_power),
+            Token::Dot => {
+                self.advance();
+                self.dot_rhs(binding_power)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn dot_rhs(&mut self, binding_power: u8) -> ParseResult<Ast> {
+        match self.peek() {
+            Token::Identifier(_) | Token::QuotedIdentifier(_) | Token::Star => self.expression(binding_power),
+            Token::LBracket => {
+                self.advance();
+                self.multi_select_list()
+            }
+            Token::LBrace => {
+                self.advance();
+                self.multi_select_hash()
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+}
+
+// Interpreter --------------------------------------------------------------
+
+/// Evaluates `ast` against `value`.
+pub(super) fn search<'py>(ast: &Ast, value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = value.py();
+    let none = || py.None().into_bound(py);
+    Ok(match ast {
+        Ast::Identity => value.clone(),
+        Ast::Field(name) => match value.downcast::<PyDict>() {
+            Ok(dict) => dict.get_item(name)?.unwrap_or_else(none),
+            Err(_) => none(),
+        },
+        Ast::Index(index) => match elements(value) {
+            Some(items) => {
+                let len = items.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                match (0..len).contains(&index) {
+                    true => items[index as usize].clone(),
+                    false => none(),
+                }
+            }
+            None => none(),
+        },
+        Ast::Slice(start, end, step) => match elements(value) {
+            Some(items) => {
+                let indices = slice_indices(items.len(), *start, *end, step.unwrap_or(1));
+                PyList::new(py, indices.into_iter().map(|i| &items[i]))?.into_any()
+            }
+            None => none(),
+        },
+        Ast::Subexpr(left, right) => search(right, &search(left, value)?)?,
+        Ast::Projection(left, right) => match elements(&search(left, value)?) {
+            Some(items) => project(py, items, right)?,
+            None => none(),
+        },
+        Ast::ValueProjection(left, right) => match search(left, value)?.downcast::<PyDict>() {
+            Ok(dict) => project(py, dict.values().iter().collect(), right)?,
+            Err(_) => none(),
+        },
+        Ast::FilterProjection(left, right, condition) => match elements(&search(left, value)?) {
+            Some(items) => {
+                let mut matching = Vec::new();
+                for item in items {
+                    if is_truthy(&search(condition, &item)?) {
+                        matching.push(item);
+                    }
+                }
+                project(py, matching, right)?
+            }
+            None => none(),
+        },
+        Ast::Flatten(child) => match elements(&search(child, value)?) {
+            Some(items) => {
+                let mut flat = Vec::with_capacity(items.len());
+                for item in items {
+                    match elements(&item) {
+                        Some(inner) => flat.extend(inner),
+                        None => flat.push(item),
+                    }
+                }
+                PyList::new(py, flat)?.into_any()
+            }
+            None => none(),
+        },
+        Ast::Pipe(left, right) => search(right, &search(left, value)?)?,
+        Ast::MultiList(items) => {
+            if value.is_none() {
+                return Ok(none());
+            }
+            let values = items.iter().map(|item| search(item, value)).collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, values)?.into_any()
+        }
+        Ast::MultiHash(pairs) => {
+            if value.is_none() {
+                return Ok(none());
+            }
+            let dict = PyDict::new(py);
+            for (key, item) in pairs {
+                dict.set_item(key, search(item, value)?)?;
+            }
+            dict.into_any()
+        }
+        Ast::Literal(literal) => literal.bind(py).clone(),
+        Ast::Not(child) => PyBool::new(py, !is_truthy(&search(child, value)?)).to_owned().into_any(),
+        Ast::And(left, right) => {
+            let left = search(left, value)?;
+            match is_truthy(&left) {
+                true => search(right, value)?,
+                false => left,
+            }
+        }
+        Ast::Or(left, right) => {
+            let left = search(left, value)?;
+            match is_truthy(&left) {
+                true => left,
+                false => search(right, value)?,
+            }
+        }
+        Ast::Compare(comparator, left, right) => {
+            compare(*comparator, &search(left, value)?, &search(right, value)?)?
+        }
+        Ast::Function(function, args) => function.call(args, value)?,
+        Ast::ExpRef(_) => {
+            return Err(PyTypeError::new_err("expression references (&expr) are only valid as function arguments"))
+        }
+    })
+}
+
+fn project<'py>(py: Python<'py>, items: Vec<Bound<'py, PyAny>>, right: &Ast) -> PyResult<Bound<'py, PyAny>> {
+    let mut collected = Vec::with_capacity(items.len());
+    for item in items {
+        let result = search(right, &item)?;
+        if !result.is_none() {
+            collected.push(result);
+        }
+    }
+    Ok(PyList::new(py, collected)?.into_any())
+}
+
+/// JMESPath truthiness: empty strings, arrays and objects, `false` and `null` are false; every number is true.
+fn is_truthy(value: &Bound<'_, PyAny>) -> bool {
+    match kind(value) {
+        Kind::Null => false,
+        Kind::Bool => value.is_truthy().unwrap_or(false),
+        Kind::Number | Kind::Other => true,
+        Kind::String | Kind::Array | Kind::Object => value.len().map(|len| len > 0).unwrap_or(true),
+    }
+}
+
+fn compare<'py>(comparator: Comparator, a: &Bound<'py, PyAny>, b: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = a.py();
+    let result = match comparator {
+        Comparator::Eq => json_eq(a, b)?,
+        Comparator::Ne => !json_eq(a, b)?,
+        ordering => {
+            // Ordering is only defined between numbers; anything else gives null.
+            if kind(a) != Kind::Number || kind(b) != Kind::Number {
+                return Ok(py.None().into_bound(py));
+            }
+            match ordering {
+                Comparator::Lt => a.lt(b)?,
+                Comparator::Lte => a.le(b)?,
+                Comparator::Gt => a.gt(b)?,
+                _ => a.ge(b)?,
+            }
+        }
+    };
+    Ok(PyBool::new(py, result).to_owned().into_any())
+}
+
+// Functions ----------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+pub(super) enum Function {
+    Abs,
+    Avg,
+    Ceil,
+    Contains,
+    EndsWith,
+    Floor,
+    Join,
+    Keys,
+    Length,
+    Map,
+    Max,
+    MaxBy,
+    Merge,
+    Min,
+    MinBy,
+    NotNull,
+    Reverse,
+    Sort,
+    SortBy,
+    StartsWith,
+    Sum,
+    ToArray,
+    ToNumber,
+    ToString,
+    Type,
+    Values,
+}
+
+impl Function {
+    /// Looks a function up by name and checks how many arguments it was given.
+    fn resolve(name: &str, args: usize) -> Result<Function, String> {
+        use Function::*;
+        let (function, min, variadic) = match name {
+            "abs" => (Abs, 1, false),
+            "avg" => (Avg, 1, false),
+            "ceil" => (Ceil, 1, false),
+            "contains" => (Contains, 2, false),
+            "ends_with" => (EndsWith, 2, false),
+            "floor" => (Floor, 1, false),
+            "join" => (Join, 2, false),
+            "keys" => (Keys, 1, false),
+            "length" => (Length, 1, false),
+            "map" => (Map, 2, false),
+            "max" => (Max, 1, false),
+            "max_by" => (MaxBy, 2, false),
+            "merge" => (Merge, 1, true),
+            "min" => (Min, 1, false),
+            "min_by" => (MinBy, 2, false),
+            "not_null" => (NotNull, 1, true),
+            "reverse" => (Reverse, 1, false),
+            "sort" => (Sort, 1, false),
+            "sort_by" => (SortBy, 2, false),
+            "starts_with" => (StartsWith, 2, false),
+            "sum" => (Sum, 1, false),
+            "to_array" => (ToArray, 1, false),
+            "to_number" => (ToNumber, 1, false),
+            "to_string" => (ToString, 1, false),
+            "type" => (Type, 1, false),
+            "values" => (Values, 1, false),
+            _ => return Err(format!("unknown function {name}()")),
+        };
+        if args < min || (!variadic && args > min) {
+            let expected = if variadic { format!("at least {min}") } else { min.to_string() };
+            return Err(format!("{name}() takes {expected} argument(s) but {args} were given"));
+        }
+        Ok(function)
+    }
+
+    fn name(self) -> &'static str {
+        use Function::*;
+        match self {
+            Abs => "abs",
+            Avg => "avg",
+            Ceil => "ceil",
+            Contains => "contains",
+            EndsWith => "ends_with",
+            Floor => "floor",
+            Join => "join",
+            Keys => "keys",
+            Length => "length",
+            Map => "map",
+            Max => "max",
+            MaxBy => "max_by",
+            Merge => "merge",
+            Min => "min",
+            MinBy => "min_by",
+            NotNull => "not_null",
+            Reverse => "reverse",
+            Sort => "sort",
+            SortBy => "sort_by",
+            StartsWith => "starts_with",
+            Sum => "sum",
+            ToArray => "to_array",
+            ToNumber => "to_number",
+            ToString => "to_string",
+            Type => "type",
+            Values => "values",
+        }
+    }
+
+    fn call<'py>(self, args: &[Ast], current: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        use Function::*;
+        let py = current.py();
+        let none = || py.None().into_bound(py);
+
+        // Functions taking an expression reference evaluate it per element themselves.
+        if let Map | MaxBy | MinBy | SortBy = self {
+            let (expr, array) = match self {
+                Map => (&args[0], &args[1]),
+                _ => (&args[1], &args[0]),
+            };
+            let Ast::ExpRef(expr) = expr else {
+                return Err(self.type_error("an expression reference (&expr)", "a value"));
+            };
+            let items = self.array(&search(array, current)?)?;
+            return match self {
+                Map => {
+                    let mapped = items.iter().map(|item| search(expr, item)).collect::<PyResult<Vec<_>>>()?;
+                    Ok(PyList::new(py, mapped)?.into_any())
+                }
+                _ => {
+                    let keys = items.iter().map(|item| search(expr, item)).collect::<PyResult<Vec<_>>>()?;
+                    let order = self.sorted_order(&keys)?;
+                    Ok(match self {
+                        SortBy => PyList::new(py, order.iter().map(|&i| &items[i]))?.into_any(),
+                        MaxBy => order.last().map_or_else(none, |&i| items[i].clone()),
+                        _ => order.first().map_or_else(none, |&i| items[i].clone()),
+                    })
+                }
+            };
+        }
+
+        let args = args.iter().map(|arg| search(arg, current)).collect::<PyResult<Vec<_>>>()?;
+        let arg = &args[0];
+        Ok(match self {
+            Abs => self.number(arg)?.call_method0("__abs__")?,
+            Ceil | Floor => {
+                let name = if let Ceil = self { "ceil" } else { "floor" };
+                py.import("math")?.call_method1(name, (self.number(arg)?,))?
+            }
+            Avg | Sum => {
+                let items = self.array(arg)?;
+                let mut total = PyInt::new(py, 0).into_any();
+                for item in &items {
+                    total = total.add(self.element(item, Kind::Number, "an array of numbers")?)?;
+                }
+                match self {
+                    Sum => total,
+                    _ if items.is_empty() => none(),
+                    _ => total.div(items.len())?,
+                }
+            }
+            Contains => {
+                let found = match kind(arg) {
+                    Kind::Array => {
+                        let mut found = false;
+                        for item in self.array(arg)? {
+                            if json_eq(&item, &args[1])? {
+                                found = true;
+                                break;
+                            }
+                        }
+                        found
+                    }
+                    Kind::String => match args[1].downcast::<PyString>() {
+                        Ok(needle) => arg.downcast::<PyString>()?.to_str()?.contains(needle.to_str()?),
+                        Err(_) => false,
+                    },
+                    _ => return Err(self.type_error("an array or a string", &type_name(arg))),
+                };
+                PyBool::new(py, found).to_owned().into_any()
+            }
+            StartsWith | EndsWith => {
+                let (subject, affix) = (self.string(arg)?, self.string(&args[1])?);
+                let result = match self {
+                    StartsWith => subject.starts_with(affix),
+                    _ => subject.ends_with(affix),
+                };
+                PyBool::new(py, result).to_owned().into_any()
+            }
+            Join => {
+                let glue = self.string(arg)?;
+                let parts = self
+                    .array(&args[1])?
+                    .iter()
+                    .map(|item| self.element(item, Kind::String, "an array of strings")?.extract::<String>())
+                    .collect::<PyResult<Vec<_>>>()?;
+                PyString::new(py, &parts.join(glue)).into_any()
+            }
+            Keys => PyList::new(py, self.object(arg)?.keys())?.into_any(),
+            Values => PyList::new(py, self.object(arg)?.values())?.into_any(),
+            Length => match kind(arg) {
+                Kind::String | Kind::Array | Kind::Object => PyInt::new(py, arg.len()? as i64).into_any(),
+                _ => return Err(self.type_error("a string, an array or an object", &type_name(arg))),
+            },
+            Max | Min | Sort => {
+                let items = self.array(arg)?;
+                let order = self.sorted_order(&items)?;
+                match self {
+                    Sort => PyList::new(py, order.iter().map(|&i| &items[i]))?.into_any(),
+                    Max => order.last().map_or_else(none, |&i| items[i].clone()),
+                    _ => order.first().map_or_else(none, |&i| items[i].clone()),
+                }
+            }
+            Merge => {
+                let merged = PyDict::new(py);
+                for arg in &args {
+                    merged.update(self.object(arg)?.as_mapping())?;
+                }
+                merged.into_any()
+            }
+            NotNull => args.iter().find(|arg| !arg.is_none()).cloned().unwrap_or_else(none),
+            Reverse => match kind(arg) {
+                Kind::Array => {
+                    let mut items = self.array(arg)?;
+                    items.reverse();
+                    PyList::new(py, items)?.into_any()
+                }
+                Kind::String => PyString::new(py, &self.string(arg)?.chars().rev().collect::<String>()).into_any(),
+                _ => return Err(self.type_error("an array or a string", &type_name(arg))),
+            },
+            ToArray => match kind(arg) {
+                Kind::Array => arg.clone(),
+                _ => PyList::new(py, [arg])?.into_any(),
+            },
+            ToNumber => match kind(arg) {
+                Kind::Number => arg.clone(),
+                Kind::String => {
+                    let text = self.string(arg)?;
+                    match number_kind(text.as_bytes()) {
+                        Some(true) => PyFloat::new(py, text.parse().unwrap_or(f64::NAN)).into_any(),
+                        Some(false) => py.get_type::<PyInt>().call1((text,))?,
+                        None => none(),
+                    }
+                }
+                _ => none(),
+            },
+            ToString => match kind(arg) {
+                Kind::String => arg.clone(),
+                _ => {
+                    let opts = SerializeOptions::new(false, false, None, false, false, true);
+                    let separators = (",".to_string(), ":".to_string());
+                    PyString::new(py, &to_json(arg.clone(), None, &separators, &opts)?).into_any()
+                }
+            },
+            Type => PyString::new(py, &type_name(arg)).into_any(),
+            Map | MaxBy | MinBy | SortBy => unreachable!("handled above"),
+        })
+    }
+
+    /// Indices of `keys` in ascending order (stable), which must be all numbers or all strings.
+    fn sorted_order(self, keys: &[Bound<'_, PyAny>]) -> PyResult<Vec<usize>> {
+        let expected = "an array of numbers or an array of strings";
+        let first = keys.first().map(kind);
+        for key in keys {
+            let key_kind = kind(key);
+            if !matches!(key_kind, Kind::Number | Kind::String) || Some(key_kind) != first {
+                return Err(self.type_error(expected, &type_name(key)));
+            }
+        }
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        let mut error = None;
+        order.sort_by(|&a, &b| match keys[a].compare(&keys[b]) {
+            Ok(ordering) => ordering,
+            Err(err) => {
+                error.get_or_insert(err);
+                Ordering::Equal
+            }
+        });
+        match error {
+            Some(err) => Err(err),
+            None => Ok(order),
+        }
+    }
+
+    fn type_error(self, expected: &str, got: &str) -> PyErr {
+        PyTypeError::new_err(format!("{}() expected {expected}, got {got}", self.name()))
+    }
+
+    fn number<'a, 'py>(self, value: &'a Bound<'py, PyAny>) -> PyResult<&'a Bound<'py, PyAny>> {
+        self.element(value, Kind::Number, "a number")
+    }
+
+    fn string<'a>(self, value: &'a Bound<'_, PyAny>) -> PyResult<&'a str> {
+        match value.downcast::<PyString>() {
+            Ok(s) if kind(value) == Kind::String => s.to_str(),
+            _ => Err(self.type_error("a string", &type_name(value))),
+        }
+    }
+
+    fn array<'py>(self, value: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        elements(value).ok_or_else(|| self.type_error("an array", &type_name(value)))
+    }
+
+    fn object<'a, 'py>(self, value: &'a Bound<'py, PyAny>) -> PyResult<&'a Bound<'py, PyDict>> {
+        value.downcast::<PyDict>().map_err(|_| self.type_error("an object", &type_name(value)))
+    }
+
+    fn element<'a, 'py>(self, value: &'a Bound<'py, PyAny>, expected: Kind, what: &str) -> PyResult<&'a Bound<'py, PyAny>> {
+        match kind(value) == expected {
+            true => Ok(value),
+            false => Err(self.type_error(what, &type_name(value))),
+        }
+    }
+}
+
+/// The JMESPath name of a value's type, or its Python type name for non-JSON values.
+fn type_name(value: &Bound<'_, PyAny>) -> String {
+    match kind(value) {
+        Kind::Null => "null".to_string(),
+        Kind::Bool => "boolean".to_string(),
+        Kind::Number => "number".to_string(),
+        Kind::String => "string".to_string(),
+        Kind::Array => "array".to_string(),
+        Kind::Object => "object".to_string(),
+        Kind::Other => value.get_type().name().map(|n| n.to_string()).unwrap_or_default(),
+    }
+}
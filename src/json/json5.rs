@@ -0,0 +1,462 @@
+//! JSON5 parser for hand-written configuration files.
+//!
+//! JSON5 is a superset of JSON: it adds comments, trailing commas, single-quoted strings,
+//! unquoted keys, hexadecimal numbers and `Infinity`/`NaN`. Values are built as the same
+//! Python types `parse_json` returns, and malformed input raises `JSONDecodeError` with the
+//! position of the problem.
+
+use pyo3::exceptions::PyRecursionError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString};
+
+use super::decoder::{DecodeError, DecodeResult, Unescaped, MAX_DEPTH};
+use super::{cached_key, raise_decode_error, with_json_bytes};
+
+/// Parses a JSON5 document into the corresponding Python object.
+///
+/// Every JSON document is valid JSON5, so this also reads plain JSON, just more slowly than
+/// `parse_json`. On top of JSON it accepts `//` and `/* */` comments, a trailing comma after
+/// the last array item or object member, single-quoted strings, unquoted (identifier) keys,
+/// hexadecimal ints such as `0xFF`, numbers with a leading `+` or a leading or trailing
+/// decimal point, `Infinity` and `NaN`, and strings continued over several lines with a
+/// trailing backslash.
+///
+/// # Arguments
+/// * `json_str` - The JSON5 document as `str`, `bytes`, `bytearray` or `memoryview`
+///
+/// # Returns
+/// * A Python object representing the parsed data; ints of any size stay exact
+///
+/// # Raises
+/// * `JSONDecodeError` - If the input is not valid JSON5, carrying `msg`, `doc`, `pos`, `lineno` and `colno`
+/// * `TypeError` - If the input is not a `str` or a bytes-like object
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// config = fastpy_rs.json.parse_json5('''
+/// {
+///     // Served from the edge cache
+///     host: 'example.com',
+///     port: 0x1F90,
+///     retries: [1, 2, 4,],
+/// }
+/// ''')
+/// print(config)  # Output: {'host': 'example.com', 'port': 8080, 'retries': [1, 2, 4]}
+/// ```
+#[pyfunction]
+pub fn parse_json5<'py>(json_str: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = json_str.py();
+    with_json_bytes(json_str, |bytes| {
+        let input = std::str::from_utf8(bytes)
+            .map_err(|e| raise_decode_error(json_str, bytes, "Invalid UTF-8 at", e.valid_up_to()))?;
+        let mut parser = Parser { py, input, pos: 0, depth: 0 };
+        parser.document().map_err(|err| match err {
+            DecodeError::Syntax(msg, offset) => raise_decode_error(json_str, bytes, msg, offset),
+            DecodeError::Python(err) => err,
+        })
+    })?
+}
+
+struct Parser<'a, 'py> {
+    py: Python<'py>,
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a, 'py> Parser<'a, 'py> {
+    fn document(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        self.skip_trivia()?;
+        let value = self.value()?;
+        self.skip_trivia()?;
+        if self.pos != self.input.len() {
+            return Err(DecodeError::Syntax("Extra data", self.pos));
+        }
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.input.as_bytes()[self.pos..]
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_trivia(&mut self) -> DecodeResult<()> {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c) => self.pos += 1,
+                Some(b'/') if self.rest().starts_with(b"//") => {
+                    self.pos = self.input[self.pos..]
+                        .find(['\n', '\r', '\u{2028}', '\u{2029}'])
+                        .map_or(self.input.len(), |end| self.pos + end);
+                }
+                Some(b'/') if self.rest().starts_with(b"/*") => match self.input[self.pos + 2..].find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(DecodeError::Syntax("Unterminated comment starting at", self.pos)),
+                },
+                Some(0x80..) => match self.peek_char() {
+                    Some(ch) if is_space(ch) => self.pos += ch.len_utf8(),
+                    _ => return Ok(()),
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        match self.peek() {
+            Some(b'"' | b'\'') => Ok(self.string()?.into_any()),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'-' | b'+' | b'.' | b'0'..=b'9' | b'I' | b'N') => self.number(),
+            Some(b'n') if self.rest().starts_with(b"null") => {
+                self.pos += 4;
+                Ok(self.py.None().into_bound(self.py))
+            }
+            Some(b't') if self.rest().starts_with(b"true") => {
+                self.pos += 4;
+                Ok(PyBool::new(self.py, true).to_owned().into_any())
+            }
+            Some(b'f') if self.rest().starts_with(b"false") => {
+                self.pos += 5;
+                Ok(PyBool::new(self.py, false).to_owned().into_any())
+            }
+            _ => Err(DecodeError::Syntax("Expecting value", self.pos)),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> DecodeResult<Bound<'py, PyAny>>) -> DecodeResult<Bound<'py, PyAny>> {
+        if self.depth >= MAX_DEPTH {
+            return Err(PyRecursionError::new_err("maximum recursion depth exceeded while decoding a JSON5 document").into());
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn number(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        let start = self.pos;
+        let negative = self.peek() == Some(b'-');
+        if let Some(b'-' | b'+') = self.peek() {
+            self.pos += 1;
+        }
+        for (name, value) in [("Infinity", f64::INFINITY), ("NaN", f64::NAN)] {
+            if self.rest().starts_with(name.as_bytes()) {
+                self.pos += name.len();
+                let value = if negative { -value } else { value };
+                return Ok(PyFloat::new(self.py, value).into_any());
+            }
+        }
+        if let Some(b"0x" | b"0X") = self.rest().get(..2) {
+            return self.hex_number(start, negative);
+        }
+
+        let int_start = self.pos;
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            Some(b'.') => {}
+            _ => return Err(DecodeError::Syntax("Expecting value", start)),
+        }
+        if self.pos > int_start && self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            return Err(DecodeError::Syntax("Leading zeros are not allowed", int_start));
+        }
+        let mut is_float = false;
+        if self.peek() == Some(b'.') {
+            is_float = true;
+            self.pos += 1;
+            let fraction = self.pos;
+            self.skip_digits();
+            if fraction == int_start + 1 && self.pos == fraction {
+                return Err(DecodeError::Syntax("Expecting value", start));
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            is_float = true;
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if !self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                return Err(DecodeError::Syntax("Expecting exponent digits", self.pos));
+            }
+            self.skip_digits();
+        }
+
+        let text = &self.input[start..self.pos];
+        if is_float {
+            let value: f64 = text.parse().unwrap_or(f64::NAN);
+            return Ok(PyFloat::new(self.py, value).into_any());
+        }
+        match text.parse::<i64>() {
+            Ok(value) => Ok(PyInt::new(self.py, value).into_any()),
+            // Too big for 64 bits: let Python build the arbitrary-precision int.
+            Err(_) => Ok(self.py.get_type::<PyInt>().call1((text,))?),
+        }
+    }
+
+    fn hex_number(&mut self, start: usize, negative: bool) -> DecodeResult<Bound<'py, PyAny>> {
+        self.pos += 2;
+        let digits_start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_hexdigit()) {
+            self.pos += 1;
+        }
+        if self.pos == digits_start {
+            return Err(DecodeError::Syntax("Expecting hexadecimal digits", start));
+        }
+        let digits = &self.input[digits_start..self.pos];
+        if let Ok(value) = i64::from_str_radix(digits, 16) {
+            return Ok(PyInt::new(self.py, if negative { -value } else { value }).into_any());
+        }
+        let value = self.py.get_type::<PyInt>().call1((digits, 16))?;
+        Ok(if negative { value.neg()? } else { value })
+    }
+
+    fn skip_digits(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+    }
+
+    fn string(&mut self) -> DecodeResult<Bound<'py, PyString>> {
+        let (text, escaped) = self.string_contents()?;
+        Ok(match escaped {
+            Some(text) => text.into_pystring(self.py)?,
+            None => PyString::new(self.py, text),
+        })
+    }
+
+    /// Scans a single- or double-quoted string starting at the opening quote.
+    ///
+    /// Strings without escapes are returned as a borrowed slice of the input; the rest
+    /// are unescaped into an owned buffer.
+    fn string_contents(&mut self) -> DecodeResult<(&'a str, Option<Unescaped>)> {
+        let open = self.pos;
+        let quote = self.input.as_bytes()[open];
+        self.pos += 1;
+        let start = self.pos;
+        let mut out: Option<Unescaped> = None;
+        loop {
+            let chunk = self.pos;
+            while let Some(b) = self.peek() {
+                if b == quote || b == b'\\' || b == b'\n' || b == b'\r' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            if let Some(out) = &mut out {
+                out.push_str(&self.input[chunk..self.pos]);
+            }
+            match self.peek() {
+                None => return Err(DecodeError::Syntax("Unterminated string starting at", open)),
+                Some(b'\\') => {
+                    let out = out.get_or_insert_with(|| {
+                        let mut out = Unescaped::String(String::with_capacity(self.pos - start + 16));
+                        out.push_str(&self.input[start..self.pos]);
+                        out
+                    });
+                    self.escape(out, open)?;
+                }
+                Some(b'\n' | b'\r') => return Err(DecodeError::Syntax("Unescaped line break in string", self.pos)),
+                Some(_) => {
+                    let text = &self.input[start..self.pos];
+                    self.pos += 1;
+                    return Ok((text, out));
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self, out: &mut Unescaped, open: usize) -> DecodeResult<()> {
+        let escape = self.pos;
+        let Some(next) = self.input[escape + 1..].chars().next() else {
+            return Err(DecodeError::Syntax("Unterminated string starting at", open));
+        };
+        self.pos = escape + 1 + next.len_utf8();
+        let ch = match next {
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{b}',
+            '0' if !self.peek().is_some_and(|b| b.is_ascii_digit()) => '\0',
+            '0'..='9' => return Err(DecodeError::Syntax("Invalid \\escape", escape)),
+            'x' => {
+                let code = hex_digits(self.input, escape + 2, 2)?;
+                self.pos = escape + 4;
+                out.push_code_point(code);
+                return Ok(());
+            }
+            'u' => {
+                let unit = hex_digits(self.input, escape + 2, 4)?;
+                self.pos = escape + 6;
+                if (0xd800..0xdc00).contains(&unit) && self.rest().starts_with(b"\\u") {
+                    let low = hex_digits(self.input, self.pos + 2, 4)?;
+                    if (0xdc00..0xe000).contains(&low) {
+                        self.pos += 6;
+                        out.push_code_point(0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00));
+                        return Ok(());
+                    }
+                }
+                out.push_code_point(unit);
+                return Ok(());
+            }
+            // A backslash before a line break continues the string on the next line.
+            '\r' => {
+                if self.peek() == Some(b'\n') {
+                    self.pos += 1;
+                }
+                return Ok(());
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => return Ok(()),
+            other => other,
+        };
+        out.push_code_point(ch as u32);
+        Ok(())
+    }
+
+    fn key(&mut self) -> DecodeResult<Bound<'py, PyString>> {
+        if let Some(b'"' | b'\'') = self.peek() {
+            let (text, escaped) = self.string_contents()?;
+            return Ok(match escaped {
+                Some(text) => text.into_pystring(self.py)?,
+                None => cached_key(self.py, text),
+            });
+        }
+        self.identifier()
+    }
+
+    /// Reads an unquoted key: an ECMAScript 5.1 identifier name, `\uXXXX` escapes included.
+    fn identifier(&mut self) -> DecodeResult<Bound<'py, PyString>> {
+        let start = self.pos;
+        let mut escaped: Option<String> = None;
+        loop {
+            let at = self.pos;
+            let (ch, len) = match self.peek_char() {
+                Some('\\') => {
+                    if self.input.as_bytes().get(at + 1) != Some(&b'u') {
+                        return Err(DecodeError::Syntax("Invalid \\escape", at));
+                    }
+                    let code = hex_digits(self.input, at + 2, 4)?;
+                    match char::from_u32(code) {
+                        Some(ch) => (ch, 6),
+                        None => return Err(DecodeError::Syntax("Invalid identifier character at", at)),
+                    }
+                }
+                Some(ch) => (ch, ch.len_utf8()),
+                None => break,
+            };
+            let valid = if at == start { is_identifier_start(ch) } else { is_identifier_part(ch) };
+            if !valid {
+                if len == 6 {
+                    return Err(DecodeError::Syntax("Invalid identifier character at", at));
+                }
+                break;
+            }
+            if len == 6 && escaped.is_none() {
+                escaped = Some(self.input[start..at].to_string());
+            }
+            if let Some(escaped) = &mut escaped {
+                escaped.push(ch);
+            }
+            self.pos += len;
+        }
+        if self.pos == start {
+            return Err(DecodeError::Syntax("Expecting property name", start));
+        }
+        Ok(match escaped {
+            Some(name) => PyString::new(self.py, &name),
+            None => cached_key(self.py, &self.input[start..self.pos]),
+        })
+    }
+
+    fn object(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        self.pos += 1;
+        let dict = PyDict::new(self.py);
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(dict.into_any());
+            }
+            let key = self.key()?;
+            self.skip_trivia()?;
+            if self.peek() != Some(b':') {
+                return Err(DecodeError::Syntax("Expecting ':' delimiter", self.pos));
+            }
+            self.pos += 1;
+            self.skip_trivia()?;
+            let value = self.value()?;
+            dict.set_item(key, value)?;
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(dict.into_any());
+                }
+                _ => return Err(DecodeError::Syntax("Expecting ',' delimiter", self.pos)),
+            }
+        }
+    }
+
+    fn array(&mut self) -> DecodeResult<Bound<'py, PyAny>> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                break;
+            }
+            items.push(self.value()?);
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return Err(DecodeError::Syntax("Expecting ',' delimiter", self.pos)),
+            }
+        }
+        Ok(PyList::new(self.py, items)?.into_any())
+    }
+}
+
+/// Reads `count` hex digits at `at`, the start of an escape's digits.
+fn hex_digits(input: &str, at: usize, count: usize) -> DecodeResult<u32> {
+    input
+        .get(at..at + count)
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+        .ok_or(DecodeError::Syntax("Invalid hexadecimal escape", at - 2))
+}
+
+/// JSON5 whitespace beyond ASCII: the Unicode space separators, NBSP, BOM and the line and
+/// paragraph separators.
+fn is_space(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{a0}' | '\u{feff}' | '\u{1680}' | '\u{2000}'..='\u{200a}' | '\u{2028}' | '\u{2029}' | '\u{202f}' | '\u{205f}' | '\u{3000}'
+    )
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch == '$' || ch == '_' || ch.is_alphabetic()
+}
+
+fn is_identifier_part(ch: char) -> bool {
+    is_identifier_start(ch) || ch.is_alphanumeric() || ch == '\u{200c}' || ch == '\u{200d}'
+}
@@ -0,0 +1,246 @@
+//! JSON Lines (NDJSON): one JSON document per line.
+//!
+//! Reading happens in batches of whole lines with the GIL released; only turning a
+//! line into Python objects needs the interpreter.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyString};
+
+use super::decoder::DecodeError;
+use super::{
+    char_count, parse_error_location, parse_slice, relocated_decode_error, with_json_bytes, write_json, SerializeOptions,
+    UTF8_BOM,
+};
+
+/// How much input is read (and split into lines) per GIL release.
+const BATCH_BYTES: usize = 1 << 20;
+
+/// How much serialized output is buffered before it is written out with the GIL released.
+const FLUSH_BYTES: usize = 1 << 20;
+
+/// Parses a JSON Lines document: one JSON value per line.
+///
+/// Blank lines are skipped and a UTF-8 BOM at the start is ignored. The input is read and
+/// split into lines with the GIL released, in batches, so even with `lazy=True` the
+/// per-record cost is a single parse.
+///
+/// # Arguments
+/// * `source` - The document as `bytes`/`bytearray`/`memoryview`, or a path (`str` or `os.PathLike`) to read it from
+/// * `lazy` - Return an iterator that reads the input batch by batch instead of a list of every record
+///
+/// # Returns
+/// * A list of the parsed values, or an iterator over them with `lazy=True`
+///
+/// # Raises
+/// * `JSONDecodeError` - If a line is not valid JSON; `lineno` is the line in the input and `doc` that line's text
+/// * `OSError` - If the file cannot be read
+/// * `TypeError` - If `source` is neither bytes-like nor a path
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// records = fastpy_rs.json.parse_jsonl(b'{"id": 1}\n{"id": 2}\n')
+/// print(records)  # Output: [{'id': 1}, {'id': 2}]
+///
+/// # Stream a large file without holding every record in memory
+/// for event in fastpy_rs.json.parse_jsonl("events.jsonl", lazy=True):
+///     print(event["type"])
+/// ```
+#[pyfunction]
+#[pyo3(signature = (source, *, lazy = false))]
+pub fn parse_jsonl(py: Python<'_>, source: &Bound<'_, PyAny>, lazy: bool) -> PyResult<PyObject> {
+    let mut reader = JsonLinesReader::open(source)?;
+    if lazy {
+        return Ok(Py::new(py, reader)?.into_any());
+    }
+    let mut values = Vec::new();
+    while let Some(value) = reader.next_value(py)? {
+        values.push(value);
+    }
+    Ok(PyList::new(py, values)?.into_any().unbind())
+}
+
+/// Writes every item of `iterable` as one compact JSON document per line.
+///
+/// Items are serialized exactly like `serialize_json` does; the output is buffered and
+/// written to the file with the GIL released.
+///
+/// # Arguments
+/// * `iterable` - Any iterable of serializable objects (a generator works too)
+/// * `path` - Where to write, as `str` or `os.PathLike`; an existing file is overwritten
+/// * `sort_keys` - Write dictionary keys in sorted order
+/// * `ensure_ascii` - Escape every non-ASCII character as `\uXXXX`
+/// * `default` - Called with any object that cannot be serialized; must return a serializable replacement
+///
+/// # Returns
+/// * The number of lines written
+///
+/// # Raises
+/// * `ValueError` - If an item cannot be serialized; the lines before it have already been written
+/// * `OSError` - If the file cannot be written
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// count = fastpy_rs.json.dump_jsonl(({"id": i} for i in range(3)), "ids.jsonl")
+/// print(count)  # Output: 3
+/// ```
+#[pyfunction]
+#[pyo3(signature = (iterable, path, *, sort_keys = false, ensure_ascii = false, default = None))]
+pub fn dump_jsonl<'py>(
+    py: Python<'py>,
+    iterable: &Bound<'py, PyAny>,
+    path: PathBuf,
+    sort_keys: bool,
+    ensure_ascii: bool,
+    default: Option<Bound<'py, PyAny>>,
+) -> PyResult<usize> {
+    let opts = SerializeOptions::new(sort_keys, ensure_ascii, default, false, false, true);
+    let separators = (",".to_string(), ":".to_string());
+    let mut file = py.allow_threads(|| File::create(&path))?;
+    let mut buf = Vec::with_capacity(FLUSH_BYTES);
+    let mut count = 0;
+    let mut result = Ok(());
+
+    for item in iterable.try_iter()? {
+        let start = buf.len();
+        if let Err(err) = item.and_then(|item| write_json(&mut buf, item, None, &separators, &opts)) {
+            // Drop the half-written record but keep every complete line before it.
+            buf.truncate(start);
+            result = Err(err);
+            break;
+        }
+        buf.push(b'\n');
+        count += 1;
+        if buf.len() >= FLUSH_BYTES {
+            py.allow_threads(|| file.write_all(&buf))?;
+            buf.clear();
+        }
+    }
+    py.allow_threads(|| file.write_all(&buf))?;
+    result.map(|_| count)
+}
+
+/// Iterator over the values of a JSON Lines document, returned by `parse_jsonl(..., lazy=True)`.
+#[pyclass(module = "fastpy_rs.json")]
+pub struct JsonLinesReader {
+    reader: Box<dyn BufRead + Send + Sync>,
+    /// The current batch of raw input.
+    data: Vec<u8>,
+    /// Non-blank lines of `data` with their 1-based line number in the whole input.
+    lines: Vec<(usize, Range<usize>)>,
+    next: usize,
+    line_number: usize,
+    exhausted: bool,
+}
+
+#[pymethods]
+impl JsonLinesReader {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.next_value(py)
+    }
+}
+
+impl JsonLinesReader {
+    fn open(source: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let reader: Box<dyn BufRead + Send + Sync> =
+            if source.is_instance_of::<PyString>() || source.hasattr("__fspath__")? {
+                let path: PathBuf = source.extract()?;
+                let file = source.py().allow_threads(|| File::open(path))?;
+                Box::new(BufReader::with_capacity(1 << 16, file))
+            } else {
+                let data = with_json_bytes(source, <[u8]>::to_vec).map_err(|_| {
+                    PyTypeError::new_err(format!(
+                        "parse_jsonl() expects bytes or a path, not {}",
+                        source.get_type().name().map(|n| n.to_string()).unwrap_or_default()
+                    ))
+                })?;
+                Box::new(Cursor::new(data))
+            };
+        Ok(JsonLinesReader {
+            reader,
+            data: Vec::new(),
+            lines: Vec::new(),
+            next: 0,
+            line_number: 0,
+            exhausted: false,
+        })
+    }
+
+    fn next_value(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        loop {
+            if let Some((line_number, range)) = self.lines.get(self.next).cloned() {
+                self.next += 1;
+                let line = &self.data[range];
+                return match parse_slice(py, line) {
+                    Ok(value) => Ok(Some(value.unbind())),
+                    Err(err) => Err(line_error(py, line, line_number, err)),
+                };
+            }
+            if self.exhausted {
+                return Ok(None);
+            }
+            let JsonLinesReader { reader, data, lines, line_number, exhausted, .. } = self;
+            py.allow_threads(|| read_batch(reader, data, lines, line_number, exhausted))?;
+            self.next = 0;
+        }
+    }
+}
+
+/// Reads whole lines into `data` until the batch is full or the input ends.
+fn read_batch(
+    reader: &mut Box<dyn BufRead + Send + Sync>,
+    data: &mut Vec<u8>,
+    lines: &mut Vec<(usize, Range<usize>)>,
+    line_number: &mut usize,
+    exhausted: &mut bool,
+) -> io::Result<()> {
+    data.clear();
+    lines.clear();
+    while data.len() < BATCH_BYTES {
+        let start = data.len();
+        if reader.read_until(b'\n', data)? == 0 {
+            *exhausted = true;
+            break;
+        }
+        *line_number += 1;
+        let (mut begin, mut end) = (start, data.len());
+        if *line_number == 1 && data[begin..end].starts_with(UTF8_BOM) {
+            begin += UTF8_BOM.len();
+        }
+        if data[begin..end].ends_with(b"\n") {
+            end -= 1;
+        }
+        if data[begin..end].ends_with(b"\r") {
+            end -= 1;
+        }
+        if data[begin..end].iter().all(|b| matches!(b, b' ' | b'\t' | b'\r' | b'\n')) {
+            continue;
+        }
+        lines.push((*line_number, begin..end));
+    }
+    Ok(())
+}
+
+/// A `JSONDecodeError` for one line, with `lineno` pointing at that line of the input.
+fn line_error(py: Python<'_>, line: &[u8], line_number: usize, err: DecodeError) -> PyErr {
+    let (msg, offset) = match parse_error_location(err) {
+        Ok(location) => location,
+        Err(err) => return err,
+    };
+    let column = char_count(&line[..offset]);
+    let doc = String::from_utf8_lossy(line);
+    relocated_decode_error(py, &msg, &doc, column, line_number, column + 1)
+}
@@ -0,0 +1,835 @@
+//! JSONPath (RFC 9535) evaluated over Python objects.
+//!
+//! Expressions are checked for well-typedness when they are parsed, as the RFC requires,
+//! so a mistake such as comparing a non-singular query is a `ValueError` from `compile`
+//! rather than a silently empty result.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyString};
+use regex::Regex;
+
+use super::queries::{elements, json_eq, kind, slice_indices, Kind};
+
+/// How deeply filters and parentheses may nest before parsing gives up.
+const MAX_NESTING: usize = 256;
+
+/// The largest index I-JSON can represent exactly, 2^53 - 1.
+const MAX_INDEX: i64 = (1 << 53) - 1;
+
+/// A parsed JSONPath query.
+pub(super) struct JsonPath {
+    query: Query,
+}
+
+impl JsonPath {
+    /// Parses `expression`, raising `ValueError` with the position of the first problem.
+    pub(super) fn parse(py: Python<'_>, expression: &str) -> PyResult<JsonPath> {
+        let mut parser = Parser { py, src: expression.as_bytes(), pos: 0, depth: 0 };
+        let query = parser
+            .query(b'$')
+            .and_then(|query| match parser.pos == parser.src.len() {
+                true => Ok(query),
+                false => Err(parser.error("unexpected character")),
+            })
+            .map_err(|(msg, pos)| {
+                PyValueError::new_err(format!("Invalid JSONPath expression {expression:?}: {msg} at position {pos}"))
+            })?;
+        Ok(JsonPath { query })
+    }
+
+    /// Every node `doc` has at the locations the query selects, in document order.
+    pub(super) fn select<'py>(&self, doc: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        self.query.nodes(doc, doc)
+    }
+}
+
+struct Query {
+    /// `$` queries start at the root, `@` queries at the node being filtered.
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+struct Segment {
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, i64),
+    Filter(Logical),
+}
+
+enum Logical {
+    Or(Vec<Logical>),
+    And(Vec<Logical>),
+    Not(Box<Logical>),
+    Exists(Query),
+    Call(Call),
+    Compare(Comparable, Comparator, Comparable),
+}
+
+enum Comparable {
+    Literal(Py<PyAny>),
+    /// Always a singular query.
+    Query(Query),
+    /// Always a function returning a value.
+    Call(Call),
+}
+
+#[derive(Clone, Copy)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+enum Call {
+    Length(Box<Comparable>),
+    Count(Query),
+    Value(Query),
+    Match(Box<Comparable>, Box<Comparable>, Pattern),
+    Search(Box<Comparable>, Box<Comparable>, Pattern),
+}
+
+/// The regex of a `match` or `search` call, compiled up front when the pattern is a literal.
+enum Pattern {
+    /// `None` when the literal is not a valid I-Regexp, which never matches anything.
+    Static(Option<Regex>),
+    Dynamic,
+}
+
+impl Call {
+    fn returns_value(&self) -> bool {
+        matches!(self, Call::Length(_) | Call::Count(_) | Call::Value(_))
+    }
+}
+
+// Evaluation ---------------------------------------------------------------
+
+impl Query {
+    fn nodes<'py>(&self, root: &Bound<'py, PyAny>, current: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        let mut nodes = vec![if self.absolute { root.clone() } else { current.clone() }];
+        for segment in &self.segments {
+            let mut selected = Vec::new();
+            for node in &nodes {
+                if segment.descendant {
+                    for descendant in descendants(node) {
+                        segment.select(root, &descendant, &mut selected)?;
+                    }
+                } else {
+                    segment.select(root, node, &mut selected)?;
+                }
+            }
+            nodes = selected;
+            if nodes.is_empty() {
+                break;
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant
+                && matches!(segment.selectors.as_slice(), [Selector::Name(_)] | [Selector::Index(_)])
+        })
+    }
+}
+
+impl Segment {
+    fn select<'py>(
+        &self,
+        root: &Bound<'py, PyAny>,
+        node: &Bound<'py, PyAny>,
+        out: &mut Vec<Bound<'py, PyAny>>,
+    ) -> PyResult<()> {
+        for selector in &self.selectors {
+            match selector {
+                Selector::Name(name) => {
+                    if let Ok(dict) = node.downcast::<PyDict>() {
+                        out.extend(dict.get_item(name)?);
+                    }
+                }
+                Selector::Wildcard => out.extend(children(node)),
+                Selector::Index(index) => {
+                    if let Some(items) = elements(node) {
+                        let len = items.len() as i64;
+                        let index = if *index < 0 { len + index } else { *index };
+                        if (0..len).contains(&index) {
+                            out.push(items[index as usize].clone());
+                        }
+                    }
+                }
+                Selector::Slice(start, end, step) => {
+                    if let (Some(items), true) = (elements(node), *step != 0) {
+                        out.extend(slice_indices(items.len(), *start, *end, *step).into_iter().map(|i| items[i].clone()));
+                    }
+                }
+                Selector::Filter(condition) => {
+                    for child in children(node) {
+                        if condition.test(root, &child)? {
+                            out.push(child);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The member values of an object or the elements of an array.
+fn children<'py>(node: &Bound<'py, PyAny>) -> Vec<Bound<'py, PyAny>> {
+    match node.downcast::<PyDict>() {
+        Ok(dict) => dict.values().iter().collect(),
+        Err(_) => elements(node).unwrap_or_default(),
+    }
+}
+
+/// `node` followed by all of its descendants, each before its own children.
+fn descendants<'py>(node: &Bound<'py, PyAny>) -> Vec<Bound<'py, PyAny>> {
+    let mut out = Vec::new();
+    let mut stack = vec![node.clone()];
+    while let Some(node) = stack.pop() {
+        stack.extend(children(&node).into_iter().rev());
+        out.push(node);
+    }
+    out
+}
+
+impl Logical {
+    fn test<'py>(&self, root: &Bound<'py, PyAny>, current: &Bound<'py, PyAny>) -> PyResult<bool> {
+        Ok(match self {
+            Logical::Or(terms) => {
+                for term in terms {
+                    if term.test(root, current)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Logical::And(terms) => {
+                for term in terms {
+                    if !term.test(root, current)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Logical::Not(term) => !term.test(root, current)?,
+            Logical::Exists(query) => !query.nodes(root, current)?.is_empty(),
+            Logical::Call(call) => call.test(root, current)?,
+            Logical::Compare(left, comparator, right) => {
+                compare(*comparator, left.value(root, current)?, right.value(root, current)?)?
+            }
+        })
+    }
+}
+
+impl Comparable {
+    /// The value this evaluates to, or `None` for the RFC's "Nothing".
+    fn value<'py>(&self, root: &Bound<'py, PyAny>, current: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        Ok(match self {
+            Comparable::Literal(literal) => Some(literal.bind(root.py()).clone()),
+            Comparable::Query(query) => query.nodes(root, current)?.pop(),
+            Comparable::Call(call) => call.value(root, current)?,
+        })
+    }
+}
+
+impl Call {
+    fn value<'py>(&self, root: &Bound<'py, PyAny>, current: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let py = root.py();
+        Ok(match self {
+            Call::Length(arg) => match arg.value(root, current)? {
+                Some(value) if matches!(kind(&value), Kind::String | Kind::Array | Kind::Object) => {
+                    Some(PyInt::new(py, value.len()? as i64).into_any())
+                }
+                _ => None,
+            },
+            Call::Count(query) => Some(PyInt::new(py, query.nodes(root, current)?.len() as i64).into_any()),
+            Call::Value(query) => {
+                let mut nodes = query.nodes(root, current)?;
+                match nodes.len() {
+                    1 => nodes.pop(),
+                    _ => None,
+                }
+            }
+            Call::Match(..) | Call::Search(..) => {
+                Some(PyBool::new(py, self.test(root, current)?).to_owned().into_any())
+            }
+        })
+    }
+
+    fn test<'py>(&self, root: &Bound<'py, PyAny>, current: &Bound<'py, PyAny>) -> PyResult<bool> {
+        let (subject, pattern, compiled, anchored) = match self {
+            Call::Match(subject, pattern, compiled) => (subject, pattern, compiled, true),
+            Call::Search(subject, pattern, compiled) => (subject, pattern, compiled, false),
+            _ => return Ok(false),
+        };
+        let Some(subject) = subject.value(root, current)? else {
+            return Ok(false);
+        };
+        let Ok(subject) = subject.downcast::<PyString>() else {
+            return Ok(false);
+        };
+        let regex = match compiled {
+            Pattern::Static(regex) => regex.clone(),
+            Pattern::Dynamic => match pattern.value(root, current)? {
+                Some(pattern) => match pattern.downcast::<PyString>() {
+                    Ok(pattern) => i_regexp(pattern.to_str()?, anchored),
+                    Err(_) => None,
+                },
+                None => None,
+            },
+        };
+        Ok(regex.is_some_and(|regex| subject.to_str().is_ok_and(|s| regex.is_match(s))))
+    }
+}
+
+fn compare(comparator: Comparator, a: Option<Bound<'_, PyAny>>, b: Option<Bound<'_, PyAny>>) -> PyResult<bool> {
+    let equal = |a: &Option<Bound<'_, PyAny>>, b: &Option<Bound<'_, PyAny>>| match (a, b) {
+        (None, None) => Ok(true),
+        (Some(a), Some(b)) => json_eq(a, b),
+        _ => Ok(false),
+    };
+    // Only numbers with numbers and strings with strings have an order; everything else is
+    // neither less nor greater.
+    let less = |a: &Option<Bound<'_, PyAny>>, b: &Option<Bound<'_, PyAny>>| match (a, b) {
+        (Some(a), Some(b)) if kind(a) == kind(b) && matches!(kind(a), Kind::Number | Kind::String) => a.lt(b),
+        _ => Ok(false),
+    };
+    Ok(match comparator {
+        Comparator::Eq => equal(&a, &b)?,
+        Comparator::Ne => !equal(&a, &b)?,
+        Comparator::Lt => less(&a, &b)?,
+        Comparator::Lte => less(&a, &b)? || equal(&a, &b)?,
+        Comparator::Gt => less(&b, &a)?,
+        Comparator::Gte => less(&b, &a)? || equal(&a, &b)?,
+    })
+}
+
+/// Compiles an I-Regexp (RFC 9485) for the `regex` crate.
+///
+/// The two dialects agree except for `.`, which in I-Regexp matches anything but line
+/// breaks. `match` must cover the whole string, `search` any substring of it.
+fn i_regexp(pattern: &str, anchored: bool) -> Option<Regex> {
+    let mut translated = String::with_capacity(pattern.len() + 16);
+    let mut in_class = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                translated.push(c);
+                translated.push(chars.next()?);
+            }
+            '[' if !in_class => {
+                in_class = true;
+                translated.push(c);
+            }
+            ']' if in_class => {
+                in_class = false;
+                translated.push(c);
+            }
+            '.' if !in_class => translated.push_str("[^\\n\\r]"),
+            c => translated.push(c),
+        }
+    }
+    let source = match anchored {
+        true => format!("\\A(?:{translated})\\z"),
+        false => translated,
+    };
+    Regex::new(&source).ok()
+}
+
+// Parser -------------------------------------------------------------------
+
+type ParseResult<T> = Result<T, (String, usize)>;
+
+struct Parser<'a, 'py> {
+    py: Python<'py>,
+    src: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_, '_> {
+    fn error(&self, msg: &str) -> (String, usize) {
+        match self.peek() {
+            None => ("unexpected end of expression".to_string(), self.pos),
+            Some(_) => (msg.to_string(), self.pos),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> ParseResult<()> {
+        match self.eat(byte) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected '{}'", byte as char))),
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let found = self.src[self.pos..].starts_with(s.as_bytes());
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(self.error("expression is nested too deeply"));
+        }
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Parses a query starting with `identifier`, which is `$` or `@`.
+    fn query(&mut self, identifier: u8) -> ParseResult<Query> {
+        self.expect(identifier)?;
+        let mut segments = Vec::new();
+        loop {
+            let start = self.pos;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'.' | b'[') => segments.push(self.segment()?),
+                _ => {
+                    self.pos = start;
+                    break;
+                }
+            }
+        }
+        Ok(Query { absolute: identifier == b'$', segments })
+    }
+
+    fn segment(&mut self) -> ParseResult<Segment> {
+        if self.eat(b'[') {
+            return Ok(Segment { descendant: false, selectors: self.bracketed()? });
+        }
+        self.expect(b'.')?;
+        let descendant = self.eat(b'.');
+        let selector = if descendant && self.eat(b'[') {
+            return Ok(Segment { descendant, selectors: self.bracketed()? });
+        } else if self.eat(b'*') {
+            Selector::Wildcard
+        } else {
+            Selector::Name(self.member_name()?)
+        };
+        Ok(Segment { descendant, selectors: vec![selector] })
+    }
+
+    /// A name in dot notation: a letter, `_` or non-ASCII character, then those or digits.
+    fn member_name(&mut self) -> ParseResult<String> {
+        let start = self.pos;
+        let is_name_char = |b: u8, first: bool| b.is_ascii_alphabetic() || b == b'_' || b >= 0x80 || (!first && b.is_ascii_digit());
+        if !self.peek().is_some_and(|b| is_name_char(b, true)) {
+            return Err(self.error("expected a member name"));
+        }
+        while self.peek().is_some_and(|b| is_name_char(b, false)) {
+            self.pos += 1;
+        }
+        Ok(String::from_utf8_lossy(&self.src[start..self.pos]).into_owned())
+    }
+
+    /// The selectors of `[...]`, after the opening bracket.
+    fn bracketed(&mut self) -> ParseResult<Vec<Selector>> {
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return Ok(selectors);
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn selector(&mut self) -> ParseResult<Selector> {
+        match self.peek() {
+            Some(b'\'' | b'"') => Ok(Selector::Name(self.string()?)),
+            Some(b'*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some(b'?') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                Ok(Selector::Filter(self.nested(Self::logical_or)?))
+            }
+            _ => {
+                let start = self.integer()?;
+                let after_start = self.pos;
+                self.skip_whitespace();
+                if !self.eat(b':') {
+                    self.pos = after_start;
+                    return start.map(Selector::Index).ok_or_else(|| self.error("expected a selector"));
+                }
+                self.skip_whitespace();
+                let end = self.integer()?;
+                self.skip_whitespace();
+                let mut step = None;
+                if self.eat(b':') {
+                    self.skip_whitespace();
+                    step = self.integer()?;
+                }
+                Ok(Selector::Slice(start, end, step.unwrap_or(1)))
+            }
+        }
+    }
+
+    /// An optional integer within the I-JSON range; `-0` and leading zeros are not allowed.
+    fn integer(&mut self) -> ParseResult<Option<i64>> {
+        let start = self.pos;
+        self.eat(b'-');
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => {
+                while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+            _ if self.pos == start => return Ok(None),
+            _ => return Err(self.error("expected a digit")),
+        }
+        let text = std::str::from_utf8(&self.src[start..self.pos]).unwrap_or_default();
+        match text.parse::<i64>() {
+            Ok(n) if text != "-0" && (-MAX_INDEX..=MAX_INDEX).contains(&n) => {
+                if self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                    return Err(self.error("leading zeros are not allowed"));
+                }
+                Ok(Some(n))
+            }
+            _ => Err((format!("invalid integer {text}"), start)),
+        }
+    }
+
+    /// A single- or double-quoted string literal.
+    fn string(&mut self) -> ParseResult<String> {
+        let quote = self.src[self.pos];
+        self.pos += 1;
+        let mut out = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b) if b == quote => {
+                    self.pos += 1;
+                    return Ok(String::from_utf8_lossy(&out).into_owned());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b @ (b'/' | b'\\')) => b as char,
+                        Some(b) if b == quote => b as char,
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let c = self.unicode_escape()?;
+                            let mut buf = [0; 4];
+                            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.pos += 1;
+                    out.push(escaped as u8);
+                }
+                Some(b) if b < 0x20 => return Err(self.error("control characters must be escaped")),
+                Some(b) => {
+                    self.pos += 1;
+                    out.push(b);
+                }
+            }
+        }
+    }
+
+    /// The character of a `\uXXXX` escape (after the `u`), combining surrogate pairs.
+    fn unicode_escape(&mut self) -> ParseResult<char> {
+        let high = self.hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                if !self.eat_str("\\u") {
+                    return Err(self.error("unpaired surrogate"));
+                }
+                let low = self.hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error("unpaired surrogate"));
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return Err(self.error("unpaired surrogate")),
+            code => code,
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid escape"))
+    }
+
+    fn hex4(&mut self) -> ParseResult<u32> {
+        let digits = self.src.get(self.pos..self.pos + 4).and_then(|d| std::str::from_utf8(d).ok());
+        match digits.and_then(|d| u32::from_str_radix(d, 16).ok().filter(|_| d.bytes().all(|b| b.is_ascii_hexdigit()))) {
+            Some(code) => {
+                self.pos += 4;
+                Ok(code)
+            }
+            None => Err(self.error("expected four hex digits")),
+        }
+    }
+
+    fn logical_or(&mut self) -> ParseResult<Logical> {
+        let mut terms = vec![self.logical_and()?];
+        loop {
+            let start = self.pos;
+            self.skip_whitespace();
+            if !self.eat_str("||") {
+                self.pos = start;
+                break;
+            }
+            self.skip_whitespace();
+            terms.push(self.logical_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.pop().unwrap() } else { Logical::Or(terms) })
+    }
+
+    fn logical_and(&mut self) -> ParseResult<Logical> {
+        let mut terms = vec![self.basic()?];
+        loop {
+            let start = self.pos;
+            self.skip_whitespace();
+            if !self.eat_str("&&") {
+                self.pos = start;
+                break;
+            }
+            self.skip_whitespace();
+            terms.push(self.basic()?);
+        }
+        Ok(if terms.len() == 1 { terms.pop().unwrap() } else { Logical::And(terms) })
+    }
+
+    /// A parenthesized expression, an existence or function test, or a comparison.
+    fn basic(&mut self) -> ParseResult<Logical> {
+        if self.eat(b'!') {
+            self.skip_whitespace();
+            return Ok(Logical::Not(Box::new(self.nested(Self::negatable)?)));
+        }
+        if self.peek() == Some(b'(') {
+            return self.nested(Self::negatable);
+        }
+        let start = self.pos;
+        let left = self.operand()?;
+        let after_left = self.pos;
+        self.skip_whitespace();
+        let Some(comparator) = self.comparator() else {
+            self.pos = after_left;
+            return match left {
+                Operand::Query(query) => Ok(Logical::Exists(query)),
+                Operand::Call(call) if !call.returns_value() => Ok(Logical::Call(call)),
+                Operand::Call(_) => Err(("the result of this function must be compared".to_string(), start)),
+                Operand::Literal(_) => Err(self.error("expected a comparison operator")),
+            };
+        };
+        let left = self.comparable(left, start)?;
+        self.skip_whitespace();
+        let right_start = self.pos;
+        let right = self.operand()?;
+        let right = self.comparable(right, right_start)?;
+        Ok(Logical::Compare(left, comparator, right))
+    }
+
+    /// What may follow `!`: a parenthesized expression or a test.
+    fn negatable(&mut self) -> ParseResult<Logical> {
+        if self.eat(b'(') {
+            self.skip_whitespace();
+            let inner = self.logical_or()?;
+            self.skip_whitespace();
+            self.expect(b')')?;
+            return Ok(inner);
+        }
+        let start = self.pos;
+        match self.operand()? {
+            Operand::Query(query) => Ok(Logical::Exists(query)),
+            Operand::Call(call) if !call.returns_value() => Ok(Logical::Call(call)),
+            _ => Err(("expected a query or a test function".to_string(), start)),
+        }
+    }
+
+    fn comparator(&mut self) -> Option<Comparator> {
+        let comparators = [
+            ("==", Comparator::Eq),
+            ("!=", Comparator::Ne),
+            ("<=", Comparator::Lte),
+            (">=", Comparator::Gte),
+            ("<", Comparator::Lt),
+            (">", Comparator::Gt),
+        ];
+        comparators.into_iter().find(|(text, _)| self.eat_str(text)).map(|(_, comparator)| comparator)
+    }
+
+    /// Checks that an operand may be compared: a literal, a singular query or a value function.
+    fn comparable(&self, operand: Operand, start: usize) -> ParseResult<Comparable> {
+        match operand {
+            Operand::Literal(literal) => Ok(Comparable::Literal(literal)),
+            Operand::Query(query) if query.is_singular() => Ok(Comparable::Query(query)),
+            Operand::Query(_) => Err(("only singular queries can be compared".to_string(), start)),
+            Operand::Call(call) if call.returns_value() => Ok(Comparable::Call(call)),
+            Operand::Call(_) => Err(("the result of this function cannot be compared".to_string(), start)),
+        }
+    }
+
+    fn operand(&mut self) -> ParseResult<Operand> {
+        let py = self.py;
+        match self.peek() {
+            Some(b'@') => Ok(Operand::Query(self.nested(|p| p.query(b'@'))?)),
+            Some(b'$') => Ok(Operand::Query(self.nested(|p| p.query(b'$'))?)),
+            Some(b'\'' | b'"') => Ok(Operand::Literal(PyString::new(py, &self.string()?).into_any().unbind())),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'a'..=b'z') => {
+                let start = self.pos;
+                while self.peek().is_some_and(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_') {
+                    self.pos += 1;
+                }
+                let name = std::str::from_utf8(&self.src[start..self.pos]).unwrap_or_default().to_string();
+                if self.peek() == Some(b'(') {
+                    return Ok(Operand::Call(self.nested(|p| p.call(&name, start))?));
+                }
+                Ok(Operand::Literal(match name.as_str() {
+                    "true" => PyBool::new(py, true).to_owned().into_any().unbind(),
+                    "false" => PyBool::new(py, false).to_owned().into_any().unbind(),
+                    "null" => py.None(),
+                    _ => return Err((format!("unexpected name {name:?}"), start)),
+                }))
+            }
+            _ => Err(self.error("expected a query, a literal or a function")),
+        }
+    }
+
+    /// A number literal, using the JSON number grammar (`-0` included).
+    fn number(&mut self) -> ParseResult<Operand> {
+        let start = self.pos;
+        let digits = |p: &mut Self| {
+            let from = p.pos;
+            while p.peek().is_some_and(|b| b.is_ascii_digit()) {
+                p.pos += 1;
+            }
+            p.pos > from
+        };
+        self.eat(b'-');
+        if !self.eat(b'0') && !digits(self) {
+            return Err(self.error("expected a digit"));
+        }
+        let mut is_float = false;
+        if self.eat(b'.') {
+            if !digits(self) {
+                return Err(self.error("expected a digit"));
+            }
+            is_float = true;
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            if !digits(self) {
+                return Err(self.error("expected a digit"));
+            }
+            is_float = true;
+        }
+        if self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            return Err(self.error("leading zeros are not allowed"));
+        }
+        let text = std::str::from_utf8(&self.src[start..self.pos]).unwrap_or_default();
+        let value = match (is_float, text.parse::<i64>()) {
+            (false, Ok(n)) => PyInt::new(self.py, n).into_any(),
+            _ => PyFloat::new(self.py, text.parse().unwrap_or(f64::NAN)).into_any(),
+        };
+        Ok(Operand::Literal(value.unbind()))
+    }
+
+    /// A function call, positioned on the opening parenthesis after `name`.
+    fn call(&mut self, name: &str, start: usize) -> ParseResult<Call> {
+        self.expect(b'(')?;
+        self.skip_whitespace();
+        let call = match name {
+            "length" => Call::Length(Box::new(self.value_argument()?)),
+            "count" => Call::Count(self.nodes_argument()?),
+            "value" => Call::Value(self.nodes_argument()?),
+            "match" | "search" => {
+                let subject = self.value_argument()?;
+                self.skip_whitespace();
+                self.expect(b',')?;
+                self.skip_whitespace();
+                let pattern = self.value_argument()?;
+                let anchored = name == "match";
+                let compiled = match &pattern {
+                    Comparable::Literal(literal) => Pattern::Static(
+                        literal
+                            .bind(self.py)
+                            .downcast::<PyString>()
+                            .ok()
+                            .and_then(|s| i_regexp(s.to_str().ok()?, anchored)),
+                    ),
+                    _ => Pattern::Dynamic,
+                };
+                match anchored {
+                    true => Call::Match(Box::new(subject), Box::new(pattern), compiled),
+                    false => Call::Search(Box::new(subject), Box::new(pattern), compiled),
+                }
+            }
+            _ => return Err((format!("unknown function {name}()"), start)),
+        };
+        self.skip_whitespace();
+        match self.eat(b')') {
+            true => Ok(call),
+            false => Err(self.error(&format!("{name}() given too many arguments or missing ')'"))),
+        }
+    }
+
+    fn value_argument(&mut self) -> ParseResult<Comparable> {
+        let start = self.pos;
+        let operand = self.operand()?;
+        self.comparable(operand, start)
+    }
+
+    fn nodes_argument(&mut self) -> ParseResult<Query> {
+        let start = self.pos;
+        match self.operand()? {
+            Operand::Query(query) => Ok(query),
+            _ => Err(("expected a query".to_string(), start)),
+        }
+    }
+}
+
+/// Anything that can stand on either side of a comparison before its type is checked.
+enum Operand {
+    Literal(Py<PyAny>),
+    Query(Query),
+    Call(Call),
+}
@@ -0,0 +1,587 @@
+//! Best-effort repair of malformed JSON, typically the output of a language model.
+//!
+//! The JSON is first located in the surrounding text: inside a Markdown code fence if there
+//! is one, otherwise at the first plausible `{` or `[`. If it already parses it is returned
+//! as is; otherwise a tolerant scanner re-emits it as compact, valid JSON, quoting keys and
+//! bare words, normalising quotes, numbers and literals, and closing whatever was left open.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use serde::de::IgnoredAny;
+
+use super::decoder::MAX_DEPTH;
+use super::{parse_error, parse_slice};
+
+/// Extracts the first JSON value from `text` and repairs it into valid JSON.
+///
+/// Meant for JSON written by language models: it may be wrapped in prose or a Markdown code
+/// fence, cut off mid-string, or missing closing brackets. Valid JSON found in the text is
+/// returned unchanged. Anything else is rewritten as compact JSON, fixing on the way:
+///
+/// * open strings, arrays and objects at the end of the text, which are closed
+/// * single, curly and backtick quotes, unquoted keys and bare-word values, which become strings
+/// * unescaped quotes inside strings, raw control characters and invalid escapes
+/// * missing and trailing commas, missing values (written as `null`) and mismatched brackets
+/// * `True`/`False`/`None`, `NaN`/`Infinity` (written as `null`), comments and `...` placeholders
+/// * numbers such as `+1`, `.5`, `5.`, `007` or `0x1F`
+///
+/// # Arguments
+/// * `text` - Text containing a JSON value, possibly malformed or truncated
+///
+/// # Returns
+/// * The repaired JSON document as a string
+///
+/// # Raises
+/// * `ValueError` - If the text contains no JSON value at all, or it is nested too deeply
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// reply = 'Sure! Here is the data:\n```json\n{"name": "Ann", tags: [\'a\', "b",], "bio": "Likes'
+/// print(fastpy_rs.json.repair(reply))
+/// # Output: {"name":"Ann","tags":["a","b"],"bio":"Likes"}
+/// ```
+#[pyfunction]
+pub fn repair(text: &str) -> PyResult<String> {
+    repair_text(text)
+}
+
+/// Extracts the first JSON value from `text`, repairs it like `repair` does and parses it.
+///
+/// # Arguments
+/// * `text` - Text containing a JSON value, possibly malformed or truncated
+///
+/// # Returns
+/// * The parsed value, with the same types `parse_json` returns
+///
+/// # Raises
+/// * `ValueError` - If the text contains no JSON value at all, or it is nested too deeply
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// reply = "The answer is {'city': 'Paris', 'population': 2102650, 'capital': True"
+/// print(fastpy_rs.json.parse_lenient_llm(reply))
+/// # Output: {'city': 'Paris', 'population': 2102650, 'capital': True}
+/// ```
+#[pyfunction]
+pub fn parse_lenient_llm(py: Python<'_>, text: &str) -> PyResult<PyObject> {
+    let repaired = repair_text(text)?;
+    let value = parse_slice(py, repaired.as_bytes())
+        .map_err(|e| parse_error(PyString::new(py, &repaired).as_any(), repaired.as_bytes(), e))?;
+    Ok(value.unbind())
+}
+
+fn repair_text(text: &str) -> PyResult<String> {
+    let block = fenced_block(text).unwrap_or(text).trim();
+    if is_json(block) {
+        return Ok(block.to_string());
+    }
+    let start = block
+        .char_indices()
+        .find(|&(i, ch)| (ch == '{' || ch == '[') && plausible_start(&block[i..]))
+        .map(|(i, _)| i);
+    // Without a container, only a scalar standing on its own is taken as JSON, not any prose.
+    let start = match start {
+        Some(start) => start,
+        None if plausible_scalar(block) => 0,
+        None => return Err(PyValueError::new_err("No JSON value found in text")),
+    };
+    let mut values = serde_json::Deserializer::from_str(&block[start..]).into_iter::<IgnoredAny>();
+    if let Some(Ok(_)) = values.next() {
+        return Ok(block[start..start + values.byte_offset()].to_string());
+    }
+
+    let mut repairer = Repairer { text: &block[start..], pos: 0, out: String::with_capacity(block.len() + 16), closers: Vec::new() };
+    repairer.skip_trivia();
+    if !repairer.value()? {
+        return Err(PyValueError::new_err("No JSON value found in text"));
+    }
+    Ok(repairer.out)
+}
+
+fn is_json(text: &str) -> bool {
+    serde_json::from_str::<IgnoredAny>(text).is_ok()
+}
+
+/// The contents of the first non-empty Markdown code fence, running to the end of the text
+/// if the closing fence is missing.
+fn fenced_block(text: &str) -> Option<&str> {
+    let mut rest = text;
+    while let Some(open) = rest.find("```") {
+        let after = &rest[open + 3..];
+        // Skip the info string (`json`, `javascript`, ...) on the opening line.
+        let body = after.find('\n').map_or("", |eol| &after[eol + 1..]);
+        let (content, next) = match body.find("```") {
+            Some(close) => (&body[..close], &body[close + 3..]),
+            None => (body, ""),
+        };
+        if !content.trim().is_empty() {
+            return Some(content);
+        }
+        rest = next;
+    }
+    None
+}
+
+/// Words that start a JSON value even without brackets or quotes around them.
+const LITERALS: [&str; 6] = ["true", "false", "null", "True", "False", "None"];
+
+/// Whether a `{` or `[` at the start of `text` looks like it opens JSON rather than prose.
+fn plausible_start(text: &str) -> bool {
+    let mut chars = text.chars();
+    let open = chars.next();
+    let rest = chars.as_str().trim_start();
+    let Some(next) = rest.chars().next() else {
+        return true;
+    };
+    match (open, next) {
+        (_, '"' | '\'' | '“' | '‘' | '{' | '[' | '}' | ']' | '-' | '+' | '.' | '0'..='9') => true,
+        // An unquoted key, but only if a `:` follows, so that `{braces}` in prose are passed over.
+        (Some('{'), ch) if is_word_char(ch) => rest.trim_start_matches(is_word_char).trim_start().starts_with(':'),
+        _ => LITERALS.iter().any(|word| rest.starts_with(word)),
+    }
+}
+
+fn plausible_scalar(text: &str) -> bool {
+    match text.chars().next() {
+        Some('"' | '\'' | '“' | '‘' | '-' | '.' | '0'..='9') => true,
+        _ => LITERALS.iter().any(|word| text.starts_with(word)),
+    }
+}
+
+/// Single-pass scanner that writes the repaired document to `out`.
+struct Repairer<'a> {
+    text: &'a str,
+    pos: usize,
+    out: String,
+    /// Closing brackets of the containers currently open, innermost last.
+    closers: Vec<char>,
+}
+
+impl<'a> Repairer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                self.pos += comment.find("*/").map_or(trimmed.len(), |end| end + 4);
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Writes the value at the current position, returning `false` (having written nothing)
+    /// if there is none, i.e. at a delimiter or the end of the text.
+    fn value(&mut self) -> PyResult<bool> {
+        let Some(ch) = self.peek() else {
+            return Ok(false);
+        };
+        match ch {
+            '{' => self.nested('}', Self::object)?,
+            '[' => self.nested(']', Self::array)?,
+            '"' | '\'' | '“' | '”' | '‘' | '`' => self.string(),
+            '-' | '+' | '.' | '0'..='9' => return Ok(self.number()),
+            ',' | '}' | ']' | ':' => return Ok(false),
+            ch if ch.is_alphabetic() || ch == '_' || ch == '$' => self.word(),
+            _ => return Ok(self.bare_string()),
+        }
+        Ok(true)
+    }
+
+    fn nested(&mut self, closer: char, parse: fn(&mut Self) -> PyResult<()>) -> PyResult<()> {
+        if self.closers.len() >= MAX_DEPTH {
+            return Err(PyValueError::new_err("JSON is nested too deeply to repair"));
+        }
+        self.pos += 1;
+        self.closers.push(closer);
+        let result = parse(self);
+        self.closers.pop();
+        result
+    }
+
+    /// Handles a closing bracket that does not match the innermost container: if an outer
+    /// container uses it, the inner one is closed without consuming it, otherwise it is taken
+    /// as a typo for the right bracket.
+    fn close_mismatched(&mut self, found: char) {
+        let outer = &self.closers[..self.closers.len() - 1];
+        if !outer.contains(&found) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_ellipsis(&mut self) -> bool {
+        if self.rest().starts_with("...") || self.rest().starts_with('…') {
+            self.pos += self.rest().len() - self.rest().trim_start_matches(['.', '…']).len();
+            return true;
+        }
+        false
+    }
+
+    fn object(&mut self) -> PyResult<()> {
+        self.out.push('{');
+        let mut members = 0;
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                None => break,
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(']') => {
+                    self.close_mismatched(']');
+                    break;
+                }
+                Some(',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(_) if self.skip_ellipsis() => continue,
+                Some(_) => {}
+            }
+            if members > 0 {
+                self.out.push(',');
+            }
+            members += 1;
+            match self.peek() {
+                Some('"' | '\'' | '“' | '”' | '‘' | '`') => self.string(),
+                _ => self.bare_key(),
+            }
+            self.out.push(':');
+            self.skip_trivia();
+            while let Some(':' | '=') = self.peek() {
+                self.pos += 1;
+                self.skip_trivia();
+            }
+            if !self.value()? {
+                self.out.push_str("null");
+            }
+        }
+        self.out.push('}');
+        Ok(())
+    }
+
+    fn array(&mut self) -> PyResult<()> {
+        self.out.push('[');
+        let mut items = 0;
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                None => break,
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('}') => {
+                    self.close_mismatched('}');
+                    break;
+                }
+                Some(',' | ':') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(_) if self.skip_ellipsis() => continue,
+                Some(_) => {}
+            }
+            let mark = self.out.len();
+            if items > 0 {
+                self.out.push(',');
+            }
+            if self.value()? {
+                items += 1;
+            } else {
+                self.out.truncate(mark);
+            }
+        }
+        self.out.push(']');
+        Ok(())
+    }
+
+    /// Writes a quoted string, whatever its quote characters.
+    ///
+    /// A closing quote only ends the string when it is followed by something that can come
+    /// after a string (a delimiter, a line break or the end of the text); any other quote is
+    /// taken to be part of the text and escaped.
+    fn string(&mut self) {
+        let open = self.peek().unwrap_or('"');
+        self.pos += open.len_utf8();
+        let closers: &[char] = match open {
+            '\'' => &['\''],
+            '‘' => &['’', '\''],
+            '`' => &['`'],
+            _ => &['"', '”', '“'],
+        };
+        self.out.push('"');
+        while let Some(ch) = self.peek() {
+            self.pos += ch.len_utf8();
+            if closers.contains(&ch) && self.at_string_end() {
+                break;
+            }
+            if ch == '\\' {
+                self.escape();
+            } else {
+                push_escaped(&mut self.out, ch);
+            }
+        }
+        self.out.push('"');
+    }
+
+    /// Whether the quote just read closes the string: it has to be followed by something that
+    /// may come after a value, or by another string that itself closes that way, as in
+    /// `["a" "b"]` with its comma missing. Any other quote is part of the text.
+    fn at_string_end(&self) -> bool {
+        let rest = self.rest().trim_start_matches([' ', '\t']);
+        if let Some(next) = rest.strip_prefix('"') {
+            let mut chars = next.char_indices();
+            while let Some((i, ch)) = chars.next() {
+                match ch {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => return ends_value(&next[i + 1..]),
+                    _ => {}
+                }
+            }
+            return false;
+        }
+        ends_value(rest)
+    }
+
+    /// Writes the escape sequence after a backslash, keeping the backslash as text when the
+    /// sequence is not valid JSON.
+    fn escape(&mut self) {
+        let Some(ch) = self.peek() else {
+            return;
+        };
+        match ch {
+            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => {
+                self.pos += 1;
+                self.out.push('\\');
+                self.out.push(ch);
+            }
+            '\'' => {
+                self.pos += 1;
+                self.out.push('\'');
+            }
+            'u' => match hex4(&self.rest()[1..]) {
+                Some(unit @ 0xd800..=0xdbff) => {
+                    self.pos += 5;
+                    let low = self.rest().strip_prefix("\\u").and_then(hex4);
+                    match low {
+                        Some(low @ 0xdc00..=0xdfff) => {
+                            self.pos += 6;
+                            self.out.push_str(&format!("\\u{unit:04x}\\u{low:04x}"));
+                        }
+                        _ => self.out.push('\u{fffd}'),
+                    }
+                }
+                Some(0xdc00..=0xdfff) => {
+                    self.pos += 5;
+                    self.out.push('\u{fffd}');
+                }
+                Some(unit) => {
+                    self.pos += 5;
+                    self.out.push_str(&format!("\\u{unit:04x}"));
+                }
+                // A `\u` cut off by the end of the text is dropped.
+                None if self.rest().len() < 5 && self.rest()[1..].bytes().all(|b| b.is_ascii_hexdigit()) => {
+                    self.pos = self.text.len();
+                }
+                None => self.out.push_str("\\\\"),
+            },
+            _ => self.out.push_str("\\\\"),
+        }
+    }
+
+    /// Writes a number in JSON syntax, falling back to a bare string for things like `12px`.
+    ///
+    /// Returns `false` for a lone sign or point, which is skipped.
+    fn number(&mut self) -> bool {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut i = start;
+        let negative = bytes[i] == b'-';
+        if let b'-' | b'+' = bytes[i] {
+            i += 1;
+        }
+        if self.text[i..].starts_with("Infinity") {
+            self.pos = i + "Infinity".len();
+            self.out.push_str("null");
+            return true;
+        }
+
+        if let Some(b"0x" | b"0X") = bytes.get(i..i + 2) {
+            let digits_start = i + 2;
+            let mut end = digits_start;
+            while bytes.get(end).is_some_and(u8::is_ascii_hexdigit) {
+                end += 1;
+            }
+            if let Ok(value) = u128::from_str_radix(&self.text[digits_start..end], 16) {
+                if !self.text[end..].starts_with(is_word_char) {
+                    self.pos = end;
+                    if negative {
+                        self.out.push('-');
+                    }
+                    self.out.push_str(&value.to_string());
+                    return true;
+                }
+            }
+        }
+
+        let int_start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        let int_end = i;
+        let mut fraction = None;
+        if bytes.get(i) == Some(&b'.') {
+            i += 1;
+            let fraction_start = i;
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+            fraction = Some(&self.text[fraction_start..i]);
+        }
+        if int_end == int_start && fraction.is_none_or(str::is_empty) {
+            if self.text[i..].starts_with(is_word_char) {
+                return self.bare_string();
+            }
+            self.pos = i;
+            return false;
+        }
+        let mut exponent = "";
+        if let Some(b'e' | b'E') = bytes.get(i) {
+            let mut end = i + 1;
+            if let Some(b'+' | b'-') = bytes.get(end) {
+                end += 1;
+            }
+            let digits_start = end;
+            while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+                end += 1;
+            }
+            if end > digits_start {
+                exponent = &self.text[i..end];
+                i = end;
+            } else if !self.text[end..].starts_with(is_word_char) {
+                // An exponent without digits, as left by truncation, is dropped.
+                i = end;
+            }
+        }
+        if self.text[i..].starts_with(is_word_char) {
+            return self.bare_string();
+        }
+
+        self.pos = i;
+        if negative {
+            self.out.push('-');
+        }
+        let int = self.text[int_start..int_end].trim_start_matches('0');
+        self.out.push_str(if int.is_empty() { "0" } else { int });
+        if let Some(fraction) = fraction {
+            self.out.push('.');
+            self.out.push_str(if fraction.is_empty() { "0" } else { fraction });
+        }
+        self.out.push_str(exponent);
+        true
+    }
+
+    /// Writes a literal (`true`, `None`, `NaN`, ...) or otherwise a bare word as a string.
+    fn word(&mut self) {
+        let rest = self.rest();
+        let word = &rest[..rest.find(|ch: char| !is_word_char(ch)).unwrap_or(rest.len())];
+        let at_end = word.len() == rest.len();
+        let literal = match word {
+            "true" | "True" | "TRUE" => Some("true"),
+            "false" | "False" | "FALSE" => Some("false"),
+            "null" | "None" | "NULL" | "undefined" | "nil" | "NaN" | "Infinity" => Some("null"),
+            // A literal cut off by the end of the text.
+            _ if at_end => ["true", "false", "null"].into_iter().find(|lit| lit.starts_with(word)),
+            _ => None,
+        };
+        match literal {
+            Some(literal) => {
+                self.pos += word.len();
+                self.out.push_str(literal);
+            }
+            None => {
+                self.bare_string();
+            }
+        }
+    }
+
+    /// Writes the text up to the next delimiter or line break as a string.
+    fn bare_string(&mut self) -> bool {
+        let rest = self.rest();
+        let end = rest.find([',', '}', ']', '\n', '\r']).unwrap_or(rest.len());
+        self.pos += end;
+        let text = rest[..end].trim_end();
+        write_string(&mut self.out, text);
+        true
+    }
+
+    /// Writes an unquoted key, which runs up to the `:` (or the next delimiter).
+    fn bare_key(&mut self) {
+        let rest = self.rest();
+        let end = rest.find([':', '=', ',', '}', ']', '\n', '\r']).unwrap_or(rest.len());
+        // Always make progress, even on a key that is nothing but a stray delimiter.
+        let end = end.max(rest.chars().next().map_or(0, char::len_utf8));
+        self.pos += end;
+        write_string(&mut self.out, rest[..end].trim_end());
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$'
+}
+
+fn hex4(text: &str) -> Option<u32> {
+    text.get(..4)
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+}
+
+fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for ch in text.chars() {
+        push_escaped(out, ch);
+    }
+    out.push('"');
+}
+
+/// Whether `rest`, the text after a closing quote, starts with something that may follow a value.
+fn ends_value(rest: &str) -> bool {
+    matches!(
+        rest.trim_start_matches([' ', '\t']).chars().next(),
+        None | Some(',' | ':' | '}' | ']' | '\n' | '\r' | '/')
+    )
+}
+
+fn push_escaped(out: &mut String, ch: char) {
+    match ch {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\u{8}' => out.push_str("\\b"),
+        '\u{c}' => out.push_str("\\f"),
+        '\0'..='\u{1f}' => out.push_str(&format!("\\u{:04x}", ch as u32)),
+        _ => out.push(ch),
+    }
+}
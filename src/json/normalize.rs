@@ -0,0 +1,384 @@
+//! Flattening nested documents into tabular rows, in the manner of `pandas.json_normalize`.
+//!
+//! Everything works on the dicts and lists `parse_json` returns, so values are placed
+//! in the rows as they are rather than copied; JSON text is parsed first.
+
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString};
+
+use super::queries::{document, elements};
+use super::Ancestors;
+
+/// Flattens nested objects into a single level, joining keys with `sep`.
+///
+/// Arrays and empty objects are kept as values, as `pandas.json_normalize` does.
+/// An array of objects is flattened object by object.
+///
+/// # Arguments
+/// * `obj` - A dict or a list of dicts, or JSON text holding one
+/// * `sep` - The separator placed between nested keys
+/// * `max_level` - How many levels of nesting to flatten; by default all of them
+///
+/// # Returns
+/// * The flattened dict, or a list of them
+///
+/// # Raises
+/// * `ValueError` - If two keys flatten to the same name, as `{"a.b": 1, "a": {"b": 2}}` does, or a dict contains itself
+/// * `RecursionError` - If objects are nested more than 1000 levels deep
+/// * `TypeError` - If `obj` is not an object or an array of objects, or a dict has a key that is not a `str`
+/// * `JSONDecodeError` - If `obj` is JSON text that does not parse
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// print(json.flatten({"id": 1, "user": {"name": "Ann", "address": {"city": "Oslo"}}, "tags": ["a"]}))
+/// # Output: {'id': 1, 'user.name': 'Ann', 'user.address.city': 'Oslo', 'tags': ['a']}
+/// print(json.flatten('{"a": {"b": {"c": 1}}}', sep="_", max_level=1))
+/// # Output: {'a_b': {'c': 1}}
+/// ```
+#[pyfunction]
+#[pyo3(signature = (obj, *, sep = ".", max_level = None))]
+pub fn flatten<'py>(obj: &Bound<'py, PyAny>, sep: &str, max_level: Option<usize>) -> PyResult<Bound<'py, PyAny>> {
+    let doc = document(obj)?;
+    if let Ok(record) = doc.downcast::<PyDict>() {
+        return Ok(flatten_record(record, "", sep, max_level)?.into_any());
+    }
+    let rows = records(&doc, "flatten")?
+        .iter()
+        .map(|record| flatten_record(record, "", sep, max_level))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(doc.py(), rows)?.into_any())
+}
+
+/// Rebuilds nested objects from keys joined with `sep`, undoing `flatten`.
+///
+/// # Arguments
+/// * `obj` - A flat dict or a list of them, or JSON text holding one
+/// * `sep` - The separator between nested keys
+///
+/// # Returns
+/// * The nested dict, or a list of them
+///
+/// # Raises
+/// * `ValueError` - If `sep` is empty, or a key such as `a.b` needs `a` to be an object while `a` has a value of its own
+/// * `TypeError` - If `obj` is not an object or an array of objects, or a dict has a key that is not a `str`
+/// * `JSONDecodeError` - If `obj` is JSON text that does not parse
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// print(json.unflatten({"id": 1, "user.name": "Ann", "user.address.city": "Oslo"}))
+/// # Output: {'id': 1, 'user': {'name': 'Ann', 'address': {'city': 'Oslo'}}}
+/// ```
+#[pyfunction]
+#[pyo3(signature = (obj, *, sep = "."))]
+pub fn unflatten<'py>(obj: &Bound<'py, PyAny>, sep: &str) -> PyResult<Bound<'py, PyAny>> {
+    if sep.is_empty() {
+        return Err(PyValueError::new_err("sep must not be empty"));
+    }
+    let doc = document(obj)?;
+    if let Ok(flat) = doc.downcast::<PyDict>() {
+        return Ok(unflatten_record(flat, sep)?.into_any());
+    }
+    let nested = records(&doc, "unflatten")?
+        .iter()
+        .map(|flat| unflatten_record(flat, sep))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(doc.py(), nested)?.into_any())
+}
+
+/// Turns records into flat row dicts, exploding the array at `record_path` into one row per item.
+///
+/// Without `record_path` every record becomes one flattened row. With it, each item of
+/// the array found by following `record_path` from a record becomes a row, and each
+/// `meta` field of that record is copied into all of its rows. A `record_path` with several
+/// keys explodes nested arrays: `["orders", "lines"]` gives one row per line of each order.
+/// Items that are not objects are placed in the rows under the key `"0"`. This mirrors
+/// `pandas.json_normalize`, except that `meta` paths always start from the record.
+///
+/// # Arguments
+/// * `records` - A list of dicts or a single dict, or JSON text holding one
+/// * `record_path` - A key or list of keys leading to the array to explode
+/// * `meta` - Fields of each record to add to its rows, as keys or lists of keys into nested objects
+/// * `sep` - The separator placed between nested keys
+/// * `max_level` - How many levels of nesting to flatten in each row; by default all of them
+/// * `meta_prefix` - A prefix for the meta keys
+/// * `record_prefix` - A prefix for the keys taken from the exploded items
+/// * `errors` - `"raise"` to fail when a record lacks a `meta` field, or `"ignore"` to use `None`
+///
+/// # Returns
+/// * A list of flat row dicts
+///
+/// # Raises
+/// * `KeyError` - If a record lacks a key of `record_path`, or a `meta` field while `errors` is `"raise"`
+/// * `TypeError` - If `record_path` leads to something other than an array or null, or the records are not objects
+/// * `ValueError` - If `meta` is given without `record_path`, a meta key clashes with a row key, two keys
+///   of a record flatten to the same name, a record contains itself, or `errors` is invalid
+/// * `RecursionError` - If a record is nested more than 1000 levels deep
+/// * `JSONDecodeError` - If `records` is JSON text that does not parse
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// data = [
+///     {"id": 1, "customer": {"name": "Ann"}, "lines": [{"sku": "A", "qty": 2}, {"sku": "B", "qty": 1}]},
+///     {"id": 2, "customer": {"name": "Bob"}, "lines": [{"sku": "C", "qty": 5}]},
+/// ]
+/// for row in json.to_rows(data, record_path="lines", meta=["id", ["customer", "name"]]):
+///     print(row)
+/// # Output:
+/// # {'sku': 'A', 'qty': 2, 'id': 1, 'customer.name': 'Ann'}
+/// # {'sku': 'B', 'qty': 1, 'id': 1, 'customer.name': 'Ann'}
+/// # {'sku': 'C', 'qty': 5, 'id': 2, 'customer.name': 'Bob'}
+/// ```
+#[pyfunction]
+#[pyo3(signature = (
+    records,
+    record_path = None,
+    meta = None,
+    *,
+    sep = ".",
+    max_level = None,
+    meta_prefix = None,
+    record_prefix = None,
+    errors = "raise"
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn to_rows<'py>(
+    records: &Bound<'py, PyAny>,
+    record_path: Option<&Bound<'py, PyAny>>,
+    meta: Option<&Bound<'py, PyAny>>,
+    sep: &str,
+    max_level: Option<usize>,
+    meta_prefix: Option<&str>,
+    record_prefix: Option<&str>,
+    errors: &str,
+) -> PyResult<Bound<'py, PyList>> {
+    let py = records.py();
+    let ignore_missing = match errors {
+        "raise" => false,
+        "ignore" => true,
+        _ => return Err(PyValueError::new_err(format!("errors must be 'raise' or 'ignore', not {errors:?}"))),
+    };
+    let record_path = match record_path {
+        Some(path) => keys(path)?,
+        None => Vec::new(),
+    };
+    let meta = match meta {
+        Some(meta) => meta
+            .try_iter()?
+            .map(|field| {
+                let path = keys(&field?)?;
+                Ok((format!("{}{}", meta_prefix.unwrap_or(""), path.join(sep)), path))
+            })
+            .collect::<PyResult<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    if record_path.is_empty() && !meta.is_empty() {
+        return Err(PyValueError::new_err("meta requires a record_path"));
+    }
+    let record_prefix = record_prefix.unwrap_or("");
+
+    let doc = document(records)?;
+    let records = match doc.downcast::<PyDict>() {
+        Ok(record) => vec![record.clone()],
+        Err(_) => self::records(&doc, "to_rows")?,
+    };
+    let rows = PyList::empty(py);
+    for record in &records {
+        if record_path.is_empty() {
+            rows.append(flatten_record(record, record_prefix, sep, max_level)?)?;
+            continue;
+        }
+        let mut meta_values = Vec::with_capacity(meta.len());
+        for (name, path) in &meta {
+            match lookup(record, path)? {
+                Some(value) => meta_values.push((name, value)),
+                None if ignore_missing => meta_values.push((name, py.None().into_bound(py))),
+                None => {
+                    return Err(PyKeyError::new_err(format!(
+                        "Key {name:?} not found; pass errors='ignore' to use None for missing meta fields"
+                    )))
+                }
+            }
+        }
+        let mut items = Vec::new();
+        explode(record, &record_path, 0, &mut items)?;
+        for item in items {
+            let row = match item.downcast::<PyDict>() {
+                Ok(item) => flatten_record(item, record_prefix, sep, max_level)?,
+                Err(_) => {
+                    let row = PyDict::new(py);
+                    row.set_item(format!("{record_prefix}0"), item)?;
+                    row
+                }
+            };
+            for (name, value) in &meta_values {
+                if row.contains(name.as_str())? {
+                    return Err(PyValueError::new_err(format!(
+                        "Meta key {name:?} clashes with a key of the records; use meta_prefix or record_prefix"
+                    )));
+                }
+                row.set_item(name.as_str(), value)?;
+            }
+            rows.append(row)?;
+        }
+    }
+    Ok(rows)
+}
+
+fn flatten_record<'py>(
+    record: &Bound<'py, PyDict>,
+    prefix: &str,
+    sep: &str,
+    max_level: Option<usize>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let flat = PyDict::new(record.py());
+    flatten_into(&flat, &mut prefix.to_string(), record, sep, 0, max_level, &mut Ancestors::default())?;
+    Ok(flat)
+}
+
+fn flatten_into(
+    flat: &Bound<'_, PyDict>,
+    key: &mut String,
+    dict: &Bound<'_, PyDict>,
+    sep: &str,
+    level: usize,
+    max_level: Option<usize>,
+    ancestors: &mut Ancestors,
+) -> PyResult<()> {
+    ancestors.enter(dict)?;
+    for (name, value) in dict.iter() {
+        let len = key.len();
+        key.push_str(str_key(&name)?);
+        match value.downcast::<PyDict>() {
+            Ok(nested) if !nested.is_empty() && max_level.is_none_or(|max| level < max) => {
+                key.push_str(sep);
+                flatten_into(flat, key, nested, sep, level + 1, max_level, ancestors)?;
+            }
+            _ if flat.contains(key.as_str())? => {
+                return Err(PyValueError::new_err(format!("Flattened key {key:?} occurs more than once")));
+            }
+            _ => flat.set_item(key.as_str(), value)?,
+        }
+        key.truncate(len);
+    }
+    ancestors.leave();
+    Ok(())
+}
+
+fn unflatten_record<'py>(flat: &Bound<'py, PyDict>, sep: &str) -> PyResult<Bound<'py, PyDict>> {
+    let py = flat.py();
+    let root = PyDict::new(py);
+    for (key, value) in flat.iter() {
+        let key = str_key(&key)?;
+        let parts: Vec<&str> = key.split(sep).collect();
+        let (last, parents) = parts.split_last().unwrap_or((&"", &[]));
+        let conflict = |end: usize| {
+            PyValueError::new_err(format!("Key {key:?} conflicts with {:?}", parts[..end].join(sep)))
+        };
+        let mut node = root.clone();
+        for (i, part) in parents.iter().enumerate() {
+            node = match node.get_item(part)? {
+                Some(child) => child.downcast_into::<PyDict>().map_err(|_| conflict(i + 1))?,
+                None => {
+                    let child = PyDict::new(py);
+                    node.set_item(part, &child)?;
+                    child
+                }
+            };
+        }
+        if node.contains(last)? {
+            return Err(conflict(parts.len()));
+        }
+        // Nested dicts are copied, as keys like `a.b` may be merged into them.
+        match value.downcast::<PyDict>() {
+            Ok(dict) => node.set_item(last, dict.copy()?)?,
+            Err(_) => node.set_item(last, value)?,
+        }
+    }
+    Ok(root)
+}
+
+/// Collects the items of the arrays found by following `path` from `value`.
+fn explode<'py>(
+    value: &Bound<'py, PyDict>,
+    path: &[String],
+    depth: usize,
+    items: &mut Vec<Bound<'py, PyAny>>,
+) -> PyResult<()> {
+    let Some(found) = value.get_item(&path[depth])? else {
+        return Err(PyKeyError::new_err(path[..=depth].join(".")));
+    };
+    if found.is_none() {
+        return Ok(());
+    }
+    let Some(elements) = elements(&found) else {
+        return Err(PyTypeError::new_err(format!(
+            "record_path must lead to an array or null, but {:?} is {}",
+            path[..=depth].join("."),
+            found.get_type().name()?
+        )));
+    };
+    if depth + 1 == path.len() {
+        items.extend(elements);
+        return Ok(());
+    }
+    for element in elements {
+        let Ok(element) = element.downcast::<PyDict>() else {
+            return Err(PyTypeError::new_err(format!(
+                "record_path {:?} must hold objects, not {}",
+                path[..=depth].join("."),
+                element.get_type().name()?
+            )));
+        };
+        explode(element, path, depth + 1, items)?;
+    }
+    Ok(())
+}
+
+/// Follows `path` through nested objects, returning `None` if it leads nowhere.
+fn lookup<'py>(record: &Bound<'py, PyDict>, path: &[String]) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let mut value = record.clone().into_any();
+    for key in path {
+        let Ok(dict) = value.downcast::<PyDict>() else {
+            return Ok(None);
+        };
+        match dict.get_item(key)? {
+            Some(next) => value = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(value))
+}
+
+/// The dicts of an array of objects.
+fn records<'py>(doc: &Bound<'py, PyAny>, function: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let not_records = || {
+        PyTypeError::new_err(format!("{function} expects an object or an array of objects"))
+    };
+    elements(doc)
+        .ok_or_else(not_records)?
+        .into_iter()
+        .map(|item| item.downcast_into::<PyDict>().map_err(|_| not_records()))
+        .collect()
+}
+
+/// A key or a list of keys.
+fn keys(path: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+    match path.downcast::<PyString>() {
+        Ok(key) => Ok(vec![key.to_string()]),
+        Err(_) => path.extract(),
+    }
+}
+
+fn str_key<'a>(key: &'a Bound<'_, PyAny>) -> PyResult<&'a str> {
+    match key.downcast::<PyString>() {
+        Ok(key) => key.to_str(),
+        Err(_) => Err(PyTypeError::new_err(format!("keys must be str, not {}", key.get_type().name()?))),
+    }
+}
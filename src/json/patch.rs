@@ -0,0 +1,525 @@
+//! JSON Patch (RFC 6902), JSON Merge Patch (RFC 7396) and JSON Pointer (RFC 6901).
+//!
+//! Everything works on the dicts, lists and scalars that `parse_json` returns, so a
+//! document never has to be serialized to be diffed or patched. Functions that return a
+//! new document share no containers with their inputs; scalars are immutable and are
+//! shared as they are.
+
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
+
+use super::queries::{elements, json_eq, kind, Kind};
+use super::Ancestors;
+
+/// Computes the JSON Patch (RFC 6902) that turns `a` into `b`.
+///
+/// Objects are compared key by key and arrays element by element, so the patch only
+/// touches what changed. Applying the result to `a` with `apply_patch` gives a document
+/// equal to `b`.
+///
+/// # Arguments
+/// * `a` - The source document
+/// * `b` - The target document
+///
+/// # Returns
+/// * A list of patch operations, as dicts with `op`, `path` and `value` keys
+///
+/// # Raises
+/// * `TypeError` - If a dict has a key that is not a `str`
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// patch = json.diff({"a": 1, "b": [1, 2]}, {"a": 2, "b": [1, 2, 3]})
+/// print(patch)
+/// # Output: [{'op': 'replace', 'path': '/a', 'value': 2}, {'op': 'add', 'path': '/b/2', 'value': 3}]
+/// ```
+#[pyfunction]
+pub fn diff<'py>(a: &Bound<'py, PyAny>, b: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyList>> {
+    let patch = PyList::empty(a.py());
+    diff_into(&patch, &mut String::new(), a, b, &mut Ancestors::default())?;
+    Ok(patch)
+}
+
+/// Applies a JSON Patch (RFC 6902) to a document.
+///
+/// Operations are applied in order. By default the patch is atomic: `doc` is left
+/// untouched and a new document is returned, or nothing changes at all if an operation
+/// fails.
+///
+/// # Arguments
+/// * `doc` - The document to patch
+/// * `patch` - A list of operations, or the patch as JSON text
+/// * `in_place` - Modify `doc` itself instead of a copy; a failed operation then leaves the earlier ones applied
+///
+/// # Returns
+/// * The patched document
+///
+/// # Raises
+/// * `ValueError` - If an operation is malformed, refers to a location that does not exist or a `test` fails
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// doc = {"users": [{"name": "Ann"}]}
+/// patched = json.apply_patch(doc, [
+///     {"op": "add", "path": "/users/-", "value": {"name": "Bob"}},
+///     {"op": "replace", "path": "/users/0/name", "value": "Anna"},
+/// ])
+/// print(patched)  # Output: {'users': [{'name': 'Anna'}, {'name': 'Bob'}]}
+/// ```
+#[pyfunction]
+#[pyo3(signature = (doc, patch, *, in_place = false))]
+pub fn apply_patch<'py>(
+    doc: &Bound<'py, PyAny>,
+    patch: &Bound<'py, PyAny>,
+    in_place: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = doc.py();
+    let operations = match elements(patch) {
+        Some(operations) => operations,
+        None => {
+            let parsed = super::parse_json(py, patch)?.into_bound(py);
+            elements(&parsed).ok_or_else(|| PyValueError::new_err("a JSON Patch must be an array of operations"))?
+        }
+    };
+    let mut doc = if in_place { doc.clone() } else { deep_copy(doc)? };
+    for (index, operation) in operations.iter().enumerate() {
+        doc = apply_operation(doc, operation).map_err(|reason| {
+            PyValueError::new_err(format!("JSON Patch operation {index} failed: {reason}"))
+        })?;
+    }
+    Ok(doc)
+}
+
+/// Applies a JSON Merge Patch (RFC 7396) to a document.
+///
+/// Keys of `patch` replace those of `doc`, recursively for nested objects, and keys set
+/// to `None` are removed. A patch that is not an object replaces the document entirely.
+///
+/// # Arguments
+/// * `doc` - The document to patch; it is not modified
+/// * `patch` - The merge patch
+///
+/// # Returns
+/// * The patched document
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// config = {"host": "db", "pool": {"min": 1, "max": 4}, "debug": True}
+/// print(json.merge_patch(config, {"pool": {"max": 8}, "debug": None}))
+/// # Output: {'host': 'db', 'pool': {'min': 1, 'max': 8}}
+/// ```
+#[pyfunction]
+pub fn merge_patch<'py>(doc: &Bound<'py, PyAny>, patch: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let Ok(patch) = patch.downcast::<PyDict>() else {
+        return deep_copy(patch);
+    };
+    let target = match doc.downcast::<PyDict>() {
+        Ok(doc) => deep_copy(doc)?.downcast_into::<PyDict>()?,
+        Err(_) => PyDict::new(doc.py()),
+    };
+    merge_into(&target, patch, &mut Ancestors::default())?;
+    Ok(target.into_any())
+}
+
+/// Returns the value a JSON Pointer (RFC 6901) refers to, like `dict.get`.
+///
+/// # Arguments
+/// * `doc` - The document
+/// * `pointer` - A JSON Pointer such as `"/users/0/name"`; `""` is the whole document
+/// * `default` - Returned when the location does not exist
+///
+/// # Returns
+/// * The value at `pointer` (the object itself, not a copy), or `default`
+///
+/// # Raises
+/// * `ValueError` - If `pointer` is not a valid JSON Pointer
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// doc = {"users": [{"name": "Ann"}], "a/b": 1}
+/// print(json.pointer_get(doc, "/users/0/name"))  # Output: Ann
+/// print(json.pointer_get(doc, "/a~1b"))          # Output: 1
+/// print(json.pointer_get(doc, "/users/5", None))  # Output: None
+/// ```
+#[pyfunction]
+#[pyo3(signature = (doc, pointer, default = None))]
+pub fn pointer_get<'py>(
+    doc: &Bound<'py, PyAny>,
+    pointer: &str,
+    default: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tokens = parse_pointer(pointer).map_err(PyValueError::new_err)?;
+    Ok(resolve(doc, &tokens).unwrap_or_else(|_| default.unwrap_or_else(|| doc.py().None().into_bound(doc.py()))))
+}
+
+/// Sets the value at a JSON Pointer (RFC 6901), modifying the document in place.
+///
+/// The parent of the location must exist. In an array, an index replaces the element
+/// there and `-` appends.
+///
+/// # Arguments
+/// * `doc` - The document to modify
+/// * `pointer` - A JSON Pointer such as `"/users/0/name"`
+/// * `value` - The value to store
+///
+/// # Returns
+/// * The document, which is `value` itself when `pointer` is `""`
+///
+/// # Raises
+/// * `KeyError` - If the parent of the location does not exist or the index is out of range
+/// * `ValueError` - If `pointer` is not a valid JSON Pointer
+/// * `TypeError` - If the parent is not a dict or list
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// doc = {"users": [{"name": "Ann"}]}
+/// json.pointer_set(doc, "/users/0/age", 31)
+/// json.pointer_set(doc, "/users/-", {"name": "Bob"})
+/// print(doc)  # Output: {'users': [{'name': 'Ann', 'age': 31}, {'name': 'Bob'}]}
+/// ```
+#[pyfunction]
+pub fn pointer_set<'py>(
+    doc: &Bound<'py, PyAny>,
+    pointer: &str,
+    value: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let tokens = parse_pointer(pointer).map_err(PyValueError::new_err)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        return Ok(value.clone());
+    };
+    let parent = resolve(doc, parents).map_err(|_| PyKeyError::new_err(pointer.to_string()))?;
+    if let Ok(dict) = parent.downcast::<PyDict>() {
+        dict.set_item(last, value)?;
+    } else if let Ok(list) = parent.downcast::<PyList>() {
+        match array_index(last, list.len(), true) {
+            Ok(index) if index == list.len() => list.append(value)?,
+            Ok(index) => list.set_item(index, value)?,
+            Err(_) => return Err(PyKeyError::new_err(pointer.to_string())),
+        }
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "cannot set {pointer:?}: the parent is {}, not a dict or list",
+            parent.get_type().name()?
+        )));
+    }
+    Ok(doc.clone())
+}
+
+// JSON Pointer ---------------------------------------------------------------
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!("invalid JSON Pointer {pointer:?}: it must be empty or start with '/'"));
+    };
+    rest.split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(format!("invalid JSON Pointer {pointer:?}: '~' must be followed by '0' or '1'")),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// Appends `token` to `pointer`, escaped.
+fn push_token(pointer: &mut String, token: &str) {
+    pointer.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            c => pointer.push(c),
+        }
+    }
+}
+
+/// The index an array reference token refers to; `-` (the end) only when `allow_end` is set.
+fn array_index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    if token == "-" && allow_end {
+        return Ok(len);
+    }
+    let valid = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit()) && (token == "0" || !token.starts_with('0'));
+    let index = token.parse::<usize>().ok().filter(|_| valid).ok_or_else(|| format!("{token:?} is not an array index"))?;
+    let limit = if allow_end { len } else { len.saturating_sub(1) };
+    if index > limit || (!allow_end && len == 0) {
+        return Err(format!("index {index} is out of range for an array of length {len}"));
+    }
+    Ok(index)
+}
+
+/// The value at `tokens`, or a description of why it does not exist.
+fn resolve<'py>(doc: &Bound<'py, PyAny>, tokens: &[String]) -> Result<Bound<'py, PyAny>, String> {
+    let mut current = doc.clone();
+    for token in tokens {
+        current = if let Ok(dict) = current.downcast::<PyDict>() {
+            match dict.get_item(token) {
+                Ok(Some(value)) => value,
+                _ => return Err(format!("key {token:?} does not exist")),
+            }
+        } else if let Some(items) = elements(&current) {
+            items[array_index(token, items.len(), false)?].clone()
+        } else {
+            return Err(format!("cannot look up {token:?} in a scalar"));
+        };
+    }
+    Ok(current)
+}
+
+// JSON Patch -------------------------------------------------------------------
+
+/// Applies one operation to `doc`, returning the new root (which only changes when the
+/// operation targets the root itself).
+fn apply_operation<'py>(doc: Bound<'py, PyAny>, operation: &Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>, String> {
+    let operation = operation.downcast::<PyDict>().map_err(|_| "an operation must be an object".to_string())?;
+    let member = |name: &str| operation.get_item(name).ok().flatten();
+    let string_member = |name: &str| -> Result<String, String> {
+        member(name)
+            .and_then(|value| value.downcast::<PyString>().ok().and_then(|s| s.to_str().ok().map(str::to_string)))
+            .ok_or_else(|| format!("{name:?} must be a string"))
+    };
+    let value_member = || member("value").ok_or_else(|| "\"value\" is missing".to_string());
+
+    let op = string_member("op")?;
+    let path = string_member("path")?;
+    let tokens = parse_pointer(&path)?;
+    let context = |reason: String| format!("{op} {path:?}: {reason}");
+    match op.as_str() {
+        "add" => add(doc, &tokens, deep_copy(&value_member()?).map_err(|e| e.to_string())?).map_err(context),
+        "remove" => remove(&doc, &tokens).map(|_| doc).map_err(context),
+        "replace" => {
+            resolve(&doc, &tokens).map_err(context)?;
+            let value = deep_copy(&value_member()?).map_err(|e| e.to_string())?;
+            match tokens.is_empty() {
+                true => Ok(value),
+                false => {
+                    remove(&doc, &tokens).map_err(context)?;
+                    add(doc, &tokens, value).map_err(context)
+                }
+            }
+        }
+        "move" | "copy" => {
+            let from = string_member("from")?;
+            let from_tokens = parse_pointer(&from)?;
+            if op == "move" {
+                if tokens.len() > from_tokens.len() && tokens.starts_with(&from_tokens) {
+                    return Err(context(format!("cannot move {from:?} into one of its own children")));
+                }
+                let value = remove(&doc, &from_tokens).map_err(context)?;
+                add(doc, &tokens, value).map_err(context)
+            } else {
+                let value = resolve(&doc, &from_tokens).map_err(context)?;
+                add(doc, &tokens, deep_copy(&value).map_err(|e| e.to_string())?).map_err(context)
+            }
+        }
+        "test" => {
+            let actual = resolve(&doc, &tokens).map_err(context)?;
+            match json_eq(&actual, &value_member()?) {
+                Ok(true) => Ok(doc),
+                Ok(false) => Err(context("the value differs".to_string())),
+                Err(e) => Err(context(e.to_string())),
+            }
+        }
+        _ => Err(format!("unknown operation {op:?}")),
+    }
+}
+
+fn add<'py>(doc: Bound<'py, PyAny>, tokens: &[String], value: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>, String> {
+    let Some((last, parents)) = tokens.split_last() else {
+        return Ok(value);
+    };
+    let parent = resolve(&doc, parents)?;
+    if let Ok(dict) = parent.downcast::<PyDict>() {
+        dict.set_item(last, value).map_err(|e| e.to_string())?;
+    } else if let Ok(list) = parent.downcast::<PyList>() {
+        let index = array_index(last, list.len(), true)?;
+        list.insert(index, value).map_err(|e| e.to_string())?;
+    } else {
+        return Err(format!("cannot add {last:?} to {}", type_name(&parent)));
+    }
+    Ok(doc)
+}
+
+/// Removes the value at `tokens` and returns it.
+fn remove<'py>(doc: &Bound<'py, PyAny>, tokens: &[String]) -> Result<Bound<'py, PyAny>, String> {
+    let Some((last, parents)) = tokens.split_last() else {
+        return Err("cannot remove the whole document".to_string());
+    };
+    let parent = resolve(doc, parents)?;
+    if let Ok(dict) = parent.downcast::<PyDict>() {
+        let value = resolve(&parent, std::slice::from_ref(last))?;
+        dict.del_item(last).map_err(|e| e.to_string())?;
+        Ok(value)
+    } else if let Ok(list) = parent.downcast::<PyList>() {
+        let index = array_index(last, list.len(), false)?;
+        let value = list.get_item(index).map_err(|e| e.to_string())?;
+        list.del_item(index).map_err(|e| e.to_string())?;
+        Ok(value)
+    } else {
+        Err(format!("cannot remove {last:?} from {}", type_name(&parent)))
+    }
+}
+
+fn type_name(value: &Bound<'_, PyAny>) -> String {
+    value.get_type().name().map(|name| name.to_string()).unwrap_or_default()
+}
+
+/// Appends to `patch` the operations that turn `a` into `b`, both located at `path`.
+fn diff_into<'py>(
+    patch: &Bound<'py, PyList>,
+    path: &mut String,
+    a: &Bound<'py, PyAny>,
+    b: &Bound<'py, PyAny>,
+    ancestors: &mut Ancestors,
+) -> PyResult<()> {
+    if json_eq(a, b)? {
+        return Ok(());
+    }
+    match (kind(a), kind(b)) {
+        (Kind::Object, Kind::Object) => {
+            ancestors.enter(a)?;
+            let (a, b) = (a.downcast::<PyDict>()?, b.downcast::<PyDict>()?);
+            let len = path.len();
+            for (key, value) in a.iter() {
+                push_token(path, string_key(&key)?);
+                match b.get_item(&key)? {
+                    Some(other) => diff_into(patch, path, &value, &other, ancestors)?,
+                    None => push_operation(patch, "remove", path, None)?,
+                }
+                path.truncate(len);
+            }
+            for (key, value) in b.iter() {
+                if !a.contains(&key)? {
+                    push_token(path, string_key(&key)?);
+                    push_operation(patch, "add", path, Some(&value))?;
+                    path.truncate(len);
+                }
+            }
+            ancestors.leave();
+        }
+        (Kind::Array, Kind::Array) => {
+            ancestors.enter(a)?;
+            let (a, b) = (elements(a).unwrap_or_default(), elements(b).unwrap_or_default());
+            let len = path.len();
+            let common = a.len().min(b.len());
+            for i in 0..common {
+                push_token(path, &i.to_string());
+                diff_into(patch, path, &a[i], &b[i], ancestors)?;
+                path.truncate(len);
+            }
+            // Remove from the end so that the indices of earlier removals stay valid.
+            for i in (common..a.len()).rev() {
+                push_token(path, &i.to_string());
+                push_operation(patch, "remove", path, None)?;
+                path.truncate(len);
+            }
+            for (i, value) in b.iter().enumerate().skip(common) {
+                push_token(path, &i.to_string());
+                push_operation(patch, "add", path, Some(value))?;
+                path.truncate(len);
+            }
+            ancestors.leave();
+        }
+        _ => push_operation(patch, "replace", path, Some(b))?,
+    }
+    Ok(())
+}
+
+fn string_key<'a>(key: &'a Bound<'_, PyAny>) -> PyResult<&'a str> {
+    match key.downcast::<PyString>() {
+        Ok(key) => key.to_str(),
+        Err(_) => Err(PyTypeError::new_err(format!(
+            "JSON Patch can only address str keys, not {}",
+            key.get_type().name()?
+        ))),
+    }
+}
+
+fn push_operation<'py>(patch: &Bound<'py, PyList>, op: &str, path: &str, value: Option<&Bound<'py, PyAny>>) -> PyResult<()> {
+    let operation = PyDict::new(patch.py());
+    operation.set_item("op", op)?;
+    operation.set_item("path", path)?;
+    if let Some(value) = value {
+        operation.set_item("value", deep_copy(value)?)?;
+    }
+    patch.append(operation)
+}
+
+// JSON Merge Patch ---------------------------------------------------------------
+
+fn merge_into<'py>(target: &Bound<'py, PyDict>, patch: &Bound<'py, PyDict>, ancestors: &mut Ancestors) -> PyResult<()> {
+    ancestors.enter(patch)?;
+    for (key, value) in patch.iter() {
+        if value.is_none() {
+            if target.contains(&key)? {
+                target.del_item(&key)?;
+            }
+            continue;
+        }
+        match (value.downcast::<PyDict>(), target.get_item(&key)?) {
+            (Ok(value), Some(existing)) if existing.is_instance_of::<PyDict>() => {
+                merge_into(existing.downcast::<PyDict>()?, value, ancestors)?;
+            }
+            (Ok(value), _) => {
+                let nested = PyDict::new(target.py());
+                merge_into(&nested, value, ancestors)?;
+                target.set_item(key, nested)?;
+            }
+            (Err(_), _) => target.set_item(key, deep_copy(&value)?)?,
+        }
+    }
+    ancestors.leave();
+    Ok(())
+}
+
+/// Copies the dicts and lists (tuples become lists) of a document; everything else is shared.
+fn deep_copy<'py>(value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    copy_below(value, &mut Ancestors::default())
+}
+
+fn copy_below<'py>(value: &Bound<'py, PyAny>, ancestors: &mut Ancestors) -> PyResult<Bound<'py, PyAny>> {
+    if let Ok(dict) = value.downcast::<PyDict>() {
+        ancestors.enter(value)?;
+        let copy = PyDict::new(value.py());
+        for (key, item) in dict.iter() {
+            copy.set_item(key, copy_below(&item, ancestors)?)?;
+        }
+        ancestors.leave();
+        return Ok(copy.into_any());
+    }
+    if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        ancestors.enter(value)?;
+        let items = elements(value)
+            .unwrap_or_default()
+            .iter()
+            .map(|item| copy_below(item, ancestors))
+            .collect::<PyResult<Vec<_>>>()?;
+        ancestors.leave();
+        return Ok(PyList::new(value.py(), items)?.into_any());
+    }
+    Ok(value.clone())
+}
@@ -0,0 +1,266 @@
+//! Querying parsed documents with JMESPath or JSONPath expressions.
+//!
+//! Both languages are evaluated directly over Python objects, so a document that has
+//! already been parsed is queried without converting it back to JSON, and the results
+//! are the original objects rather than copies.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyByteArray, PyBytes, PyDict, PyFloat, PyInt, PyList, PyMemoryView, PyString, PyTuple};
+
+use super::{jmespath, jsonpath, parse_json, Ancestors};
+
+/// Evaluates a JMESPath or JSONPath expression against a document.
+///
+/// Expressions starting with `$` are JSONPath (RFC 9535) and return the list of every
+/// matching value. Anything else is JMESPath and returns a single result, `None` when
+/// nothing matches. Compiled expressions are cached, so calling `query` in a loop with
+/// the same expression only parses it once.
+///
+/// # Arguments
+/// * `doc` - A parsed document (dicts, lists and scalars), or JSON text as `str`, `bytes`, `bytearray` or `memoryview`
+/// * `expression` - A JMESPath or JSONPath expression, or a `Query` returned by `compile`
+///
+/// # Returns
+/// * The JMESPath result, or a list of matches for JSONPath
+///
+/// # Raises
+/// * `ValueError` - If the expression is invalid or uses an unknown function
+/// * `TypeError` - If a JMESPath function is called with arguments of the wrong type
+/// * `JSONDecodeError` - If `doc` is JSON text that does not parse
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// doc = {"users": [{"name": "Ann", "age": 31}, {"name": "Bob", "age": 17}]}
+/// print(fastpy_rs.json.query(doc, "users[?age > `18`].name"))  # Output: ['Ann']
+/// print(fastpy_rs.json.query(doc, "$.users[*].age"))            # Output: [31, 17]
+/// print(fastpy_rs.json.query('{"a": {"b": [1, 2]}}', "a.b[-1]"))  # Output: 2
+/// ```
+#[pyfunction]
+pub fn query<'py>(doc: &Bound<'py, PyAny>, expression: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let compiled = match expression.downcast::<Query>() {
+        Ok(query) => query.get().compiled.clone(),
+        Err(_) => cached(expression.py(), expression.extract::<&str>()?)?,
+    };
+    compiled.search(&document(doc)?)
+}
+
+/// Compiles a JMESPath or JSONPath expression once for repeated use.
+///
+/// # Arguments
+/// * `expression` - A JMESPath expression, or a JSONPath expression starting with `$`
+///
+/// # Returns
+/// * A `Query` whose `search(doc)` method evaluates the expression
+///
+/// # Raises
+/// * `ValueError` - If the expression is invalid or uses an unknown function
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// names = fastpy_rs.json.compile("items[].name")
+/// for page in pages:
+///     print(names.search(page))
+/// ```
+#[pyfunction]
+pub fn compile(py: Python<'_>, expression: &str) -> PyResult<Query> {
+    Query::new(py, expression)
+}
+
+/// A compiled JMESPath or JSONPath expression.
+#[pyclass(module = "fastpy_rs.json", frozen)]
+pub struct Query {
+    expression: String,
+    compiled: Arc<Compiled>,
+}
+
+#[pymethods]
+impl Query {
+    #[new]
+    fn new(py: Python<'_>, expression: &str) -> PyResult<Self> {
+        Ok(Query { expression: expression.to_string(), compiled: cached(py, expression)? })
+    }
+
+    /// Evaluates the expression against `doc`, which may be a parsed document or JSON text.
+    fn search<'py>(&self, doc: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        self.compiled.search(&document(doc)?)
+    }
+
+    /// The source text of the expression.
+    #[getter]
+    fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Query({:?})", self.expression)
+    }
+}
+
+enum Compiled {
+    JmesPath(jmespath::Ast),
+    JsonPath(jsonpath::JsonPath),
+}
+
+impl Compiled {
+    fn search<'py>(&self, doc: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            Compiled::JmesPath(ast) => jmespath::search(ast, doc),
+            Compiled::JsonPath(path) => Ok(PyList::new(doc.py(), path.select(doc)?)?.into_any()),
+        }
+    }
+}
+
+/// Compiled expressions by source text; cleared wholesale once it grows past `QUERY_CACHE_SIZE`.
+static QUERY_CACHE: Lazy<Mutex<HashMap<String, Arc<Compiled>>>> = Lazy::new(Default::default);
+const QUERY_CACHE_SIZE: usize = 256;
+
+fn cached(py: Python<'_>, expression: &str) -> PyResult<Arc<Compiled>> {
+    if let Some(compiled) = QUERY_CACHE.lock().unwrap().get(expression) {
+        return Ok(compiled.clone());
+    }
+    let compiled = Arc::new(if expression.trim_start().starts_with('$') {
+        Compiled::JsonPath(jsonpath::JsonPath::parse(py, expression)?)
+    } else {
+        Compiled::JmesPath(jmespath::parse(py, expression)?)
+    });
+    let mut cache = QUERY_CACHE.lock().unwrap();
+    if cache.len() >= QUERY_CACHE_SIZE {
+        cache.clear();
+    }
+    cache.insert(expression.to_string(), compiled.clone());
+    Ok(compiled)
+}
+
+/// JSON text is parsed first; anything else is taken to be an already parsed document.
+pub(super) fn document<'py>(doc: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    if doc.is_instance_of::<PyString>()
+        || doc.is_instance_of::<PyBytes>()
+        || doc.is_instance_of::<PyByteArray>()
+        || doc.is_instance_of::<PyMemoryView>()
+    {
+        return Ok(parse_json(doc.py(), doc)?.into_bound(doc.py()));
+    }
+    Ok(doc.clone())
+}
+
+/// The JSON type of a Python value, as both query languages see it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+    /// Not a JSON value at all, e.g. a `datetime` in a hand-built document.
+    Other,
+}
+
+pub(super) fn kind(value: &Bound<'_, PyAny>) -> Kind {
+    if value.is_none() {
+        Kind::Null
+    } else if value.is_instance_of::<PyBool>() {
+        Kind::Bool
+    } else if value.is_instance_of::<PyInt>() || value.is_instance_of::<PyFloat>() {
+        Kind::Number
+    } else if value.is_instance_of::<PyString>() {
+        Kind::String
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        Kind::Array
+    } else if value.is_instance_of::<PyDict>() {
+        Kind::Object
+    } else {
+        Kind::Other
+    }
+}
+
+/// The elements of a list or tuple, or `None` for anything else.
+pub(super) fn elements<'py>(value: &Bound<'py, PyAny>) -> Option<Vec<Bound<'py, PyAny>>> {
+    if let Ok(list) = value.downcast::<PyList>() {
+        return Some(list.iter().collect());
+    }
+    if let Ok(tuple) = value.downcast::<PyTuple>() {
+        return Some(tuple.iter().collect());
+    }
+    None
+}
+
+/// JSON equality: like `==`, except that booleans never equal numbers.
+pub(super) fn json_eq(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<bool> {
+    equal(a, b, &mut Ancestors::default())
+}
+
+/// `json_eq` below the containers of `a` in `ancestors`.
+fn equal(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>, ancestors: &mut Ancestors) -> PyResult<bool> {
+    let kind_a = kind(a);
+    if kind_a != kind(b) {
+        return Ok(false);
+    }
+    match kind_a {
+        Kind::Array => {
+            let (items_a, items_b) = (elements(a).unwrap_or_default(), elements(b).unwrap_or_default());
+            if items_a.len() != items_b.len() {
+                return Ok(false);
+            }
+            ancestors.enter(a)?;
+            for (x, y) in items_a.iter().zip(&items_b) {
+                if !equal(x, y, ancestors)? {
+                    return Ok(false);
+                }
+            }
+            ancestors.leave();
+            Ok(true)
+        }
+        Kind::Object => {
+            let (dict_a, dict_b) = (a.downcast::<PyDict>()?, b.downcast::<PyDict>()?);
+            if dict_a.len() != dict_b.len() {
+                return Ok(false);
+            }
+            ancestors.enter(a)?;
+            for (key, x) in dict_a.iter() {
+                match dict_b.get_item(key)? {
+                    Some(y) if equal(&x, &y, ancestors)? => {}
+                    _ => return Ok(false),
+                }
+            }
+            ancestors.leave();
+            Ok(true)
+        }
+        _ => a.eq(b),
+    }
+}
+
+/// Indices selected by `[start:end:step]` on a sequence of `len` items, as in Python and RFC 9535.
+///
+/// `step` must not be zero.
+pub(super) fn slice_indices(len: usize, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let len = len as i64;
+    let normalize = |i: i64| if i >= 0 { i } else { len + i };
+    let mut indices = Vec::new();
+    if step > 0 {
+        let lower = start.map_or(0, |s| normalize(s).clamp(0, len));
+        let upper = end.map_or(len, |e| normalize(e).clamp(0, len));
+        let mut i = lower;
+        while i < upper {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else {
+        let upper = start.map_or(len - 1, |s| normalize(s).clamp(-1, len - 1));
+        let lower = end.map_or(-1, |e| normalize(e).clamp(-1, len - 1));
+        let mut i = upper;
+        while i > lower {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    indices
+}
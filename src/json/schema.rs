@@ -0,0 +1,241 @@
+//! JSON Schema validation (drafts 4, 6, 7, 2019-09 and 2020-12).
+//!
+//! Schemas are compiled once by the `jsonschema` crate, which validates `serde_json::Value`s.
+//! Documents passed as JSON text are parsed by the same decoder as `parse_json` first, so
+//! text and Python objects are held to the same nesting limit, and ints too big for a
+//! `Value` are rejected rather than checked as floats.
+
+use jsonschema::{Draft, ValidationError as SchemaError};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyIterator, PyList, PyString, PyType};
+use serde_json::{Map, Number, Value};
+
+use super::queries::{elements, kind, Kind};
+use super::{dict_key, is_json_text, object_to_value, parse_error, parse_slice, with_json_bytes, Ancestors};
+
+/// A compiled JSON Schema.
+///
+/// # Arguments
+/// * `schema` - The schema as a dict (or bool), or as JSON text
+/// * `draft` - `4`, `6`, `7`, `2019` or `2020`; by default the draft is taken from `$schema`, falling back to 2020-12
+///
+/// # Raises
+/// * `ValueError` - If the schema is not a valid JSON Schema or `draft` is not supported
+/// * `JSONDecodeError` - If the schema is JSON text that does not parse
+/// * `RecursionError` - If the schema, or a document checked against it, is nested more
+///   than 1000 levels deep
+///
+/// Documents are checked the same way, and raise `ValueError` if they hold an int that does
+/// not fit in 64 bits.
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs.json as json
+///
+/// validator = json.Validator({"type": "object", "required": ["id"], "properties": {"id": {"type": "integer"}}})
+/// print(validator.is_valid('{"id": 7}'))     # Output: True
+/// for error in validator.iter_errors({"id": "7"}):
+///     print(error.path, error.message)         # Output: /id "7" is not of type "integer"
+/// ```
+#[pyclass(module = "fastpy_rs.json", frozen)]
+pub struct Validator {
+    validator: jsonschema::Validator,
+    draft: Draft,
+}
+
+#[pymethods]
+impl Validator {
+    #[new]
+    #[pyo3(signature = (schema, *, draft = None))]
+    fn new(schema: &Bound<'_, PyAny>, draft: Option<u16>) -> PyResult<Self> {
+        let schema = document(schema)?;
+        let invalid = |err: &dyn std::fmt::Display| PyValueError::new_err(format!("Invalid JSON Schema: {err}"));
+        let draft = match draft {
+            Some(draft) => draft_from_number(draft)?,
+            None => Draft::default().detect(&schema).map_err(|err| invalid(&err))?,
+        };
+        let validator = jsonschema::options()
+            .with_draft(draft)
+            .build(&schema)
+            .map_err(|err| invalid(&err))?;
+        Ok(Validator { validator, draft })
+    }
+
+    /// Returns whether `instance` is valid; `str` and bytes-like instances are parsed as JSON text first.
+    fn is_valid(&self, py: Python<'_>, instance: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let instance = document(instance)?;
+        Ok(py.allow_threads(|| self.validator.is_valid(&instance)))
+    }
+
+    /// Raises the first `ValidationError` found in `instance`, if there is one.
+    fn validate(&self, py: Python<'_>, instance: &Bound<'_, PyAny>) -> PyResult<()> {
+        let instance = document(instance)?;
+        match self.validator.validate(&instance) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(PyErr::from_value(validation_error(py, &err)?)),
+        }
+    }
+
+    /// Returns an iterator over every `ValidationError` in `instance`.
+    fn iter_errors<'py>(&self, py: Python<'py>, instance: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyIterator>> {
+        let instance = document(instance)?;
+        let errors = self
+            .validator
+            .iter_errors(&instance)
+            .map(|err| validation_error(py, &err))
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, errors)?.try_iter()
+    }
+
+    /// The draft the schema is validated under, as `4`, `6`, `7`, `2019` or `2020`.
+    #[getter]
+    fn draft(&self) -> u16 {
+        match self.draft {
+            Draft::Draft4 => 4,
+            Draft::Draft6 => 6,
+            Draft::Draft7 => 7,
+            Draft::Draft201909 => 2019,
+            _ => 2020,
+        }
+    }
+}
+
+fn draft_from_number(draft: u16) -> PyResult<Draft> {
+    Ok(match draft {
+        4 => Draft::Draft4,
+        6 => Draft::Draft6,
+        7 => Draft::Draft7,
+        2019 => Draft::Draft201909,
+        2020 => Draft::Draft202012,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unsupported draft {draft}; expected 4, 6, 7, 2019 or 2020"
+            )))
+        }
+    })
+}
+
+static VALIDATION_ERROR: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
+/// Returns `fastpy_rs.json.ValidationError`, creating it on first use.
+pub fn validation_error_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    VALIDATION_ERROR
+        .get_or_try_init(py, || {
+            let namespace = PyDict::new(py);
+            namespace.set_item("__module__", "fastpy_rs.json")?;
+            namespace.set_item(
+                "__doc__",
+                "Subclass of ValueError describing why a document does not match a JSON Schema or the\n\
+                 type given to `decode`.\n\n\
+                 `message` is the description, `path` and `schema_path` are JSON Pointers to the\n\
+                 failing value and to the schema keyword it failed (None for `decode`), and\n\
+                 `instance` is the failing value.",
+            )?;
+            let class = py
+                .get_type::<PyType>()
+                .call1(("ValidationError", (py.get_type::<PyValueError>(),), namespace))?;
+            Ok(class.downcast_into::<PyType>()?.unbind())
+        })
+        .map(|class| class.bind(py))
+}
+
+/// Builds a `ValidationError` instance for `err`.
+fn validation_error<'py>(py: Python<'py>, err: &SchemaError<'_>) -> PyResult<Bound<'py, PyAny>> {
+    let message = err.to_string();
+    let exc = validation_error_type(py)?.call1((&message,))?;
+    exc.setattr("message", message)?;
+    exc.setattr("path", err.instance_path.as_str())?;
+    exc.setattr("schema_path", err.schema_path.as_str())?;
+    exc.setattr("instance", to_object(py, err.instance.as_ref())?)?;
+    Ok(exc)
+}
+
+/// Converts a schema or an instance to a `serde_json::Value`, parsing it first if it is JSON text.
+fn document(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if is_json_text(obj) {
+        let parsed = with_json_bytes(obj, |bytes| parse_slice(obj.py(), bytes).map_err(|e| parse_error(obj, bytes, e)))??;
+        return to_value(&parsed, &mut Ancestors::default());
+    }
+    to_value(obj, &mut Ancestors::default())
+}
+
+fn to_value(obj: &Bound<'_, PyAny>, ancestors: &mut Ancestors) -> PyResult<Value> {
+    match kind(obj) {
+        Kind::Null => Ok(Value::Null),
+        Kind::Bool => Ok(Value::Bool(obj.is_truthy()?)),
+        Kind::String => Ok(Value::String(obj.downcast::<PyString>()?.to_str()?.to_string())),
+        Kind::Number => {
+            if let Ok(i) = obj.extract::<i64>() {
+                return Ok(i.into());
+            }
+            if let Ok(u) = obj.extract::<u64>() {
+                return Ok(u.into());
+            }
+            if obj.is_instance_of::<PyInt>() {
+                return Err(PyValueError::new_err(format!(
+                    "JSON Schema validation only supports ints of up to 64 bits, not {}",
+                    obj.repr()?
+                )));
+            }
+            match Number::from_f64(obj.downcast::<PyFloat>()?.value()) {
+                Some(number) => Ok(Value::Number(number)),
+                // Not JSON; `serialize_json` raises the error for it.
+                None => object_to_value(obj),
+            }
+        }
+        Kind::Array => {
+            ancestors.enter(obj)?;
+            let items = elements(obj)
+                .unwrap_or_default()
+                .iter()
+                .map(|item| to_value(item, ancestors))
+                .collect::<PyResult<Vec<_>>>()?;
+            ancestors.leave();
+            Ok(Value::Array(items))
+        }
+        Kind::Object => {
+            ancestors.enter(obj)?;
+            let mut map = Map::new();
+            for (key, item) in obj.downcast::<PyDict>()?.iter() {
+                let Some(name) = dict_key::<serde_json::Error>(&key, false).map_err(|e| PyValueError::new_err(e.to_string()))?
+                else {
+                    return Err(PyTypeError::new_err(format!(
+                        "Dict keys must be str, int, float, bool or None, not {}",
+                        key.get_type().name()?
+                    )));
+                };
+                map.insert(name.into_owned(), to_value(&item, ancestors)?);
+            }
+            ancestors.leave();
+            Ok(Value::Object(map))
+        }
+        // Anything else is validated as what `serialize_json` writes for it.
+        Kind::Other => object_to_value(obj),
+    }
+}
+
+/// Converts a `serde_json::Value` back to the Python object `parse_json` would return.
+fn to_object<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => PyInt::new(py, i).into_any(),
+            (None, Some(u)) => PyInt::new(py, u).into_any(),
+            _ => PyFloat::new(py, n.as_f64().unwrap_or(f64::NAN)).into_any(),
+        },
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(items) => {
+            PyList::new(py, items.iter().map(|item| to_object(py, item)).collect::<PyResult<Vec<_>>>()?)?.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, to_object(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
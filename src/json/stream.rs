@@ -0,0 +1,692 @@
+//! Incremental parsing of documents too large to hold in memory, in the style of ijson.
+//!
+//! The input is read in fixed-size chunks. Only the values found under the requested
+//! prefix are turned into Python objects; everything else is validated and skipped
+//! without allocating.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyRecursionError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString};
+
+use super::{cached_key, relocated_decode_error, with_json_bytes};
+
+/// How much input is read at a time.
+const CHUNK_BYTES: usize = 1 << 16;
+
+/// Nesting depth at which building a value gives up, roughly matching CPython's recursion limit.
+const MAX_DEPTH: usize = 1000;
+
+/// Iterates over the values found under `prefix` in a JSON document, reading it incrementally.
+///
+/// `prefix` uses ijson's notation: object keys and `item` for array elements, joined by dots.
+/// `""` is the whole document, `"item"` every element of a top-level array and
+/// `"results.item"` every element of the array under the `"results"` key. Memory use is
+/// bounded by the largest single matching value, not by the size of the document.
+///
+/// # Arguments
+/// * `source` - A path (`str` or `os.PathLike`), a binary or text file object, or the document as bytes
+/// * `prefix` - Where the values to yield are located
+///
+/// # Returns
+/// * An iterator over the matching values as Python objects
+///
+/// # Raises
+/// * `JSONDecodeError` - If the document is not valid JSON, raised when the iterator reaches the problem; `pos` is a byte offset
+/// * `OSError` - If the file cannot be read
+/// * `TypeError` - If `source` is neither a path, a file object nor bytes-like
+///
+/// # Examples
+/// ```python
+/// import fastpy_rs
+///
+/// # {"count": 2, "results": [{"id": 1}, {"id": 2}]}
+/// for result in fastpy_rs.json.iter_items("export.json", prefix="results.item"):
+///     print(result["id"])
+///
+/// with open("events.json", "rb") as f:
+///     first = next(fastpy_rs.json.iter_items(f, "item"))
+/// ```
+#[pyfunction]
+#[pyo3(signature = (source, prefix = ""))]
+pub fn iter_items(source: &Bound<'_, PyAny>, prefix: &str) -> PyResult<ItemsIterator> {
+    let (input, buf) = if source.is_instance_of::<PyString>() || source.hasattr("__fspath__")? {
+        let path: PathBuf = source.extract()?;
+        let file = source.py().allow_threads(|| File::open(path))?;
+        (Input::File(file), Vec::new())
+    } else if source.hasattr("read")? {
+        (Input::Reader(source.clone().unbind()), Vec::new())
+    } else {
+        let data = with_json_bytes(source, <[u8]>::to_vec).map_err(|_| {
+            PyTypeError::new_err(format!(
+                "iter_items() expects a path, a file object or bytes, not {}",
+                source.get_type().name().map(|n| n.to_string()).unwrap_or_default()
+            ))
+        })?;
+        (Input::Exhausted, data)
+    };
+
+    Ok(ItemsIterator {
+        input,
+        buf,
+        pos: 0,
+        base: 0,
+        lines: 0,
+        line_start: 0,
+        prefix: if prefix.is_empty() {
+            Vec::new()
+        } else {
+            prefix.split('.').map(str::to_string).collect()
+        },
+        path: Vec::new(),
+        containers: Vec::new(),
+        state: State::Value,
+        scratch: Vec::new(),
+        high_surrogate_at: None,
+    })
+}
+
+enum Input {
+    File(File),
+    /// A Python object with a `read(size)` method returning `bytes` or `str`.
+    Reader(Py<PyAny>),
+    Exhausted,
+}
+
+#[derive(Clone, Copy)]
+enum Container {
+    Object,
+    Array,
+}
+
+/// Where the walk through the document stands between two yielded values.
+#[derive(Clone, Copy)]
+enum State {
+    Value,
+    FirstKey,
+    FirstItem,
+    AfterValue,
+    Done,
+}
+
+enum StreamError {
+    /// Malformed input: stdlib-style message and the absolute byte offset it refers to.
+    Syntax(&'static str, usize),
+    /// An exception raised while reading or building an object.
+    Python(PyErr),
+}
+
+impl From<PyErr> for StreamError {
+    fn from(err: PyErr) -> Self {
+        StreamError::Python(err)
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        StreamError::Python(err.into())
+    }
+}
+
+type StreamResult<T> = Result<T, StreamError>;
+
+/// Iterator returned by `iter_items`.
+#[pyclass(module = "fastpy_rs.json")]
+pub struct ItemsIterator {
+    input: Input,
+    /// The current chunk of input; `buf[..pos]` has been consumed.
+    buf: Vec<u8>,
+    pos: usize,
+    /// Absolute offset of `buf[0]` in the document.
+    base: usize,
+    /// Newlines before `buf[0]`, and the absolute offset just past the last of them.
+    lines: usize,
+    line_start: usize,
+    prefix: Vec<String>,
+    /// Path of the value about to be read, in the same notation as `prefix`.
+    path: Vec<String>,
+    containers: Vec<Container>,
+    state: State,
+    /// Text of the string or number being read, which may span several chunks. Lone
+    /// surrogates are kept in it the way `str.encode("utf-8", "surrogatepass")` writes them.
+    scratch: Vec<u8>,
+    /// Where in `scratch` a high surrogate written as a `\uXXXX` escape starts, while it may
+    /// still be paired with a low surrogate escaped right after it.
+    high_surrogate_at: Option<usize>,
+}
+
+#[pymethods]
+impl ItemsIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        match self.advance(py) {
+            Ok(value) => Ok(value.map(Bound::unbind)),
+            Err(err) => {
+                self.state = State::Done;
+                Err(self.py_err(py, err))
+            }
+        }
+    }
+}
+
+impl ItemsIterator {
+    /// Walks the document up to the next value under the prefix and builds it.
+    fn advance<'py>(&mut self, py: Python<'py>) -> StreamResult<Option<Bound<'py, PyAny>>> {
+        loop {
+            match self.state {
+                State::Done => return Ok(None),
+                State::Value => {
+                    self.skip_whitespace(py)?;
+                    if self.path == self.prefix {
+                        let value = self.value(py, 0, true)?;
+                        self.state = State::AfterValue;
+                        return Ok(value);
+                    }
+                    match self.peek(py)? {
+                        Some(b'{') if self.leads_to_prefix(None) => {
+                            self.pos += 1;
+                            self.containers.push(Container::Object);
+                            self.path.push(String::new());
+                            self.state = State::FirstKey;
+                        }
+                        Some(b'[') if self.leads_to_prefix(Some("item")) => {
+                            self.pos += 1;
+                            self.containers.push(Container::Array);
+                            self.path.push("item".to_string());
+                            self.state = State::FirstItem;
+                        }
+                        _ => {
+                            self.value(py, 0, false)?;
+                            self.state = State::AfterValue;
+                        }
+                    }
+                }
+                State::FirstKey => {
+                    self.skip_whitespace(py)?;
+                    if self.peek(py)? == Some(b'}') {
+                        self.pos += 1;
+                        self.leave();
+                    } else {
+                        self.path_key(py)?;
+                        self.state = State::Value;
+                    }
+                }
+                State::FirstItem => {
+                    self.skip_whitespace(py)?;
+                    if self.peek(py)? == Some(b']') {
+                        self.pos += 1;
+                        self.leave();
+                    } else {
+                        self.state = State::Value;
+                    }
+                }
+                State::AfterValue => {
+                    self.skip_whitespace(py)?;
+                    let at = self.offset();
+                    let next = self.next_byte(py)?;
+                    match (self.containers.last().copied(), next) {
+                        (None, None) => self.state = State::Done,
+                        (None, Some(_)) => return Err(StreamError::Syntax("Extra data", at)),
+                        (Some(Container::Object), Some(b',')) => {
+                            self.skip_whitespace(py)?;
+                            self.path_key(py)?;
+                            self.state = State::Value;
+                        }
+                        (Some(Container::Array), Some(b',')) => self.state = State::Value,
+                        (Some(Container::Object), Some(b'}')) | (Some(Container::Array), Some(b']')) => {
+                            self.leave()
+                        }
+                        (Some(_), _) => return Err(StreamError::Syntax("Expecting ',' delimiter", at)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether the container about to be entered can hold values under the prefix.
+    fn leads_to_prefix(&self, component: Option<&str>) -> bool {
+        let depth = self.path.len();
+        depth < self.prefix.len()
+            && self.prefix[..depth] == self.path[..]
+            && component.is_none_or(|c| self.prefix[depth] == c)
+    }
+
+    fn leave(&mut self) {
+        self.containers.pop();
+        self.path.pop();
+        self.state = State::AfterValue;
+    }
+
+    /// Reads an object key and its `:` while walking, and makes it the last path component.
+    fn path_key(&mut self, py: Python<'_>) -> StreamResult<()> {
+        self.key_start(py)?;
+        let key = self.text(py)?.to_string_lossy().into_owned();
+        self.colon(py)?;
+        if let Some(last) = self.path.last_mut() {
+            *last = key;
+        }
+        Ok(())
+    }
+
+    /// Parses one complete value, building the Python object only if `keep` is set.
+    fn value<'py>(&mut self, py: Python<'py>, depth: usize, keep: bool) -> StreamResult<Option<Bound<'py, PyAny>>> {
+        self.skip_whitespace(py)?;
+        let at = self.offset();
+        let value = match self.peek(py)? {
+            Some(b'{') => {
+                self.pos += 1;
+                self.object(py, depth + 1, keep)?
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                self.array(py, depth + 1, keep)?
+            }
+            Some(b'"') => {
+                self.pos += 1;
+                self.string(py, keep)?;
+                match keep {
+                    true => Some(self.text(py)?.into_any()),
+                    false => None,
+                }
+            }
+            Some(b't') => {
+                self.literal(py, b"true", at)?;
+                keep.then(|| PyBool::new(py, true).to_owned().into_any())
+            }
+            Some(b'f') => {
+                self.literal(py, b"false", at)?;
+                keep.then(|| PyBool::new(py, false).to_owned().into_any())
+            }
+            Some(b'n') => {
+                self.literal(py, b"null", at)?;
+                keep.then(|| py.None().into_bound(py))
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(py, keep, at)?,
+            _ => return Err(StreamError::Syntax("Expecting value", at)),
+        };
+        Ok(value)
+    }
+
+    fn object<'py>(&mut self, py: Python<'py>, depth: usize, keep: bool) -> StreamResult<Option<Bound<'py, PyAny>>> {
+        check_depth(depth)?;
+        let dict = keep.then(|| PyDict::new(py));
+        self.skip_whitespace(py)?;
+        if self.peek(py)? == Some(b'}') {
+            self.pos += 1;
+            return Ok(dict.map(Bound::into_any));
+        }
+        loop {
+            self.skip_whitespace(py)?;
+            self.key_start(py)?;
+            let key = match (keep, std::str::from_utf8(&self.scratch)) {
+                (true, Ok(key)) => Some(cached_key(py, key)),
+                (true, Err(_)) => Some(self.text(py)?),
+                (false, _) => None,
+            };
+            self.colon(py)?;
+            let value = self.value(py, depth, keep)?;
+            if let (Some(dict), Some(key), Some(value)) = (&dict, key, value) {
+                dict.set_item(key, value)?;
+            }
+            self.skip_whitespace(py)?;
+            let at = self.offset();
+            match self.next_byte(py)? {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(dict.map(Bound::into_any)),
+                _ => return Err(StreamError::Syntax("Expecting ',' delimiter", at)),
+            }
+        }
+    }
+
+    fn array<'py>(&mut self, py: Python<'py>, depth: usize, keep: bool) -> StreamResult<Option<Bound<'py, PyAny>>> {
+        check_depth(depth)?;
+        let mut items = Vec::new();
+        self.skip_whitespace(py)?;
+        if self.peek(py)? != Some(b']') {
+            loop {
+                if let Some(value) = self.value(py, depth, keep)? {
+                    items.push(value);
+                }
+                self.skip_whitespace(py)?;
+                let at = self.offset();
+                match self.next_byte(py)? {
+                    Some(b',') => continue,
+                    Some(b']') => break,
+                    _ => return Err(StreamError::Syntax("Expecting ',' delimiter", at)),
+                }
+            }
+        } else {
+            self.pos += 1;
+        }
+        match keep {
+            true => Ok(Some(PyList::new(py, items)?.into_any())),
+            false => Ok(None),
+        }
+    }
+
+    /// Consumes the opening quote of an object key and reads the key into `scratch`.
+    fn key_start(&mut self, py: Python<'_>) -> StreamResult<()> {
+        let at = self.offset();
+        if self.next_byte(py)? != Some(b'"') {
+            return Err(StreamError::Syntax("Expecting property name enclosed in double quotes", at));
+        }
+        self.string(py, true)
+    }
+
+    fn colon(&mut self, py: Python<'_>) -> StreamResult<()> {
+        self.skip_whitespace(py)?;
+        let at = self.offset();
+        if self.next_byte(py)? != Some(b':') {
+            return Err(StreamError::Syntax("Expecting ':' delimiter", at));
+        }
+        Ok(())
+    }
+
+    /// `scratch` as a Python string, lone surrogates included.
+    fn text<'py>(&self, py: Python<'py>) -> StreamResult<Bound<'py, PyString>> {
+        if let Ok(text) = std::str::from_utf8(&self.scratch) {
+            return Ok(PyString::new(py, text));
+        }
+        // SAFETY: the pointer and length describe `scratch`, the error handler name is
+        // NUL-terminated, and a successful call returns a new `str`.
+        let decoded = unsafe {
+            let ptr = pyo3::ffi::PyUnicode_DecodeUTF8(
+                self.scratch.as_ptr().cast(),
+                self.scratch.len() as pyo3::ffi::Py_ssize_t,
+                c"surrogatepass".as_ptr(),
+            );
+            Bound::from_owned_ptr_or_err(py, ptr).map(|text| text.downcast_into_unchecked())
+        };
+        decoded.map_err(|_| StreamError::Syntax("Invalid UTF-8 in string", self.offset()))
+    }
+
+    /// Reads the rest of a string whose opening quote has been consumed, unescaped into `scratch` if `keep`.
+    ///
+    /// A skipped string is still checked for valid UTF-8 as it goes by; `scratch` then
+    /// only holds a sequence split across chunks.
+    fn string(&mut self, py: Python<'_>, keep: bool) -> StreamResult<()> {
+        self.scratch.clear();
+        self.high_surrogate_at = None;
+        loop {
+            if !self.fill(py)? {
+                return Err(StreamError::Syntax("Unterminated string", self.offset()));
+            }
+            let rest = &self.buf[self.pos..];
+            let run = rest
+                .iter()
+                .position(|&b| b == b'"' || b == b'\\' || b < 0x20)
+                .unwrap_or(rest.len());
+            self.scratch.extend_from_slice(&rest[..run]);
+            let stop = rest.get(run).copied();
+            self.pos += run;
+            if !keep {
+                let valid = utf8_prefix(&self.scratch)
+                    .filter(|&valid| stop.is_none() || valid == self.scratch.len())
+                    .ok_or(StreamError::Syntax("Invalid UTF-8 in string", self.offset()))?;
+                self.scratch.drain(..valid);
+            }
+            match stop {
+                None => continue,
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    self.escape(py, keep)?;
+                }
+                Some(_) => return Err(StreamError::Syntax("Invalid control character at", self.offset())),
+            }
+        }
+    }
+
+    fn escape(&mut self, py: Python<'_>, keep: bool) -> StreamResult<()> {
+        let at = self.offset() - 1;
+        let c = match self.next_byte(py)? {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let unit = self.hex4(py, at)?;
+                if keep {
+                    self.push_code_unit(unit);
+                }
+                return Ok(());
+            }
+            _ => return Err(StreamError::Syntax("Invalid \\escape", at)),
+        };
+        if keep {
+            let mut utf8 = [0; 4];
+            self.scratch.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+        }
+        Ok(())
+    }
+
+    /// Appends the UTF-16 code unit of a `\\uXXXX` escape to `scratch`, combining a low
+    /// surrogate with the high one escaped right before it. Any other surrogate is kept on its
+    /// own, as the stdlib does.
+    fn push_code_unit(&mut self, unit: u32) {
+        let mut code = unit;
+        if let Some(at) = self.high_surrogate_at.filter(|&at| at + 3 == self.scratch.len()) {
+            if (0xdc00..0xe000).contains(&unit) {
+                let high = 0xd000 | (u32::from(self.scratch[at + 1] & 0x3f) << 6) | u32::from(self.scratch[at + 2] & 0x3f);
+                self.scratch.truncate(at);
+                code = 0x10000 + ((high - 0xd800) << 10) + (unit - 0xdc00);
+            }
+        }
+        self.high_surrogate_at = (0xd800..0xdc00).contains(&code).then_some(self.scratch.len());
+        match char::from_u32(code) {
+            Some(c) => {
+                let mut utf8 = [0; 4];
+                self.scratch.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+            }
+            None => self.scratch.extend_from_slice(&[
+                0xe0 | (code >> 12) as u8,
+                0x80 | ((code >> 6) & 0x3f) as u8,
+                0x80 | (code & 0x3f) as u8,
+            ]),
+        }
+    }
+
+    fn hex4(&mut self, py: Python<'_>, at: usize) -> StreamResult<u32> {
+        let mut unit = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next_byte(py)?
+                .and_then(|b| (b as char).to_digit(16))
+                .ok_or(StreamError::Syntax("Invalid \\uXXXX escape", at))?;
+            unit = unit * 16 + digit;
+        }
+        Ok(unit)
+    }
+
+    fn literal(&mut self, py: Python<'_>, text: &[u8], at: usize) -> StreamResult<()> {
+        for &expected in text {
+            if self.next_byte(py)? != Some(expected) {
+                return Err(StreamError::Syntax("Expecting value", at));
+            }
+        }
+        Ok(())
+    }
+
+    fn number<'py>(&mut self, py: Python<'py>, keep: bool, at: usize) -> StreamResult<Option<Bound<'py, PyAny>>> {
+        self.scratch.clear();
+        while let Some(b) = self.peek(py)? {
+            if !matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                break;
+            }
+            self.scratch.push(b);
+            self.pos += 1;
+        }
+        let is_float = number_kind(&self.scratch).ok_or(StreamError::Syntax("Expecting value", at))?;
+        if !keep {
+            return Ok(None);
+        }
+        // SAFETY: number_kind only accepts ASCII digits, signs, '.' and exponents.
+        let text = unsafe { std::str::from_utf8_unchecked(&self.scratch) };
+        if is_float {
+            return Ok(Some(PyFloat::new(py, text.parse().unwrap_or(f64::NAN)).into_any()));
+        }
+        match text.parse::<i64>() {
+            Ok(value) => Ok(Some(PyInt::new(py, value).into_any())),
+            Err(_) => Ok(Some(py.get_type::<PyInt>().call1((text,))?)),
+        }
+    }
+
+    fn skip_whitespace(&mut self, py: Python<'_>) -> StreamResult<()> {
+        loop {
+            while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.buf.get(self.pos) {
+                self.pos += 1;
+            }
+            if self.pos < self.buf.len() || !self.fill(py)? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn peek(&mut self, py: Python<'_>) -> StreamResult<Option<u8>> {
+        Ok(if self.fill(py)? { Some(self.buf[self.pos]) } else { None })
+    }
+
+    fn next_byte(&mut self, py: Python<'_>) -> StreamResult<Option<u8>> {
+        let b = self.peek(py)?;
+        if b.is_some() {
+            self.pos += 1;
+        }
+        Ok(b)
+    }
+
+    /// Absolute offset of the next unread byte.
+    fn offset(&self) -> usize {
+        self.base + self.pos
+    }
+
+    /// Makes sure there is an unread byte, reading the next chunk if needed; `false` at the end of the input.
+    fn fill(&mut self, py: Python<'_>) -> StreamResult<bool> {
+        if self.pos < self.buf.len() {
+            return Ok(true);
+        }
+        // Everything buffered has been consumed: remember where the lines were and drop it.
+        self.lines += self.buf.iter().filter(|&&b| b == b'\n').count();
+        if let Some(i) = self.buf.iter().rposition(|&b| b == b'\n') {
+            self.line_start = self.base + i + 1;
+        }
+        self.base += self.buf.len();
+        self.buf.clear();
+        self.pos = 0;
+
+        let buf = &mut self.buf;
+        match &mut self.input {
+            Input::File(file) => {
+                py.allow_threads(|| {
+                    buf.resize(CHUNK_BYTES, 0);
+                    let n = file.read(buf)?;
+                    buf.truncate(n);
+                    Ok::<_, io::Error>(())
+                })?;
+            }
+            Input::Reader(reader) => {
+                let chunk = reader.bind(py).call_method1("read", (CHUNK_BYTES,))?;
+                with_json_bytes(&chunk, |bytes| buf.extend_from_slice(bytes))?;
+            }
+            Input::Exhausted => {}
+        }
+        if self.buf.is_empty() {
+            self.input = Input::Exhausted;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn py_err(&self, py: Python<'_>, err: StreamError) -> PyErr {
+        let (msg, pos) = match err {
+            StreamError::Python(err) => return err,
+            StreamError::Syntax(msg, pos) => (msg, pos.max(self.base)),
+        };
+        let seen = &self.buf[..(pos - self.base).min(self.buf.len())];
+        let lineno = self.lines + seen.iter().filter(|&&b| b == b'\n').count() + 1;
+        let line_start = match seen.iter().rposition(|&b| b == b'\n') {
+            Some(i) => self.base + i + 1,
+            None => self.line_start,
+        };
+        relocated_decode_error(py, msg, "", pos, lineno, pos - line_start + 1)
+    }
+}
+
+/// Length of the longest prefix of `bytes` that is valid UTF-8 when all that is wrong
+/// with the rest is that it stops in the middle of a character, or `None` if it is invalid.
+///
+/// Lone surrogates encoded as `str.encode("utf-8", "surrogatepass")` writes them are
+/// accepted, as they are in strings that are kept.
+fn utf8_prefix(bytes: &[u8]) -> Option<usize> {
+    let mut start = 0;
+    loop {
+        let err = match std::str::from_utf8(&bytes[start..]) {
+            Ok(_) => return Some(bytes.len()),
+            Err(err) => err,
+        };
+        let invalid = start + err.valid_up_to();
+        match bytes[invalid..] {
+            [0xed, 0xa0..=0xbf, 0x80..=0xbf, ..] => start = invalid + 3,
+            [0xed] | [0xed, 0xa0..=0xbf] => return Some(invalid),
+            _ if err.error_len().is_none() => return Some(invalid),
+            _ => return None,
+        }
+    }
+}
+
+fn check_depth(depth: usize) -> StreamResult<()> {
+    if depth > MAX_DEPTH {
+        return Err(PyRecursionError::new_err("maximum recursion depth exceeded while decoding a JSON document").into());
+    }
+    Ok(())
+}
+
+/// Checks `text` against the JSON number grammar: `Some(true)` for a float, `Some(false)` for an int.
+pub(super) fn number_kind(text: &[u8]) -> Option<bool> {
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while text.get(*i).is_some_and(u8::is_ascii_digit) {
+            *i += 1;
+        }
+        *i > start
+    };
+    let mut i = usize::from(text.first() == Some(&b'-'));
+    match text.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => {
+            digits(&mut i);
+        }
+        _ => return None,
+    }
+    let mut is_float = false;
+    if text.get(i) == Some(&b'.') {
+        i += 1;
+        if !digits(&mut i) {
+            return None;
+        }
+        is_float = true;
+    }
+    if matches!(text.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(text.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if !digits(&mut i) {
+            return None;
+        }
+        is_float = true;
+    }
+    (i == text.len()).then_some(is_float)
+}
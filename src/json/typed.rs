@@ -0,0 +1,659 @@
+//! Typed decoding of JSON into dataclasses, `TypedDict`s and other annotated types.
+//!
+//! A target type is compiled once into a [`Plan`] by introspecting its type hints, and
+//! plans are cached per type. Decoding then walks the parsed document and the plan
+//! together. The document comes from the same decoder as `parse_json`, which also keeps
+//! the text of every float, so `Decimal` fields get exactly the digits that were written.
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple, PyType};
+
+use super::decoder::{parse_keeping_float_text, FloatText};
+use super::queries::{elements, kind, Kind};
+use super::{is_json_text, parse_error, to_json, validation_error_type, with_json_bytes, SerializeOptions};
+
+/// Decodes JSON into an instance of `type`, checking every value against its type hints.
+///
+/// Supported types are dataclasses, `TypedDict`s, `list[...]`, `tuple[...]`, `set[...]`,
+/// `frozenset[...]`, `dict[str, ...]` and `dict[int, ...]`, `Optional[...]` and other unions,
+/// `Literal[...]`, `Enum` subclasses, `Annotated[...]`, `NewType`s, `typing.Any`, the JSON
+/// scalars (`str`, `int`, `float`, `bool`, `None`), and `datetime`, `date`, `time`, `UUID`,
+/// `Decimal` and `bytes` as written by `serialize_json`. Forward references are resolved and
+/// types may be recursive.
+///
+/// Checks are strict: an `int` field does not accept `1.0` or `"1"`, though a `float` field
+/// accepts ints. Dataclass fields with a default may be missing, and unknown keys are ignored.
+/// The type hints of each type are introspected once and cached.
+///
+/// JSON text is read by the same decoder as `parse_json`: ints of any size stay exact, and a
+/// `Decimal` gets a number's digits exactly as written, so `1.10` becomes `Decimal('1.10')`.
+///
+/// # Arguments
+/// * `data` - JSON text as `str`, `bytes`, `bytearray` or `memoryview`, or already parsed data
+/// * `type` - The type to decode into
+///
+/// # Returns
+/// * An instance of `type`
+///
+/// # Raises
+/// * `ValidationError` - If the data does not match the type; `path` is a JSON Pointer to the
+///   offending value, which is also named in the message
+/// * `JSONDecodeError` - If `data` is JSON text that does not parse
+/// * `RecursionError` - If `data` is nested more than 1000 levels deep
+/// * `TypeError` - If `type` is not supported
+///
+/// # Examples
+/// ```python
+/// from dataclasses import dataclass
+/// from typing import Optional
+/// import fastpy_rs.json as json
+///
+/// @dataclass
+/// class Item:
+///     sku: str
+///     qty: int = 1
+///
+/// @dataclass
+/// class Order:
+///     id: int
+///     items: list[Item]
+///     note: Optional[str] = None
+///
+/// order = json.decode('{"id": 7, "items": [{"sku": "A-1", "qty": 2}, {"sku": "B-2"}]}', type=Order)
+/// print(order.items[1])  # Output: Item(sku='B-2', qty=1)
+///
+/// json.decode('{"id": 7, "items": [{"sku": "A-1", "qty": "2"}]}', type=Order)
+/// # ValidationError: Expected `int`, got `str` - at `/items/0/qty`
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, *, r#type))]
+pub fn decode<'py>(data: &Bound<'py, PyAny>, r#type: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = data.py();
+    let compiled = compiled(r#type)?;
+    let decode_text = |bytes: &[u8]| {
+        let (document, float_text) = parse_keeping_float_text(py, bytes).map_err(|e| parse_error(data, bytes, e))?;
+        decode_value(py, &compiled.get().plan, &document, &float_text, &mut Vec::new())
+    };
+    if is_json_text(data) {
+        return with_json_bytes(data, decode_text)?;
+    }
+    // Anything else is decoded as the JSON `serialize_json` writes for it.
+    let opts = SerializeOptions::new(false, false, None, false, false, true);
+    let text = to_json(data.clone(), None, &(",".to_string(), ":".to_string()), &opts)?;
+    decode_text(text.as_bytes())
+}
+
+/// How to decode one annotated type.
+enum Plan {
+    Any,
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str,
+    /// A type parsed from a JSON string (or from a number, when `numbers` is set) by calling `parse`.
+    Parsed { name: &'static str, parse: Py<PyAny>, numbers: bool },
+    Sequence(Box<Plan>, Collection),
+    Tuple(Vec<Plan>),
+    Dict { int_keys: bool, values: Box<Plan> },
+    Union(Vec<Plan>),
+    Literal(Vec<Py<PyAny>>),
+    Enum(Py<PyType>),
+    Dataclass { class: Py<PyType>, fields: Vec<Field> },
+    TypedDict { fields: Vec<Field> },
+    /// A class that was still being compiled when it was referenced, i.e. a recursive type;
+    /// its plan is looked up in the cache when it is needed.
+    Deferred(Py<PyAny>),
+}
+
+#[derive(Clone, Copy)]
+enum Collection {
+    List,
+    VarTuple,
+    Set,
+    FrozenSet,
+}
+
+struct Field {
+    name: String,
+    plan: Plan,
+    required: bool,
+}
+
+/// A compiled plan, kept in `TYPE_CACHE`.
+#[pyclass(frozen)]
+struct CompiledType {
+    plan: Plan,
+}
+
+/// Compiled plans by type; cleared wholesale once it grows past `TYPE_CACHE_SIZE`.
+static TYPE_CACHE: GILOnceCell<Py<PyDict>> = GILOnceCell::new();
+const TYPE_CACHE_SIZE: usize = 256;
+
+fn compiled<'py>(tp: &Bound<'py, PyAny>) -> PyResult<Bound<'py, CompiledType>> {
+    let py = tp.py();
+    let cache = TYPE_CACHE.get_or_init(py, || PyDict::new(py).unbind()).bind(py);
+    // Annotated metadata can make a type unhashable; such types are compiled every time.
+    let hashable = tp.hash().is_ok();
+    if hashable {
+        if let Some(compiled) = cache.get_item(tp)? {
+            return Ok(compiled.downcast_into::<CompiledType>()?);
+        }
+    }
+    let plan = Compiler { py, typing: typing(py)?, in_progress: Vec::new() }.compile(tp)?;
+    let compiled = Bound::new(py, CompiledType { plan })?;
+    if hashable {
+        if cache.len() >= TYPE_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.set_item(tp, &compiled)?;
+    }
+    Ok(compiled)
+}
+
+/// The `typing`, `types`, `collections.abc` and other objects types are compared against.
+struct Typing {
+    any: Py<PyAny>,
+    union: Py<PyAny>,
+    union_type: Py<PyAny>,
+    literal: Py<PyAny>,
+    annotated: Py<PyAny>,
+    get_origin: Py<PyAny>,
+    get_args: Py<PyAny>,
+    get_type_hints: Py<PyAny>,
+    sequences: Py<PyTuple>,
+    sets: Py<PyTuple>,
+    mappings: Py<PyTuple>,
+    enum_type: Py<PyAny>,
+    is_dataclass: Py<PyAny>,
+    fields: Py<PyAny>,
+    missing: Py<PyAny>,
+    datetime: Py<PyAny>,
+    date: Py<PyAny>,
+    time: Py<PyAny>,
+    uuid: Py<PyAny>,
+    decimal: Py<PyAny>,
+    b64decode: Py<PyAny>,
+}
+
+static TYPING: GILOnceCell<Typing> = GILOnceCell::new();
+
+fn typing(py: Python<'_>) -> PyResult<&Typing> {
+    TYPING.get_or_try_init(py, || {
+        let typing = py.import("typing")?;
+        let abc = py.import("collections.abc")?;
+        let dataclasses = py.import("dataclasses")?;
+        let datetime = py.import("datetime")?;
+        let get = |module: &Bound<'_, PyModule>, name: &str| module.getattr(name).map(Bound::unbind);
+        let tuple = |module: &Bound<'_, PyModule>, names: &[&str]| -> PyResult<Py<PyTuple>> {
+            let items = names.iter().map(|name| module.getattr(*name)).collect::<PyResult<Vec<_>>>()?;
+            Ok(PyTuple::new(py, items)?.unbind())
+        };
+        Ok(Typing {
+            any: get(&typing, "Any")?,
+            union: get(&typing, "Union")?,
+            union_type: get(&py.import("types")?, "UnionType")?,
+            literal: get(&typing, "Literal")?,
+            annotated: get(&typing, "Annotated")?,
+            get_origin: get(&typing, "get_origin")?,
+            get_args: get(&typing, "get_args")?,
+            get_type_hints: get(&typing, "get_type_hints")?,
+            sequences: tuple(&abc, &["Sequence", "MutableSequence", "Collection", "Iterable"])?,
+            sets: tuple(&abc, &["Set", "MutableSet"])?,
+            mappings: tuple(&abc, &["Mapping", "MutableMapping"])?,
+            enum_type: get(&py.import("enum")?, "Enum")?,
+            is_dataclass: get(&dataclasses, "is_dataclass")?,
+            fields: get(&dataclasses, "fields")?,
+            missing: get(&dataclasses, "MISSING")?,
+            datetime: get(&datetime, "datetime")?,
+            date: get(&datetime, "date")?,
+            time: get(&datetime, "time")?,
+            uuid: get(&py.import("uuid")?, "UUID")?,
+            decimal: get(&py.import("decimal")?, "Decimal")?,
+            b64decode: get(&py.import("base64")?, "b64decode")?,
+        })
+    })
+}
+
+struct Compiler<'t, 'py> {
+    py: Python<'py>,
+    typing: &'t Typing,
+    /// Classes whose fields are being compiled, to detect recursive types.
+    in_progress: Vec<usize>,
+}
+
+impl<'py> Compiler<'_, 'py> {
+    fn compile(&mut self, tp: &Bound<'py, PyAny>) -> PyResult<Plan> {
+        let py = self.py;
+        let t = self.typing;
+        if tp.is_none() || tp.is(py.None().bind(py).get_type()) {
+            return Ok(Plan::Null);
+        }
+        if tp.is(&t.any) || tp.is(py.get_type::<PyAny>()) {
+            return Ok(Plan::Any);
+        }
+        if let Ok(class) = tp.downcast::<PyType>() {
+            if let Some(plan) = self.compile_class(class)? {
+                return Ok(plan);
+            }
+        }
+
+        let origin = t.get_origin.call1(py, (tp,))?.into_bound(py);
+        let args = t.get_args.call1(py, (tp,))?.into_bound(py).downcast_into::<PyTuple>()?;
+        if origin.is(&t.union) || origin.is(&t.union_type) {
+            let members = args.iter().map(|member| self.compile(&member)).collect::<PyResult<Vec<_>>>()?;
+            return Ok(Plan::Union(members));
+        }
+        if origin.is(&t.literal) {
+            return Ok(Plan::Literal(args.iter().map(Bound::unbind).collect()));
+        }
+        if origin.is(&t.annotated) {
+            return self.compile(&args.get_item(0)?);
+        }
+        if let Ok(supertype) = tp.getattr("__supertype__") {
+            // typing.NewType
+            return self.compile(&supertype);
+        }
+        if let Ok(origin) = origin.downcast::<PyType>() {
+            if origin.is(py.get_type::<PyTuple>()) {
+                return match args.len() {
+                    0 => Ok(Plan::Sequence(Box::new(Plan::Any), Collection::VarTuple)),
+                    2 if args.get_item(1)?.is(py.Ellipsis()) => {
+                        Ok(Plan::Sequence(Box::new(self.compile(&args.get_item(0)?)?), Collection::VarTuple))
+                    }
+                    // `tuple[()]` is the empty tuple.
+                    1 if args.get_item(0)?.downcast::<PyTuple>().is_ok_and(|empty| empty.is_empty()) => Ok(Plan::Tuple(Vec::new())),
+                    _ => Ok(Plan::Tuple(args.iter().map(|item| self.compile(&item)).collect::<PyResult<_>>()?)),
+                };
+            }
+            let item = |compiler: &mut Self, i: usize| match args.get_item(i) {
+                Ok(arg) => compiler.compile(&arg),
+                Err(_) => Ok(Plan::Any),
+            };
+            if origin.is(py.get_type::<PyFrozenSet>()) {
+                return Ok(Plan::Sequence(Box::new(item(self, 0)?), Collection::FrozenSet));
+            }
+            if origin.is(py.get_type::<PySet>()) || origin.is_subclass(t.sets.bind(py))? {
+                return Ok(Plan::Sequence(Box::new(item(self, 0)?), Collection::Set));
+            }
+            if origin.is(py.get_type::<PyDict>()) || origin.is_subclass(t.mappings.bind(py))? {
+                let int_keys = match args.get_item(0) {
+                    Ok(key) => self.dict_key(&key)?,
+                    Err(_) => false,
+                };
+                return Ok(Plan::Dict { int_keys, values: Box::new(item(self, 1)?) });
+            }
+            // Checked last, as sets and mappings are collections too.
+            if origin.is(py.get_type::<PyList>()) || origin.is_subclass(t.sequences.bind(py))? && !origin.is_subclass_of::<PyString>()? {
+                return Ok(Plan::Sequence(Box::new(item(self, 0)?), Collection::List));
+            }
+        }
+        Err(PyTypeError::new_err(format!("Type '{}' is not supported by json.decode", tp.repr()?)))
+    }
+
+    /// The plan for a plain class, or `None` if it is a generic alias origin to look into.
+    fn compile_class(&mut self, class: &Bound<'py, PyType>) -> PyResult<Option<Plan>> {
+        let py = self.py;
+        let t = self.typing;
+        let parsed = |name: &'static str, parse: &Py<PyAny>, numbers: bool| {
+            Ok(Some(Plan::Parsed { name, parse: parse.clone_ref(py), numbers }))
+        };
+        // Exact matches first: bool is an int subclass and datetime a date subclass.
+        if class.is(py.get_type::<PyBool>()) {
+            return Ok(Some(Plan::Bool));
+        }
+        if class.is(py.get_type::<PyInt>()) {
+            return Ok(Some(Plan::Int));
+        }
+        if class.is(py.get_type::<PyFloat>()) {
+            return Ok(Some(Plan::Float));
+        }
+        if class.is(py.get_type::<PyString>()) {
+            return Ok(Some(Plan::Str));
+        }
+        if class.is(py.get_type::<PyBytes>()) {
+            return parsed("bytes", &t.b64decode, false);
+        }
+        if class.is(&t.datetime) {
+            return parsed("datetime", &t.datetime.getattr(py, "fromisoformat")?, false);
+        }
+        if class.is(&t.date) {
+            return parsed("date", &t.date.getattr(py, "fromisoformat")?, false);
+        }
+        if class.is(&t.time) {
+            return parsed("time", &t.time.getattr(py, "fromisoformat")?, false);
+        }
+        if class.is(&t.uuid) {
+            return parsed("uuid", &t.uuid, false);
+        }
+        if class.is(&t.decimal) {
+            return parsed("decimal", &t.decimal, true);
+        }
+        if class.is(py.get_type::<PyList>()) {
+            return Ok(Some(Plan::Sequence(Box::new(Plan::Any), Collection::List)));
+        }
+        if class.is(py.get_type::<PyTuple>()) {
+            return Ok(Some(Plan::Sequence(Box::new(Plan::Any), Collection::VarTuple)));
+        }
+        if class.is(py.get_type::<PySet>()) {
+            return Ok(Some(Plan::Sequence(Box::new(Plan::Any), Collection::Set)));
+        }
+        if class.is(py.get_type::<PyFrozenSet>()) {
+            return Ok(Some(Plan::Sequence(Box::new(Plan::Any), Collection::FrozenSet)));
+        }
+        if class.is(py.get_type::<PyDict>()) {
+            return Ok(Some(Plan::Dict { int_keys: false, values: Box::new(Plan::Any) }));
+        }
+        if class.is_subclass(t.enum_type.bind(py))? {
+            return Ok(Some(Plan::Enum(class.clone().unbind())));
+        }
+
+        let is_dataclass = t.is_dataclass.call1(py, (class,))?.is_truthy(py)?;
+        let is_typed_dict = class.is_subclass_of::<PyDict>()? && class.hasattr("__required_keys__")?;
+        if !is_dataclass && !is_typed_dict {
+            return Ok(None);
+        }
+        let id = class.as_ptr() as usize;
+        if self.in_progress.contains(&id) {
+            return Ok(Some(Plan::Deferred(class.clone().into_any().unbind())));
+        }
+        self.in_progress.push(id);
+        let hints = t.get_type_hints.call1(py, (class,))?.into_bound(py).downcast_into::<PyDict>()?;
+        let result = if is_dataclass { self.dataclass_fields(class, &hints) } else { self.typed_dict_fields(class, &hints) };
+        self.in_progress.pop();
+        let fields = result?;
+        Ok(Some(match is_dataclass {
+            true => Plan::Dataclass { class: class.clone().unbind(), fields },
+            false => Plan::TypedDict { fields },
+        }))
+    }
+
+    fn dataclass_fields(&mut self, class: &Bound<'py, PyType>, hints: &Bound<'py, PyDict>) -> PyResult<Vec<Field>> {
+        let py = self.py;
+        let missing = self.typing.missing.bind(py);
+        let mut fields = Vec::new();
+        for field in self.typing.fields.call1(py, (class,))?.bind(py).try_iter()? {
+            let field = field?;
+            if !field.getattr("init")?.is_truthy()? {
+                continue;
+            }
+            let name: String = field.getattr("name")?.extract()?;
+            let plan = match hints.get_item(&name)? {
+                Some(hint) => self.compile(&hint)?,
+                None => Plan::Any,
+            };
+            let required = field.getattr("default")?.is(missing) && field.getattr("default_factory")?.is(missing);
+            fields.push(Field { name, plan, required });
+        }
+        Ok(fields)
+    }
+
+    fn typed_dict_fields(&mut self, class: &Bound<'py, PyType>, hints: &Bound<'py, PyDict>) -> PyResult<Vec<Field>> {
+        let required_keys = class.getattr("__required_keys__")?;
+        let mut fields = Vec::new();
+        for (name, hint) in hints.iter() {
+            let required = required_keys.contains(&name)?;
+            fields.push(Field { name: name.extract()?, plan: self.compile(&hint)?, required });
+        }
+        Ok(fields)
+    }
+
+    /// Whether dict keys are decoded as ints; `str` (or `Any`) keys are kept as they are.
+    fn dict_key(&mut self, key: &Bound<'py, PyAny>) -> PyResult<bool> {
+        match self.compile(key)? {
+            Plan::Int => Ok(true),
+            Plan::Str | Plan::Any => Ok(false),
+            _ => Err(PyTypeError::new_err(format!(
+                "dict keys must be str or int for json.decode, not {}",
+                key.repr()?
+            ))),
+        }
+    }
+}
+
+/// A step on the way from the document root to the value being decoded.
+enum Segment<'py> {
+    Key(Bound<'py, PyString>),
+    Index(usize),
+}
+
+fn decode_value<'py>(
+    py: Python<'py>,
+    plan: &Plan,
+    value: &Bound<'py, PyAny>,
+    float_text: &FloatText<'_>,
+    path: &mut Vec<Segment<'py>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mismatch = |path: &[Segment<'_>]| {
+        invalid(py, format!("Expected `{}`, got `{}`", expected(plan), json_type(value)), path, value)
+    };
+    match (plan, kind(value)) {
+        (Plan::Any, _) | (Plan::Null, Kind::Null) | (Plan::Bool, Kind::Bool) | (Plan::Str, Kind::String) => Ok(value.clone()),
+        (Plan::Int, Kind::Number) if value.is_instance_of::<PyInt>() => Ok(value.clone()),
+        (Plan::Float, Kind::Number) => match value.is_instance_of::<PyFloat>() {
+            true => Ok(value.clone()),
+            false => Ok(PyFloat::new(py, value.extract()?).into_any()),
+        },
+        (Plan::Parsed { name, parse, numbers }, kind) => {
+            let text = match kind {
+                Kind::String => value.clone(),
+                // Floats are passed on as written, so `Decimal` gets every digit.
+                Kind::Number if *numbers => match float_text.get(&(value.as_ptr() as usize)) {
+                    Some(text) => PyString::new(py, text).into_any(),
+                    None => value.str()?.into_any(),
+                },
+                _ => return Err(mismatch(path)),
+            };
+            parse.bind(py).call1((text,)).map_err(|_| invalid(py, format!("Invalid {name}"), path, value))
+        }
+        (Plan::Sequence(item, kind), Kind::Array) => {
+            let items = elements(value).unwrap_or_default();
+            let mut objects = Vec::with_capacity(items.len());
+            for (i, item_value) in items.iter().enumerate() {
+                path.push(Segment::Index(i));
+                objects.push(decode_value(py, item, item_value, float_text, path)?);
+                path.pop();
+            }
+            Ok(match kind {
+                Collection::List => PyList::new(py, objects)?.into_any(),
+                Collection::VarTuple => PyTuple::new(py, objects)?.into_any(),
+                Collection::Set => PySet::new(py, objects)?.into_any(),
+                Collection::FrozenSet => PyFrozenSet::new(py, objects)?.into_any(),
+            })
+        }
+        (Plan::Tuple(plans), Kind::Array) => {
+            let items = elements(value).unwrap_or_default();
+            if items.len() != plans.len() {
+                let message = format!("Expected `array` of length {}, got {}", plans.len(), items.len());
+                return Err(invalid(py, message, path, value));
+            }
+            let mut objects = Vec::with_capacity(items.len());
+            for (i, (plan, item_value)) in plans.iter().zip(&items).enumerate() {
+                path.push(Segment::Index(i));
+                objects.push(decode_value(py, plan, item_value, float_text, path)?);
+                path.pop();
+            }
+            Ok(PyTuple::new(py, objects)?.into_any())
+        }
+        (Plan::Dict { int_keys, values }, Kind::Object) => {
+            let dict = PyDict::new(py);
+            for (key, item_value) in value.downcast::<PyDict>()?.iter() {
+                let key = key.downcast_into::<PyString>()?;
+                path.push(Segment::Key(key.clone()));
+                let key = match int_keys {
+                    true => match key.to_str().ok().and_then(|key| key.parse::<i64>().ok()) {
+                        Some(i) => PyInt::new(py, i).into_any(),
+                        None => {
+                            let message = format!("Expected `int` key, got {:?}", key.to_string_lossy());
+                            return Err(invalid(py, message, path, value));
+                        }
+                    },
+                    false => key.into_any(),
+                };
+                dict.set_item(key, decode_value(py, values, &item_value, float_text, path)?)?;
+                path.pop();
+            }
+            Ok(dict.into_any())
+        }
+        (Plan::Union(members), _) => {
+            let candidates: Vec<&Plan> = members.iter().filter(|member| accepts(member, value)).collect();
+            let mut error = None;
+            for member in &candidates {
+                let depth = path.len();
+                match decode_value(py, member, value, float_text, path) {
+                    Ok(object) => return Ok(object),
+                    Err(err) => {
+                        path.truncate(depth);
+                        error = Some(err);
+                    }
+                }
+            }
+            match (candidates.len(), error) {
+                // Only one member could apply, so its error is the precise one.
+                (1, Some(err)) => Err(err),
+                _ => Err(mismatch(path)),
+            }
+        }
+        (Plan::Literal(options), _) => {
+            for option in options {
+                let option = option.bind(py);
+                if option.get_type().is(value.get_type()) && option.eq(value)? {
+                    return Ok(option.clone());
+                }
+            }
+            let options = options.iter().map(|o| Ok(o.bind(py).repr()?.to_string())).collect::<PyResult<Vec<_>>>()?;
+            let message = format!("Invalid value {}, expected one of {}", value.repr()?, options.join(", "));
+            Err(invalid(py, message, path, value))
+        }
+        (Plan::Enum(class), _) => class.bind(py).call1((value,)).map_err(|_| match value.repr() {
+            Ok(repr) => invalid(py, format!("Invalid enum value {repr}"), path, value),
+            Err(err) => err,
+        }),
+        (Plan::Dataclass { class, fields }, Kind::Object) => {
+            let kwargs = decode_fields(py, fields, value, float_text, path)?;
+            class.bind(py).call((), Some(&kwargs)).map_err(|err| {
+                // Checks in `__post_init__` fail like any other mismatch.
+                if err.is_instance_of::<PyValueError>(py) || err.is_instance_of::<PyTypeError>(py) {
+                    invalid(py, err.value(py).to_string(), path, value)
+                } else {
+                    err
+                }
+            })
+        }
+        (Plan::TypedDict { fields }, Kind::Object) => Ok(decode_fields(py, fields, value, float_text, path)?.into_any()),
+        (Plan::Deferred(class), _) => {
+            let compiled = compiled(class.bind(py))?;
+            decode_value(py, &compiled.get().plan, value, float_text, path)
+        }
+        _ => Err(mismatch(path)),
+    }
+}
+
+fn decode_fields<'py>(
+    py: Python<'py>,
+    fields: &[Field],
+    value: &Bound<'py, PyAny>,
+    float_text: &FloatText<'_>,
+    path: &mut Vec<Segment<'py>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let object = value.downcast::<PyDict>()?;
+    let dict = PyDict::new(py);
+    for field in fields {
+        let key = PyString::new(py, &field.name);
+        match object.get_item(&key)? {
+            Some(item_value) => {
+                path.push(Segment::Key(key.clone()));
+                dict.set_item(key, decode_value(py, &field.plan, &item_value, float_text, path)?)?;
+                path.pop();
+            }
+            None if field.required => {
+                return Err(invalid(py, format!("Object missing required field `{}`", field.name), path, value));
+            }
+            None => {}
+        }
+    }
+    Ok(dict)
+}
+
+/// Whether `value` has a JSON type `plan` can decode, without looking any deeper.
+fn accepts(plan: &Plan, value: &Bound<'_, PyAny>) -> bool {
+    match (plan, kind(value)) {
+        (Plan::Any | Plan::Literal(_) | Plan::Enum(_), _) => true,
+        (Plan::Null, Kind::Null) | (Plan::Bool, Kind::Bool) | (Plan::Str, Kind::String) => true,
+        (Plan::Int, Kind::Number) => value.is_instance_of::<PyInt>(),
+        (Plan::Float, Kind::Number) => true,
+        (Plan::Parsed { numbers, .. }, kind) => kind == Kind::String || (*numbers && kind == Kind::Number),
+        (Plan::Sequence(..) | Plan::Tuple(_), Kind::Array) => true,
+        (Plan::Dict { .. } | Plan::Dataclass { .. } | Plan::TypedDict { .. } | Plan::Deferred(_), Kind::Object) => true,
+        (Plan::Union(members), _) => members.iter().any(|member| accepts(member, value)),
+        _ => false,
+    }
+}
+
+/// How a plan is named in error messages.
+fn expected(plan: &Plan) -> String {
+    match plan {
+        Plan::Any => "any".to_string(),
+        Plan::Null => "null".to_string(),
+        Plan::Bool => "bool".to_string(),
+        Plan::Int => "int".to_string(),
+        Plan::Float => "float".to_string(),
+        Plan::Str | Plan::Parsed { numbers: false, .. } => "str".to_string(),
+        Plan::Parsed { .. } => "str | float".to_string(),
+        Plan::Sequence(..) | Plan::Tuple(_) => "array".to_string(),
+        Plan::Dict { .. } | Plan::Dataclass { .. } | Plan::TypedDict { .. } | Plan::Deferred(_) => "object".to_string(),
+        Plan::Literal(_) | Plan::Enum(_) => "literal".to_string(),
+        Plan::Union(members) => {
+            let mut names: Vec<String> = Vec::new();
+            for name in members.iter().map(expected) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            names.join(" | ")
+        }
+    }
+}
+
+fn json_type(value: &Bound<'_, PyAny>) -> &'static str {
+    match kind(value) {
+        Kind::Null => "null",
+        Kind::Bool => "bool",
+        Kind::Number if value.is_instance_of::<PyFloat>() => "float",
+        Kind::Number => "int",
+        Kind::String => "str",
+        Kind::Array => "array",
+        Kind::Object => "object",
+        Kind::Other => "unknown",
+    }
+}
+
+/// Builds a `ValidationError` for the value at `path`.
+fn invalid(py: Python<'_>, message: String, path: &[Segment<'_>], instance: &Bound<'_, PyAny>) -> PyErr {
+    let pointer = path.iter().fold(String::new(), |mut pointer, segment| {
+        pointer.push('/');
+        match segment {
+            Segment::Key(key) => pointer.push_str(&key.to_string_lossy().replace('~', "~0").replace('/', "~1")),
+            Segment::Index(i) => pointer.push_str(&i.to_string()),
+        }
+        pointer
+    });
+    let text = match pointer.is_empty() {
+        true => message.clone(),
+        false => format!("{message} - at `{pointer}`"),
+    };
+    let exc = validation_error_type(py).and_then(|class| {
+        let exc = class.call1((text,))?;
+        exc.setattr("message", message)?;
+        exc.setattr("path", pointer)?;
+        exc.setattr("schema_path", py.None())?;
+        exc.setattr("instance", instance)?;
+        Ok(exc)
+    });
+    match exc {
+        Ok(exc) => PyErr::from_value(exc),
+        Err(err) => err,
+    }
+}
@@ -70,7 +70,7 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     // Register ai module
     let ai_module = PyModule::new(parent_module.py(), "ai")?;
     ai_module.add_function(wrap_pyfunction!(ai::token_frequency, &ai_module)?)?;
-    parent_module.add_submodule(&ai_module)?;
+    add_submodule(parent_module, &ai_module)?;
 
     // Register Benchmarking module
     let benchmark_module = PyModule::new(parent_module.py(), "benchmark")?;
@@ -79,7 +79,7 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
         &benchmark_module
     )?)?;
 
-    parent_module.add_submodule(&benchmark_module)?;
+    add_submodule(parent_module, &benchmark_module)?;
 
     // Register datatools module
     let datatools_module = PyModule::new(parent_module.py(), "datatools")?;
@@ -89,7 +89,7 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     datatools_module.add_function(wrap_pyfunction!(datatools::gzip_decompress, &datatools_module)?)?;
     datatools_module.add_function(wrap_pyfunction!(datatools::url_encode, &datatools_module)?)?;
     datatools_module.add_function(wrap_pyfunction!(datatools::url_decode, &datatools_module)?)?;
-    parent_module.add_submodule(&datatools_module)?;
+    add_submodule(parent_module, &datatools_module)?;
 
     // Register crypto module
     let crypto_module = PyModule::new(parent_module.py(), "crypto")?;
@@ -100,7 +100,7 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     crypto_module.add_function(wrap_pyfunction!(crypto::blake3_hash, &crypto_module)?)?;
     crypto_module.add_function(wrap_pyfunction!(crypto::is_valid_sha256, &crypto_module)?)?;
     crypto_module.add_function(wrap_pyfunction!(crypto::secure_compare, &crypto_module)?)?;
-    parent_module.add_submodule(&crypto_module)?;
+    add_submodule(parent_module, &crypto_module)?;
 
     // Register textutils module
     let textutils_module = PyModule::new(parent_module.py(), "textutils")?;
@@ -108,17 +108,79 @@ fn register_child_module(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
         textutils::regex_search,
         &textutils_module
     )?)?;
-    parent_module.add_submodule(&textutils_module)?;
+    add_submodule(parent_module, &textutils_module)?;
 
     // Register json module
     let json_module = PyModule::new(parent_module.py(), "json")?;
     json_module.add_function(wrap_pyfunction!(json::parse_json, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::parse_json5, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::repair, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::parse_lenient_llm, &json_module)?)?;
     json_module.add_function(wrap_pyfunction!(json::serialize_json, &json_module)?)?;
-    parent_module.add_submodule(&json_module)?;
+    json_module.add_function(wrap_pyfunction!(json::loads, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::load, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::dumps, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::dump, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::load_file, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::dump_file, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::canonicalize, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::parse_jsonl, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::dump_jsonl, &json_module)?)?;
+    json_module.add_class::<json::JsonLinesReader>()?;
+    json_module.add_function(wrap_pyfunction!(json::iter_items, &json_module)?)?;
+    json_module.add_class::<json::ItemsIterator>()?;
+    json_module.add_function(wrap_pyfunction!(json::query, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::compile, &json_module)?)?;
+    json_module.add_class::<json::Query>()?;
+    json_module.add_function(wrap_pyfunction!(json::decode, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::infer_schema, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::flatten, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::unflatten, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::to_rows, &json_module)?)?;
+    json_module.add_class::<json::Validator>()?;
+    json_module.add("ValidationError", json::validation_error_type(parent_module.py())?)?;
+    json_module.add_function(wrap_pyfunction!(json::diff, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::apply_patch, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::merge_patch, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::pointer_get, &json_module)?)?;
+    json_module.add_function(wrap_pyfunction!(json::pointer_set, &json_module)?)?;
+    json_module.add("JSONDecodeError", json::json_decode_error_type(parent_module.py())?)?;
+    add_submodule(parent_module, &json_module)?;
 
     // Register http module
     let http_module = PyModule::new(parent_module.py(), "http")?;
     http_module.add_function(wrap_pyfunction!(http::get, &http_module)?)?;
-    parent_module.add_submodule(&http_module)?;
+    http_module.add_function(wrap_pyfunction!(http::request, &http_module)?)?;
+    http_module.add_function(wrap_pyfunction!(http::post, &http_module)?)?;
+    http_module.add_function(wrap_pyfunction!(http::put, &http_module)?)?;
+    http_module.add_function(wrap_pyfunction!(http::patch, &http_module)?)?;
+    http_module.add_function(wrap_pyfunction!(http::delete, &http_module)?)?;
+    http_module.add_function(wrap_pyfunction!(http::head, &http_module)?)?;
+    http_module.add_class::<http::Response>()?;
+    http_module.add_class::<http::Retry>()?;
+    http_module.add_class::<http::Attempt>()?;
+    http_module.add("RequestError", http::request_error_type(parent_module.py())?)?;
+    http_module.add_class::<http::Client>()?;
+    http_module.add_function(wrap_pyfunction!(http::arequest, &http_module)?)?;
+    http_module.add_function(wrap_pyfunction!(http::aget, &http_module)?)?;
+    http_module.add_class::<http::AsyncClient>()?;
+    http_module.add_function(wrap_pyfunction!(http::get_many, &http_module)?)?;
+    add_submodule(parent_module, &http_module)?;
+    Ok(())
+}
+
+/// Adds `child` to `parent` and to `sys.modules` as `fastpy_rs.<name>`
+///
+/// Extension submodules are plain attributes by default; the `sys.modules` entry is what
+/// makes `import fastpy_rs.json as json` and `from fastpy_rs.json import loads` work.
+///
+/// # Arguments
+/// * `parent` - The parent Python module
+/// * `child` - The submodule to register
+fn add_submodule(parent: &Bound<'_, PyModule>, child: &Bound<'_, PyModule>) -> PyResult<()> {
+    parent.add_submodule(child)?;
+    let name = format!("fastpy_rs.{}", child.name()?);
+    child.setattr("__name__", &name)?;
+    parent.py().import("sys")?.getattr("modules")?.set_item(name, child)?;
     Ok(())
 }
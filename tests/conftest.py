@@ -0,0 +1,111 @@
+import json
+import select
+import socket
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qsl, urlsplit
+
+import pytest
+
+
+class Handler(BaseHTTPRequestHandler):
+    """A small httpbin-like server, so the HTTP tests don't need the network."""
+
+    protocol_version = "HTTP/1.1"
+    # Ids of /hang requests whose client disconnected while waiting.
+    aborted = set()
+    # How many times each /flaky id was requested.
+    hits = {}
+
+    def log_message(self, format, *args):
+        pass
+
+    def handle(self):
+        try:
+            super().handle()
+        except (BrokenPipeError, ConnectionResetError):
+            # The client gave up, e.g. after a timeout.
+            pass
+
+    def send(self, status, body=b"", content_type="application/json", headers=()):
+        if isinstance(body, (dict, list)):
+            body = json.dumps(body).encode()
+        self.send_response(status)
+        self.send_header("Content-Type", content_type)
+        self.send_header("Content-Length", str(len(body)))
+        for name, value in headers:
+            self.send_header(name, value)
+        self.end_headers()
+        if self.command != "HEAD":
+            self.wfile.write(body)
+
+    def handle_any(self):
+        url = urlsplit(self.path)
+        length = int(self.headers.get("Content-Length") or 0)
+        body = self.rfile.read(length) if length else b""
+        parts = url.path.strip("/").split("/")
+
+        if parts[0] == "echo":
+            self.send(200, {
+                "method": self.command,
+                "path": url.path,
+                "query": parse_qsl(url.query, keep_blank_values=True),
+                "headers": {name.lower(): value for name, value in self.headers.items()},
+                "body": body.decode("latin-1"),
+                "client_port": self.client_address[1],
+            })
+        elif parts[0] == "status":
+            self.send(int(parts[1]), b"", content_type="text/plain")
+        elif parts[0] == "redirect":
+            self.send(302, b"", headers=[("Location", "/echo")])
+        elif parts[0] == "loop":
+            self.send(302, b"", headers=[("Location", "/loop")])
+        elif parts[0] == "set-cookie":
+            cookies = [("Set-Cookie", f"{name}={value}; Path=/") for name, value in parse_qsl(url.query)]
+            self.send(200, b"{}", headers=cookies)
+        elif parts[0] == "bytes":
+            self.send(200, bytes(range(256)), content_type="application/octet-stream")
+        elif parts[0] == "latin1":
+            self.send(200, "café".encode("latin-1"), content_type="text/plain; charset=ISO-8859-1")
+        elif parts[0] == "multi":
+            self.send(200, b"{}", headers=[("X-Tag", "a"), ("X-Tag", "b")])
+        elif parts[0] == "flaky":
+            # /flaky/<id>/<failures>/<status>: fails with <status> the first <failures> times.
+            hits = Handler.hits[parts[1]] = Handler.hits.get(parts[1], 0) + 1
+            if hits <= int(parts[2]):
+                query = dict(parse_qsl(url.query))
+                headers = [("Retry-After", query["retry_after"])] if "retry_after" in query else []
+                self.send(int(parts[3]), {"hits": hits}, headers=headers)
+            else:
+                self.send(200, {"hits": hits})
+        elif parts[0] == "hang":
+            # Waits until the client goes away, for up to 5 seconds.
+            deadline = time.monotonic() + 5
+            while time.monotonic() < deadline:
+                if select.select([self.connection], [], [], 0.05)[0] and not self.connection.recv(1, socket.MSG_PEEK):
+                    Handler.aborted.add(parts[1])
+                    self.close_connection = True
+                    return
+            self.send(200, b"{}")
+        elif parts[0] == "aborted":
+            self.send(200, {"aborted": parts[1] in Handler.aborted})
+        elif parts[0] == "slow":
+            time.sleep(float(parts[1]))
+            self.send(200, b"{}")
+        else:
+            self.send(404, b"", content_type="text/plain")
+
+    do_GET = do_POST = do_PUT = do_PATCH = do_DELETE = do_HEAD = do_OPTIONS = handle_any
+
+
+@pytest.fixture(scope="session")
+def http_server():
+    """The base URL of a local HTTP server running for the whole session."""
+    server = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+    server.daemon_threads = True
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+    server.server_close()
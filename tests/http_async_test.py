@@ -0,0 +1,218 @@
+import asyncio
+import threading
+import time
+import uuid
+
+import pytest
+from fastpy_rs import http
+
+
+def run(coro):
+    return asyncio.run(coro)
+
+
+def test_aget(http_server):
+    async def main():
+        return await http.aget(f"{http_server}/echo", params={"q": "1"}, headers={"X-Id": "7"})
+
+    response = run(main())
+    assert response.status == 200
+    data = response.json()
+    assert data["method"] == "GET"
+    assert data["query"] == [["q", "1"]]
+    assert data["headers"]["x-id"] == "7"
+
+
+def test_arequest(http_server):
+    async def main():
+        return await http.arequest("PATCH", f"{http_server}/echo", json={"a": [1, 2]})
+
+    data = run(main()).json()
+    assert data["method"] == "PATCH"
+    assert data["body"] == '{"a":[1,2]}'
+    assert data["headers"]["content-type"] == "application/json"
+
+
+def test_requests_run_concurrently(http_server):
+    async def main():
+        start = time.perf_counter()
+        responses = await asyncio.gather(*(http.aget(f"{http_server}/slow/0.3") for _ in range(8)))
+        return responses, time.perf_counter() - start
+
+    responses, elapsed = run(main())
+    assert [r.status for r in responses] == [200] * 8
+    assert elapsed < 1.5
+
+
+def test_event_loop_is_not_blocked(http_server):
+    async def main():
+        ticks = 0
+
+        async def ticker():
+            nonlocal ticks
+            while True:
+                await asyncio.sleep(0.01)
+                ticks += 1
+
+        task = asyncio.create_task(ticker())
+        await http.aget(f"{http_server}/slow/0.3")
+        task.cancel()
+        return ticks
+
+    assert run(main()) > 10
+
+
+def test_error_is_raised_when_awaited():
+    async def main():
+        awaitable = http.aget("http://127.0.0.1:1/")
+        with pytest.raises(ValueError, match="Request failed"):
+            await awaitable
+
+    run(main())
+
+
+def test_timeout(http_server):
+    async def main():
+        with pytest.raises(ValueError, match="Request failed"):
+            await http.aget(f"{http_server}/slow/1", timeout=0.2)
+
+    run(main())
+
+
+def test_bad_arguments_raise_immediately():
+    with pytest.raises(ValueError, match="Invalid HTTP method"):
+        http.arequest("BAD METHOD", "http://127.0.0.1:1/")
+
+
+def test_requires_running_loop(http_server):
+    with pytest.raises(RuntimeError):
+        http.aget(f"{http_server}/echo")
+
+
+def test_cancel_aborts_request(http_server):
+    request_id = uuid.uuid4().hex
+
+    async def main():
+        task = asyncio.ensure_future(http.aget(f"{http_server}/hang/{request_id}"))
+        await asyncio.sleep(0.2)
+        task.cancel()
+        with pytest.raises(asyncio.CancelledError):
+            await task
+
+    start = time.perf_counter()
+    run(main())
+    assert time.perf_counter() - start < 1.0
+    for _ in range(40):
+        if http.request("GET", f"{http_server}/aborted/{request_id}").json()["aborted"]:
+            break
+        time.sleep(0.05)
+    else:
+        pytest.fail("the request was not aborted")
+
+
+def test_wait_for_timeout_aborts_request(http_server):
+    request_id = uuid.uuid4().hex
+
+    async def main():
+        with pytest.raises(asyncio.TimeoutError):
+            await asyncio.wait_for(http.aget(f"{http_server}/hang/{request_id}"), 0.2)
+
+    run(main())
+    for _ in range(40):
+        if http.request("GET", f"{http_server}/aborted/{request_id}").json()["aborted"]:
+            break
+        time.sleep(0.05)
+    else:
+        pytest.fail("the request was not aborted")
+
+
+def test_async_client(http_server):
+    async def main():
+        async with http.AsyncClient(base_url=http_server, headers={"X-App": "demo"}) as client:
+            get = await client.get("/echo", params={"n": 1})
+            post = await client.post("echo", json=[1])
+            head = await client.head("/echo")
+            other = await client.request("OPTIONS", "/echo")
+            return client, get, post, head, other
+
+    client, get, post, head, other = run(main())
+    assert get.json()["headers"]["x-app"] == "demo"
+    assert get.json()["query"] == [["n", "1"]]
+    assert post.json()["body"] == "[1]"
+    assert head.content == b""
+    assert other.json()["method"] == "OPTIONS"
+    assert client.closed
+
+
+@pytest.mark.parametrize("name", ["put", "patch", "delete"])
+def test_async_client_methods(http_server, name):
+    async def main():
+        async with http.AsyncClient(base_url=http_server) as client:
+            return await getattr(client, name)("/echo", data=b"x")
+
+    data = run(main()).json()
+    assert data["method"] == name.upper()
+    assert data["body"] == "x"
+
+
+def test_async_client_reuses_connections(http_server):
+    async def main():
+        async with http.AsyncClient(base_url=http_server) as client:
+            return {(await client.get("/echo")).json()["client_port"] for _ in range(5)}
+
+    assert len(run(main())) == 1
+
+
+def test_async_client_cookies(http_server):
+    async def main():
+        async with http.AsyncClient(base_url=http_server, cookies={"theme": "dark"}) as client:
+            await client.get("/set-cookie", params={"session": "xyz"})
+            return (await client.get("/echo")).json()["headers"]["cookie"]
+
+    assert sorted(run(main()).split("; ")) == ["session=xyz", "theme=dark"]
+
+
+def test_async_client_timeout(http_server):
+    async def main():
+        async with http.AsyncClient(base_url=http_server, timeout=0.2) as client:
+            with pytest.raises(ValueError, match="Request failed"):
+                await client.get("/slow/1")
+            first = await client.get("/slow/0.4", timeout=2)
+            # An explicit None disables the client's timeout for that request.
+            second = await client.get("/slow/0.4", timeout=None)
+            return first.status, second.status
+
+    assert run(main()) == (200, 200)
+
+
+def test_async_client_aclose(http_server):
+    async def main():
+        client = http.AsyncClient(base_url=http_server)
+        await client.aclose()
+        assert client.closed
+        with pytest.raises(ValueError, match="Client is closed"):
+            client.get("/echo")
+
+    run(main())
+
+
+def test_async_client_in_several_loops(http_server):
+    client = http.AsyncClient(base_url=http_server)
+    results = []
+
+    def work():
+        async def main():
+            return (await client.get("/echo")).status
+
+        results.append(asyncio.run(main()))
+
+    threads = [threading.Thread(target=work) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert results == [200] * 4
+
+
+def test_async_client_repr():
+    assert repr(http.AsyncClient(base_url="https://example.com")) == '<AsyncClient base_url="https://example.com/">'
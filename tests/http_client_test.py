@@ -0,0 +1,158 @@
+import threading
+
+import pytest
+from fastpy_rs import http
+
+
+def test_request_through_client(http_server):
+    with http.Client() as client:
+        response = client.get(f"{http_server}/echo", params={"q": "1"})
+        assert response.status == 200
+        assert response.json()["query"] == [["q", "1"]]
+
+
+@pytest.mark.parametrize("name", ["post", "put", "patch", "delete"])
+def test_methods(http_server, name):
+    with http.Client(base_url=http_server) as client:
+        response = getattr(client, name)("/echo", json={"x": 1})
+        data = response.json()
+        assert data["method"] == name.upper()
+        assert data["body"] == '{"x":1}'
+
+
+def test_head(http_server):
+    with http.Client(base_url=http_server) as client:
+        response = client.head("/echo")
+        assert response.status == 200
+        assert response.content == b""
+
+
+def test_generic_request(http_server):
+    with http.Client(base_url=http_server) as client:
+        assert client.request("OPTIONS", "echo").json()["method"] == "OPTIONS"
+
+
+def test_connections_are_reused(http_server):
+    with http.Client(base_url=http_server) as client:
+        ports = {client.get("/echo").json()["client_port"] for _ in range(5)}
+    assert len(ports) == 1
+
+
+@pytest.mark.parametrize("base, path", [("", "/echo"), ("", "echo"), ("/", "echo"), ("/", "/echo")])
+def test_base_url_joining(http_server, base, path):
+    with http.Client(base_url=http_server + base) as client:
+        assert client.get(path).json()["path"] == "/echo"
+
+
+def test_base_url_keeps_path(http_server):
+    with http.Client(base_url=f"{http_server}/echo") as client:
+        assert client.get("users/1").json()["path"] == "/echo/users/1"
+
+
+def test_absolute_url_ignores_base(http_server):
+    with http.Client(base_url="http://127.0.0.1:1/api") as client:
+        assert client.get(f"{http_server}/echo").json()["path"] == "/echo"
+
+
+def test_invalid_base_url():
+    with pytest.raises(ValueError, match="Invalid base_url"):
+        http.Client(base_url="not a url")
+
+
+def test_default_headers(http_server):
+    with http.Client(base_url=http_server, headers={"X-Token": "a", "X-App": "demo"}) as client:
+        headers = client.get("/echo", headers={"x-token": "b"}).json()["headers"]
+    assert headers["x-token"] == "b"
+    assert headers["x-app"] == "demo"
+
+
+def test_default_timeout(http_server):
+    with http.Client(base_url=http_server, timeout=0.2) as client:
+        with pytest.raises(ValueError, match="Request failed"):
+            client.get("/slow/1")
+        assert client.get("/slow/0.5", timeout=2).status == 200
+        # An explicit None disables the client's timeout for that request.
+        assert client.get("/slow/0.5", timeout=None).status == 200
+        assert client.request("GET", "/slow/0.5", timeout=None).status == 200
+
+
+def test_no_timeout(http_server):
+    with http.Client(base_url=http_server, timeout=None) as client:
+        assert client.get("/slow/0.1").status == 200
+
+
+def test_initial_cookies(http_server):
+    with http.Client(base_url=http_server, cookies={"session": "abc"}) as client:
+        assert client.get("/echo").json()["headers"]["cookie"] == "session=abc"
+
+
+def test_initial_cookies_stay_on_the_base_url_host(http_server):
+    other_host = http_server.replace("127.0.0.1", "localhost")
+    with http.Client(base_url=http_server, cookies={"session": "abc"}) as client:
+        assert "cookie" not in client.get(f"{other_host}/echo").json()["headers"]
+        assert client.get(f"{http_server}/echo").json()["headers"]["cookie"] == "session=abc"
+    with http.Client(cookies={"session": "abc"}) as client:
+        assert client.get(f"{other_host}/echo").json()["headers"]["cookie"] == "session=abc"
+
+
+def test_cookies_from_responses(http_server):
+    with http.Client(base_url=http_server, cookies={"session": "abc", "theme": "dark"}) as client:
+        client.get("/set-cookie", params={"session": "xyz", "lang": "en"})
+        cookie = client.get("/echo").json()["headers"]["cookie"]
+    assert sorted(cookie.split("; ")) == ["lang=en", "session=xyz", "theme=dark"]
+
+
+def test_cookies_are_per_client(http_server):
+    with http.Client(base_url=http_server) as client:
+        client.get("/set-cookie", params={"a": "1"})
+    with http.Client(base_url=http_server) as client:
+        assert "cookie" not in client.get("/echo").json()["headers"]
+
+
+def test_close(http_server):
+    client = http.Client(base_url=http_server)
+    assert not client.closed
+    client.close()
+    client.close()
+    assert client.closed
+    with pytest.raises(ValueError, match="Client is closed"):
+        client.get("/echo")
+
+
+def test_context_manager_closes_on_error(http_server):
+    with pytest.raises(KeyError):
+        with http.Client(base_url=http_server) as client:
+            raise KeyError("boom")
+    assert client.closed
+
+
+def test_shared_between_threads(http_server):
+    results = []
+    with http.Client(base_url=http_server) as client:
+        def work(i):
+            results.append(client.get("/echo", params={"i": i}).json()["query"][0][1])
+
+        threads = [threading.Thread(target=work, args=(i,)) for i in range(16)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+    assert sorted(results, key=int) == [str(i) for i in range(16)]
+
+
+def test_requests_release_the_gil(http_server):
+    import time
+
+    with http.Client(base_url=http_server) as client:
+        threads = [threading.Thread(target=client.get, args=("/slow/0.3",)) for _ in range(4)]
+        start = time.perf_counter()
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        assert time.perf_counter() - start < 1.0
+
+
+def test_repr(http_server):
+    assert repr(http.Client()) == "<Client>"
+    assert repr(http.Client(base_url="https://example.com/api")) == '<Client base_url="https://example.com/api">'
@@ -0,0 +1,83 @@
+import time
+
+import pytest
+from fastpy_rs import http
+
+
+def test_results_in_input_order(http_server):
+    urls = [f"{http_server}/slow/{delay}" for delay in (0.3, 0.0, 0.2, 0.1)]
+    results = http.get_many(urls)
+    assert [r.url for r in results] == urls
+    assert [r.status for r in results] == [200] * 4
+
+
+def test_runs_concurrently(http_server):
+    urls = [f"{http_server}/slow/0.3"] * 10
+    start = time.perf_counter()
+    results = http.get_many(urls, concurrency=10)
+    assert time.perf_counter() - start < 1.5
+    assert all(r.status == 200 for r in results)
+
+
+def test_concurrency_limit(http_server):
+    urls = [f"{http_server}/slow/0.2"] * 4
+    start = time.perf_counter()
+    http.get_many(urls, concurrency=2)
+    assert time.perf_counter() - start >= 0.4
+
+
+def test_errors_are_captured_per_item(http_server):
+    urls = [f"{http_server}/echo", "http://127.0.0.1:1/", "not a url", f"{http_server}/status/503"]
+    results = http.get_many(urls)
+    assert len(results) == 4
+    assert results[0].json()["method"] == "GET"
+    assert isinstance(results[1], ValueError)
+    assert str(results[1]).startswith("Request failed")
+    assert isinstance(results[2], ValueError)
+    assert results[3].status == 503
+
+
+def test_timeout_per_item(http_server):
+    results = http.get_many([f"{http_server}/slow/1", f"{http_server}/echo"], timeout=0.3)
+    assert isinstance(results[0], ValueError)
+    assert results[1].status == 200
+
+
+def test_headers(http_server):
+    results = http.get_many([f"{http_server}/echo"] * 2, headers={"X-Token": "t"})
+    assert [r.json()["headers"]["x-token"] for r in results] == ["t", "t"]
+
+
+def test_accepts_any_iterable(http_server):
+    results = http.get_many(f"{http_server}/echo?i={i}" for i in range(5))
+    assert [r.json()["query"] for r in results] == [[["i", str(i)]] for i in range(5)]
+
+
+def test_empty():
+    assert http.get_many([]) == []
+
+
+def test_many_urls(http_server):
+    results = http.get_many([f"{http_server}/echo?i={i}" for i in range(200)], concurrency=16)
+    assert [r.json()["query"][0][1] for r in results] == [str(i) for i in range(200)]
+
+
+def test_bad_concurrency():
+    with pytest.raises(ValueError, match="concurrency"):
+        http.get_many(["http://127.0.0.1:1/"], concurrency=0)
+
+
+def test_non_string_url():
+    with pytest.raises(TypeError):
+        http.get_many([1])
+
+
+def test_interrupt_cancels_batch(http_server):
+    import _thread
+    import threading
+
+    threading.Timer(0.3, _thread.interrupt_main).start()
+    start = time.perf_counter()
+    with pytest.raises(KeyboardInterrupt):
+        http.get_many([f"{http_server}/hang/interrupted"] * 2)
+    assert time.perf_counter() - start < 2
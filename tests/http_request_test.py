@@ -0,0 +1,156 @@
+import pytest
+import fastpy_rs
+from fastpy_rs import http
+
+
+def test_request_get(http_server):
+    response = http.request("GET", f"{http_server}/echo")
+    assert response.status == 200
+    assert response.url == f"{http_server}/echo"
+    assert response.headers["content-type"] == "application/json"
+    data = response.json()
+    assert data["method"] == "GET"
+    assert data["body"] == ""
+
+
+def test_method_is_case_insensitive(http_server):
+    assert http.request("post", f"{http_server}/echo").json()["method"] == "POST"
+
+
+@pytest.mark.parametrize("name", ["post", "put", "patch", "delete"])
+def test_shortcuts(http_server, name):
+    response = getattr(http, name)(f"{http_server}/echo")
+    assert response.json()["method"] == name.upper()
+
+
+def test_head(http_server):
+    response = http.head(f"{http_server}/echo")
+    assert response.status == 200
+    assert response.content == b""
+    assert int(response.headers["content-length"]) > 0
+
+
+def test_params(http_server):
+    response = http.request(
+        "GET", f"{http_server}/echo", params={"q": "a b&c", "tag": ["x", "y"], "skip": None, "n": 1}
+    )
+    assert response.json()["query"] == [["q", "a b&c"], ["tag", "x"], ["tag", "y"], ["n", "1"]]
+
+
+def test_params_as_pairs(http_server):
+    response = http.request("GET", f"{http_server}/echo?a=1", params=[("b", "2"), ("b", "3")])
+    assert response.json()["query"] == [["a", "1"], ["b", "2"], ["b", "3"]]
+
+
+def test_headers(http_server):
+    response = http.request("GET", f"{http_server}/echo", headers={"X-Token": "secret", "X-Number": 5})
+    headers = response.json()["headers"]
+    assert headers["x-token"] == "secret"
+    assert headers["x-number"] == "5"
+
+
+def test_json_body(http_server):
+    response = http.post(f"{http_server}/echo", json={"name": "Алиса", "ids": [1, 2]})
+    data = response.json()
+    assert data["headers"]["content-type"] == "application/json"
+    assert data["body"].encode("latin-1").decode() == '{"name":"Алиса","ids":[1,2]}'
+
+
+def test_json_body_unserializable():
+    with pytest.raises(ValueError):
+        http.post("http://127.0.0.1:1/", json={"x": object()})
+
+
+def test_raw_body(http_server):
+    assert http.put(f"{http_server}/echo", data=b"\x00\x01raw").json()["body"] == "\x00\x01raw"
+    assert http.put(f"{http_server}/echo", data="text").json()["body"] == "text"
+
+
+def test_form_body(http_server):
+    data = http.post(f"{http_server}/echo", data={"a": "x y", "b": ["1", "2"]}).json()
+    assert data["headers"]["content-type"] == "application/x-www-form-urlencoded"
+    assert data["body"] == "a=x+y&b=1&b=2"
+
+
+def test_explicit_content_type_wins(http_server):
+    data = http.post(f"{http_server}/echo", json=[1], headers={"Content-Type": "application/vnd.api+json"}).json()
+    assert data["headers"]["content-type"] == "application/vnd.api+json"
+
+
+def test_json_and_data_together():
+    with pytest.raises(ValueError, match="either `json` or `data`"):
+        http.post("http://127.0.0.1:1/", json={}, data=b"")
+
+
+def test_bad_data_type():
+    with pytest.raises(TypeError, match="`data` must be a dict or a list of pairs, not int"):
+        http.post("http://127.0.0.1:1/", data=42)
+    with pytest.raises(TypeError, match="`params` must be a dict or a list of pairs, not int"):
+        http.request("GET", "http://127.0.0.1:1/", params=5)
+    with pytest.raises(TypeError, match="`params` must be a dict or a list of pairs"):
+        http.request("GET", "http://127.0.0.1:1/", params=[1, 2])
+
+
+def test_invalid_method():
+    with pytest.raises(ValueError, match="Invalid HTTP method"):
+        http.request("BAD METHOD", "http://127.0.0.1:1/")
+
+
+def test_error_status_is_returned(http_server):
+    response = http.request("GET", f"{http_server}/status/404")
+    assert response.status == 404
+    assert repr(response) == "<Response [404]>"
+    with pytest.raises(ValueError, match="Status code: 404 Not Found"):
+        response.raise_for_status()
+
+
+def test_raise_for_status_success(http_server):
+    assert http.request("GET", f"{http_server}/status/204").raise_for_status() is None
+
+
+def test_redirect_is_followed(http_server):
+    response = http.request("GET", f"{http_server}/redirect")
+    assert response.status == 200
+    assert response.url == f"{http_server}/echo"
+
+
+def test_content_is_bytes(http_server):
+    response = http.request("GET", f"{http_server}/bytes")
+    assert response.content == bytes(range(256))
+
+
+def test_text_uses_charset(http_server):
+    assert http.request("GET", f"{http_server}/latin1").text == "café"
+
+
+def test_text_defaults_to_utf8(http_server):
+    response = http.post(f"{http_server}/echo", data="ü".encode())
+    assert '"body": "\\u00c3\\u00bc"' in response.text
+
+
+def test_repeated_headers_are_joined(http_server):
+    assert http.request("GET", f"{http_server}/multi").headers["x-tag"] == "a, b"
+
+
+def test_json_invalid_body(http_server):
+    with pytest.raises(fastpy_rs.json.JSONDecodeError):
+        http.request("GET", f"{http_server}/bytes").json()
+
+
+def test_timeout(http_server):
+    with pytest.raises(ValueError, match="Request failed"):
+        http.request("GET", f"{http_server}/slow/1", timeout=0.2)
+
+
+def test_bad_timeout():
+    with pytest.raises(ValueError, match="Timeout"):
+        http.request("GET", "http://127.0.0.1:1/", timeout=-1)
+
+
+def test_connection_error():
+    with pytest.raises(ValueError, match="Request failed"):
+        http.request("GET", "http://127.0.0.1:1/")
+
+
+def test_no_timeout(http_server):
+    assert http.request("GET", f"{http_server}/slow/0.1", timeout=None).status == 200
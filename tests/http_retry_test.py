@@ -0,0 +1,228 @@
+import asyncio
+import time
+import uuid
+from email.utils import formatdate
+
+import pytest
+from fastpy_rs import http
+
+FAST = dict(backoff=0.01, jitter=False)
+
+
+def flaky(base, failures, status=503, **query):
+    url = f"{base}/flaky/{uuid.uuid4().hex}/{failures}/{status}"
+    if query:
+        url += "?" + "&".join(f"{k}={v}" for k, v in query.items())
+    return url
+
+
+def test_retries_until_success(http_server):
+    response = http.request("GET", flaky(http_server, 2), retry=http.Retry(**FAST))
+    assert response.status == 200
+    assert response.json() == {"hits": 3}
+    assert [a.status for a in response.attempts] == [503, 503, 200]
+    assert [a.error for a in response.attempts] == [None, None, None]
+
+
+def test_no_retry_by_default(http_server):
+    response = http.request("GET", flaky(http_server, 1))
+    assert response.status == 503
+    assert len(response.attempts) == 1
+    assert response.attempts[0].status == 503
+    assert response.attempts[0].delay == 0
+
+
+def test_gives_up_after_max_attempts(http_server):
+    response = http.request("GET", flaky(http_server, 10), retry=http.Retry(max_attempts=4, **FAST))
+    assert response.status == 503
+    assert response.json() == {"hits": 4}
+    assert len(response.attempts) == 4
+    with pytest.raises(ValueError, match="Status code: 503"):
+        response.raise_for_status()
+
+
+def test_get_retries_and_returns_the_body(http_server):
+    assert http.get(flaky(http_server, 2), retry=http.Retry(**FAST)) == '{"hits": 3}'
+    with pytest.raises(ValueError, match="Status code: 503 Service Unavailable"):
+        http.get(flaky(http_server, 10), retry=http.Retry(max_attempts=2, **FAST))
+    with pytest.raises(http.RequestError) as info:
+        http.get("http://127.0.0.1:1/", retry=http.Retry(max_attempts=2, **FAST))
+    assert len(info.value.attempts) == 2
+
+
+def test_status_not_in_list_is_not_retried(http_server):
+    response = http.request("GET", flaky(http_server, 1, status=404), retry=http.Retry(**FAST))
+    assert response.status == 404
+    assert len(response.attempts) == 1
+
+
+def test_custom_statuses(http_server):
+    retry = http.Retry(statuses=[404], **FAST)
+    assert http.request("GET", flaky(http_server, 1, status=404), retry=retry).status == 200
+    assert http.request("GET", flaky(http_server, 1, status=503), retry=retry).status == 503
+
+
+def test_post_is_not_retried_by_default(http_server):
+    response = http.post(flaky(http_server, 1), retry=http.Retry(**FAST))
+    assert response.status == 503
+    assert len(response.attempts) == 1
+
+
+def test_custom_methods(http_server):
+    response = http.post(flaky(http_server, 1), retry=http.Retry(methods=["post"], **FAST))
+    assert response.status == 200
+    assert len(response.attempts) == 2
+
+
+def test_exponential_backoff(http_server):
+    retry = http.Retry(max_attempts=4, backoff=0.05, jitter=False)
+    response = http.request("GET", flaky(http_server, 3), retry=retry)
+    assert [a.delay for a in response.attempts] == pytest.approx([0.05, 0.1, 0.2, 0])
+
+
+def test_max_backoff(http_server):
+    retry = http.Retry(max_attempts=4, backoff=0.05, max_backoff=0.08, jitter=False)
+    response = http.request("GET", flaky(http_server, 3), retry=retry)
+    assert [a.delay for a in response.attempts] == pytest.approx([0.05, 0.08, 0.08, 0])
+
+
+def test_jitter(http_server):
+    retry = http.Retry(max_attempts=6, backoff=0.02, jitter=True)
+    response = http.request("GET", flaky(http_server, 5), retry=retry)
+    for n, attempt in enumerate(response.attempts[:-1]):
+        full = 0.02 * 2 ** n
+        assert full / 2 <= attempt.delay <= full
+
+
+def test_waits_between_attempts(http_server):
+    start = time.perf_counter()
+    http.request("GET", flaky(http_server, 2), retry=http.Retry(backoff=0.1, jitter=False))
+    assert time.perf_counter() - start >= 0.3
+
+
+def test_retry_after_seconds(http_server):
+    retry = http.Retry(backoff=0.01, jitter=False)
+    start = time.perf_counter()
+    response = http.request("GET", flaky(http_server, 1, status=429, retry_after=1), retry=retry)
+    assert time.perf_counter() - start >= 1
+    assert response.status == 200
+    assert response.attempts[0].delay == 1
+
+
+def test_retry_after_date(http_server):
+    past = formatdate(time.time() - 60, usegmt=True).replace(" ", "%20")
+    response = http.request("GET", flaky(http_server, 1, retry_after=past), retry=http.Retry(**FAST))
+    assert response.status == 200
+    assert response.attempts[0].delay == 0
+
+
+def test_retry_after_longer_than_max_backoff(http_server):
+    retry = http.Retry(max_backoff=1, **FAST)
+    response = http.request("GET", flaky(http_server, 1, retry_after=120), retry=retry)
+    assert response.status == 503
+    assert len(response.attempts) == 1
+
+
+def test_retry_after_ignored(http_server):
+    retry = http.Retry(respect_retry_after=False, **FAST)
+    start = time.perf_counter()
+    response = http.request("GET", flaky(http_server, 1, retry_after=120), retry=retry)
+    assert time.perf_counter() - start < 1
+    assert response.status == 200
+
+
+def test_connection_errors_are_retried():
+    with pytest.raises(http.RequestError, match="Request failed") as info:
+        http.request("GET", "http://127.0.0.1:1/", retry=http.Retry(max_attempts=3, **FAST))
+    attempts = info.value.attempts
+    assert len(attempts) == 3
+    assert all(a.status is None and a.error.startswith("Request failed") for a in attempts)
+    assert [a.delay for a in attempts] == pytest.approx([0.01, 0.02, 0])
+
+
+@pytest.mark.parametrize("url", ["not a url", "ftp://127.0.0.1/file", "/loop"])
+def test_permanent_errors_are_not_retried(http_server, url):
+    if url.startswith("/"):
+        url = http_server + url
+    start = time.perf_counter()
+    with pytest.raises(http.RequestError) as info:
+        http.request("GET", url, retry=http.Retry(max_attempts=3, backoff=5))
+    assert time.perf_counter() - start < 2
+    assert len(info.value.attempts) == 1
+    assert info.value.attempts[0].delay == 0
+
+
+def test_async_permanent_errors_are_not_retried(http_server):
+    async def main():
+        with pytest.raises(http.RequestError) as info:
+            await http.aget(f"{http_server}/loop", retry=http.Retry(max_attempts=3, backoff=5))
+        return info.value
+
+    assert len(asyncio.run(main()).attempts) == 1
+
+
+def test_request_error_without_retry():
+    with pytest.raises(http.RequestError) as info:
+        http.request("GET", "http://127.0.0.1:1/")
+    assert isinstance(info.value, ValueError)
+    assert len(info.value.attempts) == 1
+
+
+def test_timeouts_are_retried(http_server):
+    with pytest.raises(http.RequestError) as info:
+        http.request("GET", f"{http_server}/slow/1", timeout=0.1, retry=http.Retry(max_attempts=2, **FAST))
+    assert len(info.value.attempts) == 2
+    assert info.value.attempts[0].elapsed >= 0.1
+
+
+def test_client_retry(http_server):
+    with http.Client(base_url=http_server, retry=http.Retry(**FAST)) as client:
+        assert client.get(flaky("", 2)).status == 200
+        assert client.get(flaky("", 2), retry=http.Retry(max_attempts=1)).status == 503
+
+
+def test_async_retry(http_server):
+    async def main():
+        first = await http.aget(flaky(http_server, 2), retry=http.Retry(**FAST))
+        async with http.AsyncClient(base_url=http_server, retry=http.Retry(**FAST)) as client:
+            second = await client.get(flaky("", 1))
+        return first, second
+
+    first, second = asyncio.run(main())
+    assert [a.status for a in first.attempts] == [503, 503, 200]
+    assert [a.status for a in second.attempts] == [503, 200]
+
+
+def test_async_request_error(http_server):
+    async def main():
+        with pytest.raises(http.RequestError) as info:
+            await http.aget("http://127.0.0.1:1/", retry=http.Retry(max_attempts=2, **FAST))
+        return info.value
+
+    assert len(asyncio.run(main()).attempts) == 2
+
+
+def test_get_many_retry(http_server):
+    urls = [flaky(http_server, 1), "http://127.0.0.1:1/"]
+    results = http.get_many(urls, retry=http.Retry(max_attempts=2, **FAST))
+    assert [a.status for a in results[0].attempts] == [503, 200]
+    assert isinstance(results[1], http.RequestError)
+    assert len(results[1].attempts) == 2
+
+
+def test_retry_defaults():
+    retry = http.Retry()
+    assert retry.max_attempts == 3
+    assert retry.backoff == 0.5
+    assert retry.max_backoff == 30.0
+    assert retry.jitter is True
+    assert retry.statuses == [429, 500, 502, 503, 504]
+    assert retry.methods == ["GET", "HEAD", "OPTIONS", "PUT", "DELETE", "TRACE"]
+    assert retry.respect_retry_after is True
+    assert repr(http.Retry(2, statuses=[503], methods=["get"])).startswith("Retry(max_attempts=2, backoff=0.5")
+
+
+@pytest.mark.parametrize("kwargs", [{"max_attempts": 0}, {"backoff": -1}, {"max_backoff": float("inf")}, {"methods": ["NOT VALID"]}])
+def test_retry_invalid(kwargs):
+    with pytest.raises(ValueError):
+        http.Retry(**kwargs)
@@ -0,0 +1,125 @@
+import json as std_json
+import math
+
+import pytest
+from fastpy_rs import json
+
+
+SPEC_EXAMPLE = """
+// Example from https://json5.org
+{
+  // comments
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  lineBreaks: "Look, Mom! \\
+No \\\\n's!",
+  hexadecimal: 0xdecaf,
+  leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+  positiveSign: +1,
+  trailingComma: 'in objects', andIn: ['arrays',],
+  "backwardsCompatible": "with JSON",
+}
+"""
+
+
+def test_spec_example():
+    assert json.parse_json5(SPEC_EXAMPLE) == {
+        "unquoted": "and you can quote me on that",
+        "singleQuotes": 'I can use "double quotes" here',
+        "lineBreaks": "Look, Mom! No \\n's!",
+        "hexadecimal": 0xDECAF,
+        "leadingDecimalPoint": 0.8675309,
+        "andTrailing": 8675309.0,
+        "positiveSign": 1,
+        "trailingComma": "in objects",
+        "andIn": ["arrays"],
+        "backwardsCompatible": "with JSON",
+    }
+
+
+@pytest.mark.parametrize(
+    "text",
+    [
+        '{"a": [1, 2.5, -3e2, true, false, null, "x\\u00e9\\n"], "b": {}}',
+        '[]',
+        '"\\ud83d\\ude00"',
+        '123456789012345678901234567890',
+    ],
+)
+def test_plain_json_matches_stdlib(text):
+    result = json.parse_json5(text)
+    assert result == std_json.loads(text)
+    assert type(result) is type(std_json.loads(text))
+
+
+@pytest.mark.parametrize(
+    "text, expected",
+    [
+        ("0x1F", 31),
+        ("-0XFF", -255),
+        ("0xFFFFFFFFFFFFFFFFFF", 0xFFFFFFFFFFFFFFFFFF),
+        ("+.5", 0.5),
+        ("5.", 5.0),
+        ("-5e-1", -0.5),
+        ("'it\\'s'", "it's"),
+        ("'\\x41\\v\\0\\q\\/'", "A\x0b\x00q/"),
+        ('"a\\\r\nb"', "ab"),
+        ("'\u2028'", "\u2028"),
+        ("{$_a1\\u0062: 1, ünï: 2, 'quoted key': 3}", {"$_a1b": 1, "ünï": 2, "quoted key": 3}),
+        ("[1, /* two */ 2, // three\n 3,]", [1, 2, 3]),
+        ("\ufeff\u00a0{a:1}\u2029", {"a": 1}),
+        ("{a: 1, a: 2}", {"a": 2}),
+    ],
+)
+def test_extensions(text, expected):
+    assert json.parse_json5(text) == expected
+
+
+def test_non_finite_numbers():
+    result = json.parse_json5("[Infinity, -Infinity, +Infinity, NaN, -NaN]")
+    assert result[:3] == [math.inf, -math.inf, math.inf]
+    assert all(isinstance(x, float) and math.isnan(x) for x in result[3:])
+
+
+def test_bytes_input():
+    assert json.parse_json5(b"{a: 'b'}") == {"a": "b"}
+    assert json.parse_json5(bytearray(b"[1,]")) == [1]
+    assert json.parse_json5(memoryview(b"// x\n2")) == 2
+
+
+@pytest.mark.parametrize(
+    "text, pos",
+    [
+        ("{a: 1,,}", 6),
+        ("[1,,]", 3),
+        ("[,]", 1),
+        ("01", 0),
+        ("1e", 2),
+        ("0x", 0),
+        ("/* open", 0),
+        ("'abc", 0),
+        ("'a\nb'", 2),
+        ("'\\1'", 1),
+        ("{1a: 2}", 1),
+        ("{a 1}", 3),
+        ("[1] x", 4),
+        ("undefined", 0),
+        ("", 0),
+    ],
+)
+def test_errors(text, pos):
+    with pytest.raises(json.JSONDecodeError) as exc_info:
+        json.parse_json5(text)
+    assert exc_info.value.pos == pos
+    assert isinstance(exc_info.value, ValueError)
+
+
+def test_error_location():
+    with pytest.raises(json.JSONDecodeError) as exc_info:
+        json.parse_json5("{\n  // comment\n  a: [1, 2,,]\n}")
+    assert (exc_info.value.lineno, exc_info.value.colno) == (3, 12)
+
+
+def test_invalid_input_type():
+    with pytest.raises(TypeError):
+        json.parse_json5(5)
@@ -0,0 +1,111 @@
+import decimal
+import hashlib
+import json as std_json
+import struct
+
+import pytest
+from fastpy_rs import crypto, json
+
+
+def test_rfc8785_sample():
+    text = (
+        '{"numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001], '
+        '"string": "\\u20ac$\\u000F\\u000aA\'\\u0042\\u0022\\u005c\\\\\\"\\/", "literals": [null, true, false]}'
+    )
+    expected = (
+        '{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],'
+        '"string":"€$\\u000f\\nA\'B\\"\\\\\\\\\\"/"}'
+    )
+    assert json.canonicalize(std_json.loads(text)) == expected.encode()
+
+
+def test_keys_sorted_by_utf16_code_units():
+    data = {
+        "€": "Euro Sign",
+        "\r": "Carriage Return",
+        "דּ": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "\U0001f600": "Emoji: Grinning Face",
+        "\u0080": "Control",
+        "ö": "Latin Small Letter O With Diaeresis",
+    }
+    keys = list(std_json.loads(json.canonicalize(data)))
+    assert keys == ["\r", "1", "\u0080", "ö", "€", "\U0001f600", "דּ"]
+
+
+@pytest.mark.parametrize(
+    "bits, expected",
+    [
+        ("0000000000000000", "0"),
+        ("8000000000000000", "0"),
+        ("0000000000000001", "5e-324"),
+        ("8000000000000001", "-5e-324"),
+        ("7fefffffffffffff", "1.7976931348623157e+308"),
+        ("ffefffffffffffff", "-1.7976931348623157e+308"),
+        ("4340000000000000", "9007199254740992"),
+        ("c340000000000000", "-9007199254740992"),
+        ("4430000000000000", "295147905179352830000"),
+        ("44b52d02c7e14af5", "9.999999999999997e+22"),
+        ("44b52d02c7e14af6", "1e+23"),
+        ("44b52d02c7e14af7", "1.0000000000000001e+23"),
+        ("444b1ae4d6e2ef4e", "999999999999999700000"),
+        ("444b1ae4d6e2ef4f", "999999999999999900000"),
+        ("444b1ae4d6e2ef50", "1e+21"),
+        ("3eb0c6f7a0b5ed8c", "9.999999999999997e-7"),
+        ("3eb0c6f7a0b5ed8d", "0.000001"),
+        ("41b3de4355555553", "333333333.3333332"),
+        ("41b3de4355555554", "333333333.33333325"),
+        ("41b3de4355555555", "333333333.3333333"),
+        ("41b3de4355555556", "333333333.3333334"),
+        ("41b3de4355555557", "333333333.33333343"),
+        ("becbf647612f3696", "-0.0000033333333333333333"),
+        ("43143ff3c1cb0959", "1424953923781206.2"),
+    ],
+)
+def test_rfc8785_number_formatting(bits, expected):
+    value = struct.unpack(">d", bytes.fromhex(bits))[0]
+    assert json.canonicalize(value) == expected.encode()
+
+
+@pytest.mark.parametrize(
+    "value, expected",
+    [
+        (1.0, b"1"),
+        (-7, b"-7"),
+        (2**53, b"9007199254740992"),
+        (2**60, b"1152921504606847000"),
+        (10**21, b"1e+21"),
+        (2**70, b"1.1805916207174113e+21"),
+        (decimal.Decimal("4.50"), b"4.5"),
+        (True, b"true"),
+        ("\x00\x1f\x7f\u2028/", b'"\\u0000\\u001f\x7f\xe2\x80\xa8/"'),
+        ((1, [2.5e-7], {}), b"[1,[2.5e-7],{}]"),
+    ],
+)
+def test_scalars(value, expected):
+    assert json.canonicalize(value) == expected
+
+
+@pytest.mark.parametrize(
+    "value",
+    [float("nan"), float("inf"), [float("-inf")], 2**53 + 1, -(2**64) - 1, 10**400, decimal.Decimal("1e400"), "\ud800"],
+)
+def test_rejects_values_without_a_canonical_form(value):
+    with pytest.raises(ValueError):
+        json.canonicalize(value)
+
+
+def test_default_and_nesting():
+    assert json.canonicalize({"z": {1, 2} - {2}, "a": object()}, default=lambda o: "obj") == b'{"a":"obj","z":[1]}'
+    loop = []
+    loop.append(loop)
+    with pytest.raises(ValueError):
+        json.canonicalize(loop)
+
+
+def test_hash_is_independent_of_key_order_and_whitespace():
+    first = {"amount": 10.0, "currency": "EUR", "items": [{"sku": "A", "qty": 1}]}
+    second = std_json.loads('{ "items": [ {"qty": 1, "sku": "A"} ], "currency": "EUR", "amount": 1e1 }')
+    canonical = json.canonicalize(first)
+    assert canonical == json.canonicalize(second)
+    assert crypto.sha256(canonical) == hashlib.sha256(canonical).hexdigest()
@@ -0,0 +1,191 @@
+import decimal
+import io
+import json as std_json
+import math
+
+import pytest
+import fastpy_rs.json as json
+
+
+DOCUMENTS = [
+    '{"name": "John", "tags": ["a", "b"], "nested": {"x": null, "y": true}}',
+    '[1, -2, 3.5, -0.0, 1e400, 12345678901234567890123456789]',
+    '"caf\\u00e9 \\ud83d\\ude00 \\ud800"',
+    ' \n 42 \t',
+    '[NaN, Infinity, -Infinity]',
+    '{"a": 1, "a": 2}',
+]
+
+
+@pytest.mark.parametrize("doc", DOCUMENTS)
+def test_loads_matches_stdlib(doc):
+    # Same values, including reprs of floats, big ints and lone surrogates
+    assert repr(json.loads(doc)) == repr(std_json.loads(doc))
+    assert repr(json.loads(doc.encode())) == repr(std_json.loads(doc))
+
+
+def test_loads_lone_surrogates():
+    # Lone surrogates in the text itself, not only as escapes
+    for doc in ['["\ud800"]', '{"\udfff": "a\ud800b\\n"}', '"\ud800\udc00"']:
+        assert repr(json.loads(doc)) == repr(std_json.loads(doc))
+        assert repr(json.parse_json(doc)) == repr(std_json.loads(doc))
+    doc = '["\ud800", }'
+    with pytest.raises(std_json.JSONDecodeError) as expected:
+        std_json.loads(doc)
+    with pytest.raises(json.JSONDecodeError) as actual:
+        json.loads(doc)
+    assert actual.value.pos == expected.value.pos
+
+
+def test_parse_json_shares_the_loads_decoder():
+    doc = '[12345678901234567890123456789, -0.0, 1e400, "\\ud800"]'
+    assert repr(json.parse_json(doc)) == repr(json.loads(doc)) == repr(std_json.loads(doc))
+    with pytest.raises(json.JSONDecodeError, match="Invalid JSON"):
+        json.parse_json("[NaN]")
+    with pytest.raises(RecursionError):
+        json.parse_json("[" * 100000 + "]" * 100000)
+
+
+def test_loads_hooks():
+    doc = '{"price": 1.10, "qty": 3, "items": [{"id": 1}], "bad": NaN}'
+    for kwargs in [
+        {"parse_float": decimal.Decimal},
+        {"parse_int": str},
+        {"parse_constant": lambda name: name},
+        {"object_hook": lambda d: sorted(d)},
+        {"object_pairs_hook": lambda pairs: pairs},
+        {"object_hook": len, "object_pairs_hook": list},
+    ]:
+        assert repr(json.loads(doc, **kwargs)) == repr(std_json.loads(doc, **kwargs))
+
+
+def test_loads_hook_errors_propagate():
+    def hook(_):
+        raise KeyError("boom")
+
+    with pytest.raises(KeyError):
+        json.loads('{"a": 1}', object_hook=hook)
+
+
+@pytest.mark.parametrize(
+    "doc",
+    ["", "[1,]", '{"a" 1}', '{"a": 1,}', '"abc', "[1 2]", '"\\x"', '"\\u12"', '"ab\\', '["x", "a\\', "{1: 2}", '["a\tb"]', "01", "﻿[]", "[\n1,\n]"],
+)
+def test_loads_errors_match_stdlib(doc):
+    with pytest.raises(std_json.JSONDecodeError) as expected:
+        std_json.loads(doc)
+    with pytest.raises(json.JSONDecodeError) as actual:
+        json.loads(doc)
+    assert (actual.value.msg, actual.value.pos, actual.value.lineno, actual.value.colno) == (
+        expected.value.msg,
+        expected.value.pos,
+        expected.value.lineno,
+        expected.value.colno,
+    )
+
+
+def test_loads_bytes_with_bom():
+    assert json.loads(b'\xef\xbb\xbf{"a": 1}') == {"a": 1}
+
+
+def test_loads_too_deep():
+    with pytest.raises(RecursionError):
+        json.loads("[" * 100000 + "]" * 100000)
+
+
+OBJECTS = [
+    {"name": "Jöhn", "age": 30, "scores": [1.5, 2.0, 1e-7], "nested": {"ok": True, "none": None}},
+    [1, "two", 3.0, [4, [5]], {}],
+    {1: "int", 2.5: "float", True: "bool", None: "none"},
+    {"nan": float("nan"), "inf": float("inf"), "-inf": float("-inf"), float("nan"): 1},
+    "\u2028 \x00 \"quoted\"",
+]
+
+
+@pytest.mark.parametrize("obj", OBJECTS)
+@pytest.mark.parametrize(
+    "kwargs",
+    [{}, {"indent": 2}, {"indent": "\t", "ensure_ascii": False}, {"separators": (",", ":")}],
+)
+def test_dumps_matches_stdlib(obj, kwargs):
+    assert json.dumps(obj, **kwargs) == std_json.dumps(obj, **kwargs)
+
+
+def test_dumps_lone_surrogates():
+    obj = ["\ud800 \"a\"\n", {"k": "\udfff"}]
+    assert json.dumps(obj) == std_json.dumps(obj)
+    with pytest.raises(ValueError):
+        json.dumps(obj, ensure_ascii=False)
+
+
+def test_dumps_sort_keys_matches_stdlib():
+    obj = {"b": 1, "a": {"d": [3, {"z": 1, "y": 2}], "c": None}}
+    assert json.dumps(obj, sort_keys=True, indent=4) == std_json.dumps(obj, sort_keys=True, indent=4)
+
+
+def test_dumps_allow_nan():
+    assert json.dumps([float("nan"), float("-inf")]) == "[NaN, -Infinity]"
+    with pytest.raises(ValueError):
+        json.dumps(float("nan"), allow_nan=False)
+    with pytest.raises(ValueError):
+        json.dumps({float("inf"): 1}, allow_nan=False)
+
+
+def test_dumps_skipkeys():
+    assert json.dumps({(1, 2): "x", "a": 1}, skipkeys=True) == '{"a": 1}'
+    with pytest.raises(ValueError, match="Dict keys must be"):
+        json.dumps({(1, 2): "x"})
+
+
+def test_dumps_circular_reference():
+    data = {"a": []}
+    data["a"].append(data)
+    with pytest.raises(ValueError, match="Circular reference detected"):
+        json.dumps(data)
+    with pytest.raises(RecursionError):
+        json.dumps(data, check_circular=False)
+
+
+def test_dumps_shared_reference_is_not_circular():
+    shared = [1, 2]
+    assert json.dumps([shared, shared]) == "[[1, 2], [1, 2]]"
+
+
+def test_dumps_default():
+    assert json.dumps({"x": object()}, default=lambda o: "obj") == '{"x": "obj"}'
+
+
+def test_cls_is_rejected():
+    with pytest.raises(TypeError):
+        json.dumps({}, cls=std_json.JSONEncoder)
+    with pytest.raises(TypeError):
+        json.loads("{}", cls=std_json.JSONDecoder)
+    assert json.dumps({}, cls=None) == "{}"
+
+
+def test_load_and_dump_file_objects():
+    out = io.StringIO()
+    json.dump({"a": [1, 2]}, out, indent=2)
+    assert out.getvalue() == std_json.dumps({"a": [1, 2]}, indent=2)
+    assert json.load(io.StringIO(out.getvalue())) == {"a": [1, 2]}
+    assert json.load(io.BytesIO(b"[1.5]"), parse_float=decimal.Decimal) == [decimal.Decimal("1.5")]
+
+
+def test_load_and_dump_real_file(tmp_path):
+    path = tmp_path / "data.json"
+    with open(path, "w", encoding="utf-8") as f:
+        json.dump({"π": math.pi}, f, ensure_ascii=False)
+    with open(path, encoding="utf-8") as f:
+        assert std_json.load(f) == {"π": math.pi}
+    with open(path, "rb") as f:
+        assert json.load(f) == {"π": math.pi}
+
+
+def test_import_as_module():
+    import importlib
+
+    module = importlib.import_module("fastpy_rs.json")
+    assert module is json
+    from fastpy_rs.json import dumps, loads
+
+    assert loads(dumps({"a": 1})) == {"a": 1}
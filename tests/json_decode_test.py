@@ -0,0 +1,194 @@
+import datetime
+import decimal
+import enum
+import uuid
+from dataclasses import dataclass, field
+from typing import Annotated, Any, Literal, NewType, NotRequired, Optional, TypedDict, Union
+
+import pytest
+from fastpy_rs import json
+
+
+@dataclass
+class Item:
+    sku: str
+    qty: int = 1
+
+
+@dataclass
+class Order:
+    id: int
+    items: list[Item]
+    note: Optional[str] = None
+    tags: set[str] = field(default_factory=set)
+
+
+class Color(enum.Enum):
+    RED = "red"
+    GREEN = "green"
+
+
+class Point(TypedDict):
+    x: float
+    y: float
+    label: NotRequired[str]
+
+
+@dataclass
+class Node:
+    value: int
+    children: "list[Node]"
+
+
+UserId = NewType("UserId", int)
+
+
+def test_dataclass():
+    order = json.decode('{"id": 7, "items": [{"sku": "A-1", "qty": 2}, {"sku": "B-2"}], "extra": 1}', type=Order)
+    assert order == Order(id=7, items=[Item("A-1", 2), Item("B-2")])
+    assert json.decode(b'{"id": 1, "items": [], "note": "x", "tags": ["a", "a"]}', type=Order).tags == {"a"}
+
+
+def test_python_objects_as_input():
+    assert json.decode({"id": 1, "items": [{"sku": "x"}]}, type=Order) == Order(1, [Item("x")])
+
+
+def test_typed_dict():
+    assert json.decode('{"x": 1, "y": 2.5}', type=Point) == {"x": 1.0, "y": 2.5}
+    assert json.decode('[{"x": 0, "y": 0, "label": "o", "z": 1}]', type=list[Point]) == [{"x": 0, "y": 0, "label": "o"}]
+
+
+def test_recursive_dataclass():
+    tree = json.decode('{"value": 1, "children": [{"value": 2, "children": []}]}', type=Node)
+    assert tree == Node(1, [Node(2, [])])
+    with pytest.raises(json.ValidationError) as exc_info:
+        json.decode('{"value": 1, "children": [{"value": 2, "children": [{"value": null, "children": []}]}]}', type=Node)
+    assert exc_info.value.path == "/children/0/children/0/value"
+
+
+@pytest.mark.parametrize(
+    "data, tp, expected",
+    [
+        ("1", int, 1),
+        ("1", float, 1.0),
+        ("true", bool, True),
+        ("null", None, None),
+        ('"x"', str, "x"),
+        ("[1, null]", list[Optional[int]], [1, None]),
+        ("[1, \"a\"]", list[Union[int, str]], [1, "a"]),
+        ("[1, \"a\"]", list[int | str], [1, "a"]),
+        ('[1, "a"]', tuple[int, str], (1, "a")),
+        ("[1, 2, 3]", tuple[int, ...], (1, 2, 3)),
+        ('{"1": 2}', dict[int, int], {1: 2}),
+        ('{"a": [1]}', dict[str, list[int]], {"a": [1]}),
+        ("[1, 2]", frozenset[int], frozenset({1, 2})),
+        ('"b"', Literal["a", "b"], "b"),
+        ('"red"', Color, Color.RED),
+        ("5", Annotated[int, "meta"], 5),
+        ("5", UserId, 5),
+        ('{"a": [1, {"b": null}]}', Any, {"a": [1, {"b": None}]}),
+        ('"2024-05-01T12:30:00+00:00"', datetime.datetime, datetime.datetime(2024, 5, 1, 12, 30, tzinfo=datetime.timezone.utc)),
+        ('"2024-05-01"', datetime.date, datetime.date(2024, 5, 1)),
+        ('"12:30:00"', datetime.time, datetime.time(12, 30)),
+        ('"12345678-1234-5678-1234-567812345678"', uuid.UUID, uuid.UUID("12345678-1234-5678-1234-567812345678")),
+        ("1.10", decimal.Decimal, decimal.Decimal("1.10")),
+        ('"aGk="', bytes, b"hi"),
+    ],
+)
+def test_types(data, tp, expected):
+    result = json.decode(data, type=tp)
+    assert result == expected
+    assert type(result) is type(expected)
+
+
+@pytest.mark.parametrize(
+    "data, tp, message, path",
+    [
+        ('{"id": 7, "items": [{"sku": "A-1", "qty": "2"}]}', Order, "Expected `int`, got `str`", "/items/0/qty"),
+        ('{"id": 7, "items": [{"qty": 2}]}', Order, "Object missing required field `sku`", "/items/0"),
+        ('{"id": 7, "items": {}}', Order, "Expected `array`, got `object`", "/items"),
+        ('{"id": 1.0, "items": []}', Order, "Expected `int`, got `float`", "/id"),
+        ('{"id": true, "items": []}', Order, "Expected `int`, got `bool`", "/id"),
+        ('{"id": 1, "items": [], "note": 5}', Order, "Expected `str | null`, got `int`", "/note"),
+        ('[1, 2, 3]', tuple[int, int], "Expected `array` of length 2, got 3", ""),
+        ('{"x": "1", "y": 2}', Point, "Expected `float`, got `str`", "/x"),
+        ('{"a/b": "x"}', dict[str, int], "Expected `int`, got `str`", "/a~1b"),
+        ('{"k": 1}', dict[int, int], 'Expected `int` key, got "k"', "/k"),
+        ('"blue"', Color, "Invalid enum value 'blue'", ""),
+        ('"c"', Literal["a", "b"], "Invalid value 'c', expected one of 'a', 'b'", ""),
+        ('"yesterday"', datetime.date, "Invalid date", ""),
+    ],
+)
+def test_errors(data, tp, message, path):
+    with pytest.raises(json.ValidationError) as exc_info:
+        json.decode(data, type=tp)
+    error = exc_info.value
+    assert isinstance(error, ValueError)
+    assert error.message == message
+    assert error.path == path
+    assert error.schema_path is None
+    assert str(error) == (f"{message} - at `{path}`" if path else message)
+
+
+def test_error_instance():
+    with pytest.raises(json.ValidationError) as exc_info:
+        json.decode('{"id": 7, "items": [{"sku": 5}]}', type=Order)
+    assert exc_info.value.instance == 5
+
+
+def test_optional_dataclass_reports_nested_error():
+    with pytest.raises(json.ValidationError) as exc_info:
+        json.decode('{"sku": 1}', type=Optional[Item])
+    assert exc_info.value.path == "/sku"
+
+
+def test_post_init_errors_become_validation_errors():
+    @dataclass
+    class Positive:
+        n: int
+
+        def __post_init__(self):
+            if self.n <= 0:
+                raise ValueError("n must be positive")
+
+    with pytest.raises(json.ValidationError) as exc_info:
+        json.decode('[{"n": 1}, {"n": 0}]', type=list[Positive])
+    assert exc_info.value.message == "n must be positive"
+    assert exc_info.value.path == "/1"
+
+
+def test_invalid_json():
+    with pytest.raises(json.JSONDecodeError):
+        json.decode('{"id": ', type=Order)
+
+
+@pytest.mark.parametrize("tp", [object(), dict[float, int], complex])
+def test_unsupported_types(tp):
+    with pytest.raises(TypeError):
+        json.decode("1", type=tp)
+
+
+def test_type_is_keyword_only():
+    with pytest.raises(TypeError):
+        json.decode("1", int)
+
+
+@pytest.mark.parametrize("text", ["1.10", "0.1000000000000000055511151231257827", "-2.50e-3", "123456789012345678901234567890"])
+def test_decimal_keeps_every_digit(text):
+    assert str(json.decode(text, type=decimal.Decimal)) == str(decimal.Decimal(text))
+    assert str(json.decode(f'{{"d": [{text}]}}', type=dict[str, list[decimal.Decimal]])["d"][0]) == str(decimal.Decimal(text))
+
+
+def test_big_ints_stay_exact():
+    big = 2**100 + 1
+    assert json.decode(str(big), type=int) == big
+    assert json.decode(f"[{big}, {-big}]", type=list[int]) == [big, -big]
+    assert json.decode({"id": big, "items": []}, type=Order).id == big
+    assert json.decode(f'{{"x": {big}}}', type=Any) == {"x": big}
+
+
+def test_deep_nesting_and_lone_surrogates():
+    deep = "[" * 500 + "]" * 500
+    assert json.decode(deep, type=Any) == json.parse_json(deep)
+    assert json.decode('"\\ud800"', type=str) == "\ud800"
+    assert json.decode('{"\\udc00": 1}', type=dict[str, int]) == {"\udc00": 1}
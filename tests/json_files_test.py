@@ -0,0 +1,137 @@
+import gzip
+import math
+import os
+import pathlib
+import threading
+
+import pytest
+from fastpy_rs import json
+
+DOC = {"name": "café", "items": [1, 2.5, None, True], "nested": {"a": [{"b": "x" * 100}] * 50}}
+
+
+def test_round_trip(tmp_path):
+    path = tmp_path / "doc.json"
+    assert json.dump_file(DOC, path) is None
+    assert path.read_text(encoding="utf-8") == json.serialize_json(DOC)
+    assert json.load_file(path) == DOC
+    assert json.load_file(str(path)) == DOC
+
+
+def test_serialize_options(tmp_path):
+    path = tmp_path / "doc.json"
+    json.dump_file({"b": 1, "a": "é"}, path, indent=2, sort_keys=True, ensure_ascii=True)
+    assert path.read_text() == '{\n  "a": "\\u00e9",\n  "b": 1\n}'
+    json.dump_file({"a": [1, 2]}, path, separators=(", ", ": "))
+    assert path.read_text() == '{"a": [1, 2]}'
+    json.dump_file({"s": {1}}, path, default=sorted)
+    assert json.load_file(path) == {"s": [1]}
+
+
+def test_gzip(tmp_path):
+    path = tmp_path / "doc.json.gz"
+    json.dump_file(DOC, path)
+    assert gzip.decompress(path.read_bytes()) == json.serialize_json(DOC).encode()
+    assert json.load_file(path) == DOC
+    json.dump_file(DOC, path, compress_level=1)
+    assert json.load_file(path) == DOC
+
+
+def test_gzip_detected_by_content(tmp_path):
+    path = tmp_path / "doc.json"
+    path.write_bytes(gzip.compress(b'{"a": 1}'))
+    assert json.load_file(path) == {"a": 1}
+
+
+def test_large_file(tmp_path):
+    records = [{"id": i, "name": f"user {i}", "score": i / 4} for i in range(100_000)]
+    for name in ("big.json", "big.json.gz"):
+        path = tmp_path / name
+        json.dump_file(records, path)
+        assert json.load_file(path) == records
+
+
+def test_bom_is_skipped(tmp_path):
+    path = tmp_path / "bom.json"
+    path.write_bytes(b'\xef\xbb\xbf[1, 2]')
+    assert json.load_file(path) == [1, 2]
+
+
+@pytest.mark.parametrize(
+    "content, pos, lineno, colno",
+    [(b'{"a": 1,}', 8, 1, 9), (b"", 0, 1, 1), (b'[1, 2] x', 7, 1, 8), ('[\n "é",\n "ü" x]'.encode(), 13, 3, 6)],
+)
+def test_invalid_json(tmp_path, content, pos, lineno, colno):
+    path = tmp_path / "bad.json"
+    path.write_bytes(content)
+    with pytest.raises(json.JSONDecodeError) as exc_info:
+        json.load_file(path)
+    assert (exc_info.value.pos, exc_info.value.lineno, exc_info.value.colno) == (pos, lineno, colno)
+    # The file is not copied into the exception.
+    assert exc_info.value.doc == ""
+
+
+def test_missing_file(tmp_path):
+    with pytest.raises(FileNotFoundError):
+        json.load_file(tmp_path / "missing.json")
+    with pytest.raises(FileNotFoundError):
+        json.dump_file({}, tmp_path / "missing" / "out.json")
+
+
+def test_serialize_errors(tmp_path):
+    with pytest.raises(ValueError):
+        json.dump_file({"x": math.nan}, tmp_path / "nan.json")
+    json.dump_file([math.inf], tmp_path / "inf.json", allow_nan=True)
+    assert (tmp_path / "inf.json").read_text() == "[Infinity]"
+    with pytest.raises(ValueError):
+        json.dump_file({}, tmp_path / "bad.json.gz", compress_level=10)
+    assert not (tmp_path / "bad.json.gz").exists()
+
+
+def test_threads(tmp_path):
+    paths = []
+    for i in range(4):
+        path = tmp_path / f"doc{i}.json.gz"
+        json.dump_file({"i": i, "doc": DOC}, path)
+        paths.append(path)
+    results = {}
+
+    def load(path):
+        results[path] = json.load_file(path)
+
+    threads = [threading.Thread(target=load, args=(path,)) for path in paths]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert [results[path]["i"] for path in paths] == [0, 1, 2, 3]
+
+
+def test_dump_file_while_another_thread_mutates(tmp_path):
+    data = {str(i): "x" * 100 for i in range(50_000)}
+    stop = threading.Event()
+
+    def mutate():
+        n = 0
+        while not stop.is_set():
+            data[f"extra{n}"] = n
+            n += 1
+
+    thread = threading.Thread(target=mutate)
+    thread.start()
+    try:
+        for _ in range(3):
+            json.dump_file(data, tmp_path / "doc.json")
+            # Each dump is a snapshot: the mutating thread cannot run until it is done.
+            assert len(json.load_file(tmp_path / "doc.json")) >= 50_000
+    finally:
+        stop.set()
+        thread.join()
+
+
+@pytest.mark.skipif(not os.path.exists("/dev/full"), reason="needs /dev/full")
+@pytest.mark.parametrize("size", [10, 200_000])
+def test_write_errors_are_raised(size):
+    # Small documents fail when the last chunk is written, large ones halfway through.
+    with pytest.raises(OSError, match="No space left on device"):
+        json.dump_file(["x" * 100] * size, "/dev/full")
@@ -0,0 +1,143 @@
+import pytest
+from fastpy_rs import json
+
+NESTED = {"id": 1, "user": {"name": "Ann", "address": {"city": "Oslo", "zip": None}}, "tags": ["a"], "extra": {}}
+FLAT = {"id": 1, "user.name": "Ann", "user.address.city": "Oslo", "user.address.zip": None, "tags": ["a"], "extra": {}}
+
+ORDERS = [
+    {"id": 1, "customer": {"name": "Ann"}, "lines": [{"sku": "A", "qty": 2}, {"sku": "B", "qty": 1, "opts": {"gift": True}}]},
+    {"id": 2, "customer": {"name": "Bob"}, "lines": [{"sku": "C", "qty": 5}]},
+    {"id": 3, "customer": {}, "lines": []},
+]
+
+
+def test_flatten():
+    assert json.flatten(NESTED) == FLAT
+    assert list(json.flatten(NESTED)) == list(FLAT)
+    assert json.flatten(json.serialize_json(NESTED)) == FLAT
+    assert json.flatten([NESTED, {"a": {"b": 1}}]) == [FLAT, {"a.b": 1}]
+
+
+def test_flatten_options():
+    assert json.flatten({"a": {"b": {"c": 1}}}, sep="_") == {"a_b_c": 1}
+    assert json.flatten({"a": {"b": {"c": 1}}, "d": 2}, max_level=1) == {"a.b": {"c": 1}, "d": 2}
+    assert json.flatten({"a": {"b": 1}}, max_level=0) == {"a": {"b": 1}}
+
+
+def test_flatten_keeps_values():
+    items = [1, 2]
+    assert json.flatten({"a": {"items": items}})["a.items"] is items
+
+
+@pytest.mark.parametrize("doc", [{"a.b": 1, "a": {"b": 2}}, {"a": {"b": 2}, "a.b": 1}, {"a": {"b.c": 1, "b": {"c": 2}}}])
+def test_flatten_key_collisions(doc):
+    with pytest.raises(ValueError, match="a.b"):
+        json.flatten(doc)
+    with pytest.raises(ValueError):
+        json.to_rows([doc])
+
+
+def test_unflatten():
+    assert json.unflatten(FLAT) == NESTED
+    assert json.unflatten('{"a_b": 1, "a_c": 2}', sep="_") == {"a": {"b": 1, "c": 2}}
+    assert json.unflatten([{"a.b": 1}, {"c": 2}]) == [{"a": {"b": 1}}, {"c": 2}]
+
+
+def test_unflatten_does_not_modify_input():
+    inner = {"x": 1}
+    assert json.unflatten({"a": inner, "a.y": 2}) == {"a": {"x": 1, "y": 2}}
+    assert inner == {"x": 1}
+
+
+@pytest.mark.parametrize("flat", [{"a": 1, "a.b": 2}, {"a.b": 2, "a": 1}, {"a.b": 1, "a.b.c": 2}])
+def test_unflatten_conflicts(flat):
+    with pytest.raises(ValueError):
+        json.unflatten(flat)
+
+
+def test_to_rows_without_record_path():
+    assert json.to_rows([NESTED, {"x": 1}]) == [FLAT, {"x": 1}]
+    assert json.to_rows(NESTED) == [FLAT]
+    assert json.to_rows([{"a": {"b": 1}}], record_prefix="r.") == [{"r.a.b": 1}]
+
+
+def test_to_rows_with_meta():
+    rows = json.to_rows(ORDERS, record_path="lines", meta=["id", ["customer", "name"]], errors="ignore")
+    assert rows == [
+        {"sku": "A", "qty": 2, "id": 1, "customer.name": "Ann"},
+        {"sku": "B", "qty": 1, "opts.gift": True, "id": 1, "customer.name": "Ann"},
+        {"sku": "C", "qty": 5, "id": 2, "customer.name": "Bob"},
+    ]
+    assert json.to_rows(json.serialize_json(ORDERS), "lines", ["id"], errors="ignore") == json.to_rows(
+        ORDERS, "lines", ["id"]
+    )
+
+
+def test_to_rows_missing_meta():
+    with pytest.raises(KeyError):
+        json.to_rows(ORDERS, record_path="lines", meta=[["customer", "name"]])
+    rows = json.to_rows([{"lines": [{"a": 1}]}], record_path="lines", meta=["id"], errors="ignore")
+    assert rows == [{"a": 1, "id": None}]
+
+
+def test_to_rows_nested_record_path():
+    data = {
+        "name": "shop",
+        "orders": [
+            {"id": 1, "lines": [{"sku": "A"}, {"sku": "B"}]},
+            {"id": 2, "lines": None},
+            {"id": 3, "lines": [{"sku": "C"}]},
+        ],
+    }
+    assert json.to_rows(data, record_path=["orders", "lines"], meta=["name"]) == [
+        {"sku": "A", "name": "shop"},
+        {"sku": "B", "name": "shop"},
+        {"sku": "C", "name": "shop"},
+    ]
+
+
+def test_to_rows_prefixes_and_scalars():
+    data = [{"id": 1, "tags": ["x", "y"]}]
+    assert json.to_rows(data, record_path="tags", meta=["id"]) == [{"0": "x", "id": 1}, {"0": "y", "id": 1}]
+    assert json.to_rows(data, record_path="tags", meta=["id"], meta_prefix="m_", record_prefix="t_") == [
+        {"t_0": "x", "m_id": 1},
+        {"t_0": "y", "m_id": 1},
+    ]
+
+
+def test_to_rows_errors():
+    with pytest.raises(ValueError):
+        json.to_rows([{"id": 1, "items": [{"id": 2}]}], record_path="items", meta=["id"])
+    with pytest.raises(KeyError):
+        json.to_rows([{"id": 1}], record_path="items")
+    with pytest.raises(TypeError):
+        json.to_rows([{"items": {"a": 1}}], record_path="items")
+    with pytest.raises(TypeError):
+        json.to_rows([1, 2])
+    with pytest.raises(ValueError):
+        json.to_rows(ORDERS, meta=["id"])
+    with pytest.raises(ValueError):
+        json.to_rows(ORDERS, record_path="lines", errors="skip")
+
+
+def test_non_str_keys():
+    with pytest.raises(TypeError):
+        json.flatten({1: {"a": 2}})
+    with pytest.raises(TypeError):
+        json.flatten(5)
+
+
+def test_flatten_circular_and_deep_documents():
+    doc = {"a": 1}
+    doc["self"] = doc
+    with pytest.raises(ValueError, match="Circular reference"):
+        json.flatten(doc)
+    with pytest.raises(ValueError, match="Circular reference"):
+        json.to_rows([doc])
+    deep = {"leaf": 1}
+    for _ in range(200_000):
+        deep = {"a": deep}
+    with pytest.raises(RecursionError):
+        json.flatten(deep)
+    with pytest.raises(RecursionError):
+        json.to_rows({"items": [deep]}, record_path="items")
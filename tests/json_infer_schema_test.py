@@ -0,0 +1,114 @@
+import pytest
+from fastpy_rs import json
+
+SAMPLES = [
+    {"id": 1, "status": "active", "email": "ann@example.com", "tags": ["a"]},
+    {"id": 2, "status": "active", "email": "bob@example.com", "note": None},
+    {"id": 3, "status": "closed", "email": "eve@example.com", "note": "late"},
+    {"id": 4, "status": "active", "email": "joe@example.com", "note": None},
+]
+
+
+def test_infers_object_schema():
+    schema = json.infer_schema(SAMPLES)
+    assert schema == {
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "properties": {
+            "email": {"type": "string", "format": "email"},
+            "id": {"type": "integer"},
+            "note": {"type": ["string", "null"]},
+            "status": {"type": "string", "enum": ["active", "closed"]},
+            "tags": {"type": "array", "items": {"type": "string"}},
+        },
+        "required": ["email", "id", "status"],
+    }
+    assert list(schema["properties"]) == ["email", "id", "note", "status", "tags"]
+
+
+def test_samples_validate_against_inferred_schema():
+    samples = SAMPLES + [
+        {"id": 5, "status": "active", "email": "x@y.org", "nested": {"a": [1, 2.5, None]}},
+        {"id": 6, "status": None, "email": "z@y.org", "nested": {"a": [], "b": True}},
+    ]
+    schema = json.infer_schema(samples)
+    validator = json.Validator(schema)
+    assert all(validator.is_valid(sample) for sample in samples)
+    assert not validator.is_valid({"id": "7", "status": "active", "email": "a@b.c"})
+    assert schema["properties"]["status"] == {"type": ["string", "null"], "enum": ["active", "closed", None]}
+    assert schema["properties"]["nested"]["properties"]["a"]["items"] == {"type": ["number", "null"]}
+    assert schema["properties"]["nested"]["required"] == ["a"]
+
+
+def test_json_text_samples():
+    assert json.infer_schema('[{"a": 1}, {"a": "x"}]') == json.infer_schema([{"a": 1}, {"a": "x"}])
+    assert json.infer_schema(b'["{}", "[]"]')["type"] == "string"
+    assert json.infer_schema(iter([1, 2.5]))["type"] == "number"
+
+
+@pytest.mark.parametrize(
+    "values, fmt",
+    [
+        (["2024-05-01T12:30:00Z", "2024-02-29t00:00:00.123+05:30"], "date-time"),
+        (["2024-05-01", "2000-02-29"], "date"),
+        (["12:30:00Z", "23:59:60-01:00"], "time"),
+        (["12345678-1234-5678-1234-567812345678", "ABCDEF01-ABCD-ABCD-ABCD-ABCDEF012345"], "uuid"),
+        (["ann@example.com", "b.c+d@mail.example.org"], "email"),
+        (["https://example.com/a?b=c", "ftp://files.example.com"], "uri"),
+        (["192.168.0.1", "255.255.255.0"], "ipv4"),
+        (["2024-05-01", "2023-02-29"], None),
+        (["2024-05-01T25:00:00Z", "2024-05-01T12:00:00Z"], None),
+        (["12:30:00", "12:30:00Z"], None),
+        (["ann@example.com", "not an email"], None),
+        (["256.0.0.1", "1.2.3.4"], None),
+        (["01.2.3.4", "1.2.3.4"], None),
+        (["https://example.com", "example.com"], None),
+    ],
+)
+def test_formats(values, fmt):
+    schema = json.infer_schema(values, max_enum=0)
+    assert schema.get("format") == fmt
+
+
+def test_enum_options():
+    samples = ["a", "b", "a", "b", "c", "a"]
+    assert json.infer_schema(samples)["enum"] == ["a", "b", "c"]
+    assert "enum" not in json.infer_schema(samples, max_enum=2)
+    assert "enum" not in json.infer_schema(samples, max_enum=0)
+    # Each value seen once is not evidence of an enumeration.
+    assert "enum" not in json.infer_schema(["a", "b", "c"])
+    # An enum would reject the ints, so mixed fields get none.
+    assert json.infer_schema(["a", "a", 1, 1]) == {
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": ["string", "integer"],
+    }
+
+
+def test_enum_keeps_format():
+    samples = ["2024-05-01", "2024-05-02", "2024-05-01", "2024-05-02"]
+    schema = json.infer_schema(samples)
+    assert (schema["enum"], schema["format"]) == (["2024-05-01", "2024-05-02"], "date")
+    assert "format" not in json.infer_schema(["2024-05-01", "soon", "2024-05-01", "soon"])
+
+
+def test_detect_formats_off():
+    assert "format" not in json.infer_schema(["2024-05-01", "2024-05-02"], detect_formats=False)
+
+
+def test_mixed_and_empty_values():
+    schema = json.infer_schema([[], {"a": 1}, True, None])
+    assert schema["type"] == ["object", "array", "boolean", "null"]
+    assert "items" not in schema
+    assert schema["required"] == ["a"]
+
+
+def test_output_serializes():
+    schema = json.infer_schema(SAMPLES)
+    assert json.parse_json(json.serialize_json(schema)) == schema
+
+
+def test_no_samples():
+    with pytest.raises(ValueError):
+        json.infer_schema([])
+    with pytest.raises(ValueError):
+        json.infer_schema("{}")
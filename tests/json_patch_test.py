@@ -0,0 +1,194 @@
+import copy
+
+import pytest
+from fastpy_rs import json
+
+
+@pytest.mark.parametrize(
+    "a, b",
+    [
+        ({"a": 1, "b": [1, 2]}, {"a": 2, "b": [1, 2, 3]}),
+        ({"a": {"b": {"c": 1}}, "gone": True}, {"a": {"b": {"c": 2, "d": None}}, "new": [1]}),
+        ([1, 2, 3, 4], [1, 5]),
+        ({"a/b": 1, "m~n": 2}, {"a/b": 3}),
+        ({"a": [1]}, {"a": {"0": 1}}),
+        ("x", {"a": 1}),
+        ({"same": [1, {"x": 1}]}, {"same": [1, {"x": 1}]}),
+    ],
+)
+def test_diff_roundtrip(a, b):
+    original = copy.deepcopy(a)
+    patch = json.diff(a, b)
+    assert json.apply_patch(a, patch) == b
+    assert a == original
+
+
+def test_diff_output():
+    assert json.diff({"a": 1, "b": [1, 2]}, {"a": 2, "b": [1, 2, 3]}) == [
+        {"op": "replace", "path": "/a", "value": 2},
+        {"op": "add", "path": "/b/2", "value": 3},
+    ]
+    assert json.diff([1, 2, 3], [1]) == [{"op": "remove", "path": "/2"}, {"op": "remove", "path": "/1"}]
+    assert json.diff({"a/b": 1}, {}) == [{"op": "remove", "path": "/a~1b"}]
+    assert json.diff({"a": 1}, {"a": 1}) == []
+
+
+@pytest.mark.parametrize(
+    "doc, patch, expected",
+    [
+        ({"foo": "bar"}, [{"op": "add", "path": "/baz", "value": "qux"}], {"baz": "qux", "foo": "bar"}),
+        ({"foo": ["bar", "baz"]}, [{"op": "add", "path": "/foo/1", "value": "qux"}], {"foo": ["bar", "qux", "baz"]}),
+        ({"baz": "qux", "foo": "bar"}, [{"op": "remove", "path": "/baz"}], {"foo": "bar"}),
+        ({"baz": "qux"}, [{"op": "replace", "path": "/baz", "value": "boo"}], {"baz": "boo"}),
+        (
+            {"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}},
+            [{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}],
+            {"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}},
+        ),
+        ({"foo": ["all", "grass", "cows", "eat"]}, [{"op": "move", "from": "/foo/1", "path": "/foo/3"}], {"foo": ["all", "cows", "eat", "grass"]}),
+        ({"foo": ["bar"]}, [{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}], {"foo": ["bar", ["abc", "def"]]}),
+        ({"/": 9, "~1": 10}, [{"op": "test", "path": "/~01", "value": 10}], {"/": 9, "~1": 10}),
+        ({"foo": 1}, '[{"op": "copy", "from": "/foo", "path": "/bar"}]', {"foo": 1, "bar": 1}),
+        ({"foo": 1}, [{"op": "replace", "path": "", "value": [1]}], [1]),
+    ],
+)
+def test_apply_patch_rfc_examples(doc, patch, expected):
+    assert json.apply_patch(doc, patch) == expected
+
+
+@pytest.mark.parametrize(
+    "doc, patch",
+    [
+        ({"baz": "qux"}, [{"op": "test", "path": "/baz", "value": "bar"}]),
+        ({"a": 1}, [{"op": "test", "path": "/a", "value": True}]),
+        ({"foo": "bar"}, [{"op": "add", "path": "/baz/bat", "value": "qux"}]),
+        ({"a": [1]}, [{"op": "add", "path": "/a/2", "value": 0}]),
+        ({"a": [1]}, [{"op": "remove", "path": "/a/01"}]),
+        ({"a": 1}, [{"op": "replace", "path": "/b", "value": 1}]),
+        ({"a": {"b": 1}}, [{"op": "move", "from": "/a", "path": "/a/b/c"}]),
+        ({"a": 1}, [{"op": "frobnicate", "path": "/a"}]),
+        ({"a": 1}, [{"path": "/a"}]),
+        ({"a": 1}, [{"op": "add", "path": "/b"}]),
+    ],
+)
+def test_apply_patch_errors(doc, patch):
+    with pytest.raises(ValueError):
+        json.apply_patch(doc, patch)
+
+
+def test_apply_patch_is_atomic_unless_in_place():
+    doc = {"a": 1}
+    with pytest.raises(ValueError, match="operation 1"):
+        json.apply_patch(doc, [{"op": "add", "path": "/b", "value": 2}, {"op": "test", "path": "/a", "value": 5}])
+    assert doc == {"a": 1}
+    value = {"nested": []}
+    patched = json.apply_patch(doc, [{"op": "add", "path": "/c", "value": value}])
+    assert patched["c"] == value and patched["c"] is not value
+    assert json.apply_patch(doc, [{"op": "add", "path": "/b", "value": 2}], in_place=True) is doc
+    assert doc == {"a": 1, "b": 2}
+
+
+@pytest.mark.parametrize(
+    "doc, patch, expected",
+    [
+        ({"a": "b"}, {"a": "c"}, {"a": "c"}),
+        ({"a": "b"}, {"b": "c"}, {"a": "b", "b": "c"}),
+        ({"a": "b"}, {"a": None}, {}),
+        ({"a": ["b"]}, {"a": "c"}, {"a": "c"}),
+        ({"a": {"b": "c"}}, {"a": {"b": "d", "c": None}}, {"a": {"b": "d"}}),
+        ({"a": [{"b": "c"}]}, {"a": [1]}, {"a": [1]}),
+        (["a", "b"], ["c", "d"], ["c", "d"]),
+        ({"a": "foo"}, None, None),
+        ({"e": None}, {"a": 1}, {"e": None, "a": 1}),
+        ([1, 2], {"a": "b", "c": None}, {"a": "b"}),
+        ({}, {"a": {"bb": {"ccc": None}}}, {"a": {"bb": {}}}),
+    ],
+)
+def test_merge_patch_rfc_examples(doc, patch, expected):
+    original = copy.deepcopy(doc)
+    assert json.merge_patch(doc, patch) == expected
+    assert doc == original
+
+
+def test_pointer_get():
+    doc = {"users": [{"name": "Ann"}], "a/b": 1, "m~n": 2, "": 3}
+    assert json.pointer_get(doc, "/users/0/name") == "Ann"
+    assert json.pointer_get(doc, "/a~1b") == 1
+    assert json.pointer_get(doc, "/m~0n") == 2
+    assert json.pointer_get(doc, "/") == 3
+    assert json.pointer_get(doc, "") is doc
+    assert json.pointer_get(doc, "/users/0") is doc["users"][0]
+    assert json.pointer_get(doc, "/users/5") is None
+    assert json.pointer_get(doc, "/users/-", "missing") == "missing"
+    with pytest.raises(ValueError):
+        json.pointer_get(doc, "users")
+    with pytest.raises(ValueError):
+        json.pointer_get(doc, "/~2")
+
+
+def test_pointer_set():
+    doc = {"users": [{"name": "Ann"}]}
+    assert json.pointer_set(doc, "/users/0/age", 31) is doc
+    json.pointer_set(doc, "/users/-", {"name": "Bob"})
+    json.pointer_set(doc, "/users/1/name", "Rob")
+    assert doc == {"users": [{"name": "Ann", "age": 31}, {"name": "Rob"}]}
+    assert json.pointer_set(doc, "", [1]) == [1]
+    with pytest.raises(KeyError):
+        json.pointer_set(doc, "/missing/key", 1)
+    with pytest.raises(KeyError):
+        json.pointer_set(doc, "/users/5", 1)
+    with pytest.raises(TypeError):
+        json.pointer_set(doc, "/users/0/age/x", 1)
+
+
+def _cyclic_list():
+    a = []
+    a.append(a)
+    return a
+
+
+def _cyclic_dict():
+    a = {}
+    a["x"] = a
+    return a
+
+
+def _nested(depth, wrap):
+    value = 1
+    for _ in range(depth):
+        value = wrap(value)
+    return value
+
+
+@pytest.mark.parametrize(
+    "call",
+    [
+        lambda: json.apply_patch(_cyclic_list(), []),
+        lambda: json.merge_patch({}, _cyclic_dict()),
+        lambda: json.merge_patch(_cyclic_dict(), {"y": 1}),
+        lambda: json.diff(_cyclic_list(), [[1]]),
+        lambda: json.diff(_cyclic_list(), _cyclic_list()),
+    ],
+)
+def test_circular_documents_raise(call):
+    with pytest.raises(ValueError, match="Circular reference"):
+        call()
+
+
+@pytest.mark.parametrize(
+    "call",
+    [
+        lambda doc: json.apply_patch(doc, []),
+        lambda doc: json.merge_patch({}, {"a": doc}),
+        lambda doc: json.diff(doc, [doc]),
+        lambda doc: json.diff(doc, copy.copy(doc)),
+    ],
+)
+@pytest.mark.parametrize("wrap", [lambda v: [v], lambda v: {"a": v}])
+def test_deeply_nested_documents_raise(call, wrap):
+    doc = _nested(200_000, wrap)
+    with pytest.raises(RecursionError):
+        call(doc)
+    # What parse_json accepts is still fine.
+    shallow = _nested(500, wrap)
+    assert json.apply_patch(shallow, []) == shallow
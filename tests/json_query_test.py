@@ -0,0 +1,131 @@
+import pytest
+from fastpy_rs import json
+
+
+DOC = {
+    "people": [
+        {"name": "Ann", "age": 31, "tags": ["admin", "dev"]},
+        {"name": "Bob", "age": 17, "tags": []},
+        {"name": "Cy", "age": None},
+    ],
+    "nums": [3, 1.5, -2, 10],
+    "nested": [[1, 2], [3, [4]], 5],
+    "meta": {"a": 1, "b": 2},
+    "s": "hello",
+}
+
+STORE = {
+    "store": {
+        "book": [
+            {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+            {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+            {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+            {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99},
+        ],
+        "bicycle": {"color": "red", "price": 399},
+    }
+}
+
+
+@pytest.mark.parametrize(
+    "expression, expected",
+    [
+        ("people[0].name", "Ann"),
+        ("people[-1].age", None),
+        ("people[*].name", ["Ann", "Bob", "Cy"]),
+        ("people[?age > `18`].name", ["Ann"]),
+        ("people[?!tags].name", ["Bob", "Cy"]),
+        ("people[].tags[]", ["admin", "dev"]),
+        ("nested[]", [1, 2, 3, [4], 5]),
+        ("nums[::-1]", [10, -2, 1.5, 3]),
+        ("meta.*", [1, 2]),
+        ("{first: people[0].name, n: length(nums)}", {"first": "Ann", "n": 4}),
+        ("people[:2] | max_by(@, &age).name", "Ann"),
+        ("sort_by(people[:2], &age)[].name", ["Bob", "Ann"]),
+        ("join(', ', people[*].name)", "Ann, Bob, Cy"),
+        ("to_string(meta)", '{"a":1,"b":2}'),
+        ("to_number('1.5e2')", 150.0),
+        ("merge(meta, `{\"c\": 3}`)", {"a": 1, "b": 2, "c": 3}),
+        ("missing || s", "hello"),
+        ("s > `1`", None),
+        ("missing.deeper", None),
+    ],
+)
+def test_query_jmespath(expression, expected):
+    assert json.query(DOC, expression) == expected
+
+
+@pytest.mark.parametrize(
+    "expression, expected",
+    [
+        ("$.store.book[*].author", ["Nigel Rees", "Evelyn Waugh", "Herman Melville", "J. R. R. Tolkien"]),
+        ("$..price", [8.95, 12.99, 8.99, 22.99, 399]),
+        ("$..book[-1].title", ["The Lord of the Rings"]),
+        ("$..book[0,1].title", ["Sayings of the Century", "Sword of Honour"]),
+        ("$.store.book[::-2].price", [22.99, 12.99]),
+        ("$..book[?@.isbn].title", ["Moby Dick", "The Lord of the Rings"]),
+        ("$..book[?@.price < 10 && @.category == 'fiction'].title", ["Moby Dick"]),
+        ("$..book[?match(@.author, 'J.*')].title", ["The Lord of the Rings"]),
+        ("$..book[?search(@.title, 'of')].title", ["Sayings of the Century", "Sword of Honour", "The Lord of the Rings"]),
+        ("$..book[?length(@.title) > 15].price", [8.95, 22.99]),
+        ("$['store'][\"bicycle\"].color", ["red"]),
+        ("$.store.book[0:4:0]", []),
+        ("$.missing", []),
+    ],
+)
+def test_query_jsonpath(expression, expected):
+    assert json.query(STORE, expression) == expected
+
+
+def test_query_returns_the_original_objects():
+    assert json.query(DOC, "people[0]") is DOC["people"][0]
+    assert json.query(DOC, "$.people[0]")[0] is DOC["people"][0]
+
+
+def test_query_parses_json_text():
+    assert json.query('{"a": {"b": [1, 2]}}', "a.b[-1]") == 2
+    assert json.query(b'[{"id": 1}, {"id": 2}]', "$[*].id") == [1, 2]
+
+
+def test_compile():
+    names = json.compile("people[*].name")
+    assert names.expression == "people[*].name"
+    assert repr(names) == 'Query("people[*].name")'
+    assert names.search(DOC) == ["Ann", "Bob", "Cy"]
+    assert json.query(DOC, names) == ["Ann", "Bob", "Cy"]
+    assert json.Query("$..age").search(DOC) == [31, 17, None]
+
+
+@pytest.mark.parametrize(
+    "expression",
+    ["people[", "a ==", "unknown(a)", "length(a, b)", "a[::0]", "$.", "$[?@.* == 1]", "$[?length(@.a)]", "$[01]", "$[?foo(@)]"],
+)
+def test_query_invalid_expression(expression):
+    with pytest.raises(ValueError):
+        json.compile(expression)
+
+
+def test_query_function_type_error():
+    with pytest.raises(TypeError):
+        json.query(DOC, "length(people[0].age)")
+
+
+@pytest.mark.parametrize(
+    "expression",
+    [
+        "people[?age >= `17`].name",
+        "people[*].[name, age]",
+        "nested[][]",
+        "sort(nums)",
+        "avg(nums)",
+        "min_by(people[:2], &age).name",
+        "reverse(s)",
+        "contains(people[0].tags, 'dev')",
+        "keys(meta)",
+        "people[1:].{n: name}",
+        "!people[1].tags",
+    ],
+)
+def test_query_matches_jmespath(expression):
+    jmespath = pytest.importorskip("jmespath")
+    assert json.query(DOC, expression) == jmespath.search(expression, DOC)
@@ -0,0 +1,97 @@
+import json as std_json
+
+import pytest
+from fastpy_rs import json
+
+
+@pytest.mark.parametrize(
+    "text, expected",
+    [
+        ('{"a": [1, 2, {"b": "c', '{"a":[1,2,{"b":"c"}]}'),
+        ('{"a": 1, "b": ', '{"a":1,"b":null}'),
+        ('{"a": 1, "b', '{"a":1,"b":null}'),
+        ('[1, 2, tru', "[1,2,true]"),
+        ('{"a": "\\u00', '{"a":""}'),
+        ('{"f": 1.5e', '{"f":1.5}'),
+    ],
+)
+def test_closes_truncated_json(text, expected):
+    assert json.repair(text) == expected
+
+
+@pytest.mark.parametrize(
+    "text, expected",
+    [
+        ('Here you go:\n```json\n{"a": 1}\n```\nAnything else?', '{"a": 1}'),
+        ('```\n[1, 2]\n```', "[1, 2]"),
+        ('```json\n{"a": [1,', '{"a":[1]}'),
+        ('The result is {"ok": true} as requested.', '{"ok": true}'),
+        ('Use {braces} and [notes] sparingly: {"ok": true}', '{"ok": true}'),
+        ("  42  ", "42"),
+    ],
+)
+def test_extracts_json_from_prose(text, expected):
+    assert json.repair(text) == expected
+
+
+@pytest.mark.parametrize(
+    "text, expected",
+    [
+        ("{'a': 'b'}", '{"a":"b"}'),
+        ("{a: 1, $b_2: 'x'}", '{"a":1,"$b_2":"x"}'),
+        ("{“a”: “b”}", '{"a":"b"}'),
+        ('{"quote": "He said "hi" to me"}', '{"quote":"He said \\"hi\\" to me"}'),
+        ('{"a": "he said "hi""}', '{"a":"he said \\"hi\\""}'),
+        ('["a" "b", "c""d"]', '["a","b","c","d"]'),
+        ("{'text': 'it's fine'}", '{"text":"it\'s fine"}'),
+        ("{'text': 'say \"hi\"'}", '{"text":"say \\"hi\\""}'),
+        ('{"s": "line1\nline2"}', '{"s":"line1\\nline2"}'),
+        ('{"re": "\\d+"}', '{"re":"\\\\d+"}'),
+        ('{"a": 1 "b": 2}', '{"a":1,"b":2}'),
+        ("[1 2 3,]", "[1,2,3]"),
+        ('{"a": [1, 2}', '{"a":[1,2]}'),
+        ('[{"a": 1]', '[{"a":1}]'),
+        ('{"a": }', '{"a":null}'),
+        ("{a: True, b: False, c: None, d: NaN, e: -Infinity}", '{"a":true,"b":false,"c":null,"d":null,"e":null}'),
+        ("[+1, .5, 5., 007, 0x1F, -0]", "[1,0.5,5.0,7,31,-0]"),
+        ("{size: 12px, name: Ann Lee}", '{"size":"12px","name":"Ann Lee"}'),
+        ('[1, 2, ...]', "[1,2]"),
+        ('{"a": 1, // note\n /* more */ "b": 2,}', '{"a":1,"b":2}'),
+    ],
+)
+def test_fixes_common_errors(text, expected):
+    repaired = json.repair(text)
+    assert repaired == expected
+    std_json.loads(repaired)
+
+
+def test_parse_lenient_llm():
+    reply = "Sure! ```json\n{'city': 'Paris', 'tags': ['capital', 'big',], population: 2102650, 'capital': True"
+    assert json.parse_lenient_llm(reply) == {
+        "city": "Paris",
+        "tags": ["capital", "big"],
+        "population": 2102650,
+        "capital": True,
+    }
+    assert json.parse_lenient_llm('"caf\\u00e9') == "café"
+    assert json.parse_lenient_llm("[]") == []
+
+
+def test_valid_json_is_unchanged():
+    text = '{\n  "a": [1, 2.5, "x"],\n  "b": {"c": null}\n}'
+    assert json.repair(text) == text
+    assert json.parse_lenient_llm(text) == std_json.loads(text)
+
+
+@pytest.mark.parametrize("text", ["", "   ", "no JSON in this reply", "```\n```"])
+def test_no_json(text):
+    with pytest.raises(ValueError):
+        json.repair(text)
+    with pytest.raises(ValueError):
+        json.parse_lenient_llm(text)
+
+
+def test_every_truncation_repairs_to_valid_json():
+    doc = std_json.dumps({"id": 7, "name": 'Ann "the" Lee', "tags": ["a", "é"], "score": -1.5e-3, "ok": True, "x": None})
+    for end in range(1, len(doc) + 1):
+        std_json.loads(json.repair(doc[:end]))
@@ -0,0 +1,112 @@
+import json as std_json
+
+import pytest
+from fastpy_rs import json
+
+
+ORDER_SCHEMA = {
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "type": "object",
+    "required": ["id", "items"],
+    "properties": {
+        "id": {"type": "integer", "minimum": 1},
+        "email": {"type": "string", "pattern": "^[^@]+@[^@]+$"},
+        "items": {"type": "array", "items": {"$ref": "#/$defs/item"}, "minItems": 1},
+    },
+    "additionalProperties": False,
+    "$defs": {
+        "item": {
+            "type": "object",
+            "properties": {"sku": {"type": "string"}, "qty": {"type": "integer", "exclusiveMinimum": 0}},
+            "required": ["sku"],
+        }
+    },
+}
+
+GOOD = {"id": 3, "email": "ann@example.com", "items": [{"sku": "A-1", "qty": 2}]}
+BAD = {"id": 0, "items": [{"qty": 0}], "extra": True}
+
+
+@pytest.mark.parametrize("instance", [GOOD, std_json.dumps(GOOD), std_json.dumps(GOOD).encode(), bytearray(std_json.dumps(GOOD).encode())])
+def test_is_valid_accepts_objects_and_json_text(instance):
+    assert json.Validator(ORDER_SCHEMA).is_valid(instance)
+
+
+@pytest.mark.parametrize("instance", [BAD, std_json.dumps(BAD), memoryview(std_json.dumps(BAD).encode())])
+def test_is_valid_rejects(instance):
+    assert not json.Validator(ORDER_SCHEMA).is_valid(instance)
+
+
+def test_iter_errors_json_pointer_paths():
+    errors = sorted(json.Validator(ORDER_SCHEMA).iter_errors(std_json.dumps(BAD)), key=lambda e: e.schema_path)
+    assert [(e.path, e.schema_path) for e in errors] == [
+        ("", "/additionalProperties"),
+        ("/id", "/properties/id/minimum"),
+        ("/items/0/qty", "/properties/items/items/$ref/properties/qty/exclusiveMinimum"),
+        ("/items/0", "/properties/items/items/$ref/required"),
+    ]
+    assert errors[1].instance == 0
+    assert errors[3].instance == {"qty": 0}
+    assert all(isinstance(e, ValueError) and str(e) == e.message for e in errors)
+    assert list(json.Validator(ORDER_SCHEMA).iter_errors(GOOD)) == []
+
+
+def test_validate_raises_validation_error():
+    validator = json.Validator({"type": "object", "properties": {"a/b": {"type": "string"}}})
+    validator.validate({"a/b": "x"})
+    with pytest.raises(json.ValidationError) as exc_info:
+        validator.validate('{"a/b": 1}')
+    assert exc_info.value.path == "/a~1b"
+    assert exc_info.value.instance == 1
+
+
+def test_draft_7():
+    schema = {"$schema": "http://json-schema.org/draft-07/schema#", "items": [{"type": "string"}]}
+    validator = json.Validator(schema)
+    assert validator.draft == 7
+    assert validator.is_valid(["a", 1])
+    assert not validator.is_valid([1])
+    # Under 2020-12 an array-valued "items" is not a valid schema keyword value.
+    with pytest.raises(ValueError):
+        json.Validator(schema, draft=2020)
+
+
+def test_draft_defaults_to_2020_12():
+    assert json.Validator({"type": "string"}).draft == 2020
+    assert json.Validator('{"type": "string"}', draft=7).draft == 7
+    assert json.Validator(True).is_valid("null")
+
+
+@pytest.mark.parametrize("schema", [{"type": 5}, {"minimum": "x"}, {"$schema": "http://example.com/unknown"}])
+def test_invalid_schema(schema):
+    with pytest.raises(ValueError):
+        json.Validator(schema)
+
+
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        json.Validator({}, draft=3)
+    with pytest.raises(json.JSONDecodeError):
+        json.Validator(ORDER_SCHEMA).is_valid('{"id": ')
+
+
+def test_deep_documents():
+    validator = json.Validator({"type": "array"})
+    text = "[" * 900 + "]" * 900
+    assert validator.is_valid(text)
+    assert validator.is_valid(json.parse_json(text))
+    with pytest.raises(RecursionError):
+        validator.is_valid("[" * 1001 + "]" * 1001)
+    cyclic = []
+    cyclic.append(cyclic)
+    with pytest.raises(ValueError, match="Circular reference"):
+        validator.is_valid(cyclic)
+
+
+def test_big_ints_are_rejected_rather_than_rounded():
+    validator = json.Validator({"type": "integer", "maximum": 2**64 - 1})
+    assert validator.is_valid(2**64 - 1)
+    assert validator.is_valid(str(-(2**63)))
+    for instance in (2**64, str(2**64), [-(2**63) - 1]):
+        with pytest.raises(ValueError, match="64 bits"):
+            validator.is_valid(instance)
@@ -0,0 +1,128 @@
+import io
+import json as std_json
+
+import pytest
+from fastpy_rs import json
+
+
+DOC = {
+    "count": 2,
+    "meta": {"pages": [1, {"results": ["nested"]}]},
+    "results": [
+        {"id": 1, "name": "café \"quoted\" 😀", "values": [1.5e3, -0.0, 12345678901234567890123, None, True, False]},
+        {"id": 2, "name": "", "values": []},
+    ],
+    "tail": {"x": [[], {}]},
+}
+
+
+class OneByteReader:
+    """File-like object that hands out a single byte per read() call."""
+
+    def __init__(self, data):
+        self.data = data
+        self.offset = 0
+
+    def read(self, size=-1):
+        chunk = self.data[self.offset:self.offset + 1]
+        self.offset += 1
+        return chunk
+
+
+@pytest.mark.parametrize(
+    "prefix, expected",
+    [
+        ("results.item", DOC["results"]),
+        ("results.item.id", [1, 2]),
+        ("meta.pages.item", DOC["meta"]["pages"]),
+        ("tail.x", [[[], {}]]),
+        ("", [DOC]),
+        ("missing.item", []),
+    ],
+)
+def test_iter_items_prefixes(prefix, expected):
+    data = std_json.dumps(DOC, indent=2).encode()
+    assert list(json.iter_items(data, prefix)) == expected
+
+
+def test_iter_items_top_level_array():
+    assert list(json.iter_items(b'[{"a": 1}, 2, "three"]', "item")) == [{"a": 1}, 2, "three"]
+
+
+def test_iter_items_sources(tmp_path):
+    path = tmp_path / "export.json"
+    path.write_text(std_json.dumps(DOC), encoding="utf-8")
+    assert list(json.iter_items(path, "results.item")) == DOC["results"]
+    assert list(json.iter_items(str(path), prefix="results.item")) == DOC["results"]
+    with open(path, "rb") as f:
+        assert list(json.iter_items(f, "results.item")) == DOC["results"]
+    with open(path, encoding="utf-8") as f:
+        assert list(json.iter_items(f, "results.item")) == DOC["results"]
+
+
+def test_iter_items_tokens_split_across_reads():
+    data = std_json.dumps(DOC, ensure_ascii=False).encode()
+    assert list(json.iter_items(OneByteReader(data), "results.item")) == DOC["results"]
+    escaped = std_json.dumps(DOC).encode()
+    assert list(json.iter_items(io.BytesIO(escaped), "results.item")) == DOC["results"]
+
+
+def test_iter_items_is_lazy(tmp_path):
+    path = tmp_path / "big.json"
+    with open(path, "w") as f:
+        f.write('{"results": [')
+        f.write(",".join(std_json.dumps({"id": i, "pad": "x" * 100}) for i in range(20000)))
+        f.write("]}")
+    items = json.iter_items(path, "results.item")
+    assert next(items) == {"id": 0, "pad": "x" * 100}
+    assert sum(1 for _ in items) == 19999
+
+
+def test_iter_items_yields_values_before_an_error():
+    items = json.iter_items(b'{"results": [1, 2,\n ]}', "results.item")
+    assert next(items) == 1
+    assert next(items) == 2
+    with pytest.raises(json.JSONDecodeError) as exc_info:
+        next(items)
+    assert exc_info.value.msg == "Expecting value"
+    assert (exc_info.value.lineno, exc_info.value.colno, exc_info.value.pos) == (2, 2, 20)
+    assert list(items) == []
+
+
+@pytest.mark.parametrize(
+    "data",
+    [b'{"a": tru}', b'{"a" 1}', b'[1 2]', b'[1] x', b'{"a": "abc', b'{"a": 01}', b'["\\q"]', b"",
+     b'["\xff"]', b'["caf\xc3"]', b'["\xe2\x82\\n"]'],
+)
+def test_iter_items_rejects_invalid_json_in_skipped_values(data):
+    with pytest.raises(std_json.JSONDecodeError):
+        list(json.iter_items(data, "unused"))
+
+
+def test_iter_items_checks_utf8_in_skipped_strings():
+    with pytest.raises(std_json.JSONDecodeError):
+        list(json.iter_items(io.BytesIO(b'{"x": "\xff", "item": [1]}'), "item.item"))
+    data = std_json.dumps({"skip": ["café 😀 €" * 3], "item": [1]}, ensure_ascii=False).encode()
+    assert list(json.iter_items(OneByteReader(data), "item.item")) == [1]
+
+
+
+@pytest.mark.parametrize(
+    "text",
+    ['["\\ud800"]', '["\\udc00"]', '["\\ud800x"]', '["\\ud800\\ud800\\udc00"]', '["\\ud83d\\ude00", "a\\udc00\\ud800"]', '{"\\ud800": ["\\ud800"]}'],
+)
+def test_iter_items_keeps_lone_surrogates(text):
+    expected = std_json.loads(text)
+    assert list(json.iter_items(text.encode(), "")) == [expected]
+    assert list(json.iter_items(OneByteReader(text.encode()), "")) == [expected]
+    # Skipped and raw (surrogatepass-encoded) surrogates are accepted too.
+    assert list(json.iter_items(f'{{"skip": {text}, "item": [1]}}'.encode(), "item.item")) == [1]
+    raw = std_json.dumps(expected, ensure_ascii=False)
+    assert list(json.iter_items(raw.encode("utf-8", "surrogatepass"), "")) == [expected]
+    assert list(json.iter_items(io.StringIO(raw), "")) == [expected]
+
+def test_iter_items_invalid_source():
+    with pytest.raises(TypeError):
+        json.iter_items(42)
+    with pytest.raises(FileNotFoundError):
+        json.iter_items("/nonexistent/export.json")
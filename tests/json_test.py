@@ -31,10 +31,45 @@ def test_parse_json_nested():
     assert result["scores"] == [85, 92, 78]
 
 def test_parse_json_array():
-    # Test with JSON array at top level (should fail as per implementation)
+    # Test with JSON array at top level
     json_str = '[1, 2, 3, 4]'
-    with pytest.raises(ValueError, match="JSON must be an object at the top level"):
-        json.parse_json(json_str)
+    assert json.parse_json(json_str) == [1, 2, 3, 4]
+
+def test_parse_json_top_level_scalars():
+    # Test with scalar values at top level
+    assert json.parse_json('"hello"') == "hello"
+    assert json.parse_json('42') == 42
+    assert json.parse_json('-1.5') == -1.5
+    assert json.parse_json('true') is True
+    assert json.parse_json('false') is False
+    assert json.parse_json('null') is None
+
+def test_parse_json_bytes_input():
+    # Test with bytes-like inputs
+    payload = '[{"name": "Zoë"}, 1, null]'
+    expected = [{"name": "Zoë"}, 1, None]
+    assert json.parse_json(payload.encode()) == expected
+    assert json.parse_json(bytearray(payload.encode())) == expected
+    assert json.parse_json(memoryview(payload.encode())) == expected
+    # Non-contiguous memoryview
+    assert json.parse_json(memoryview(b"[ 1 , 2 ]")[::2]) == [1, 2]
+
+def test_bytearray_is_pinned_while_parsing():
+    data = bytearray(b'[{"a": 1}, {"b": 2}]')
+
+    def hook(obj):
+        data.clear()
+        return obj
+
+    with pytest.raises(BufferError):
+        json.loads(data, object_hook=hook)
+    assert data == bytearray(b'[{"a": 1}, {"b": 2}]')
+
+def test_parse_json_invalid_input_type():
+    with pytest.raises(TypeError):
+        json.parse_json(42)
+    with pytest.raises(ValueError, match="Invalid JSON"):
+        json.parse_json(b'{"name": "\xff"}')
 
 def test_parse_json_empty():
     # Test with empty object
@@ -65,3 +100,45 @@ def test_parse_json_invalid():
     # Test with empty string
     with pytest.raises(ValueError, match="Invalid JSON"):
         json.parse_json('')
+
+def test_parse_json_matches_stdlib():
+    # Test a document exercising every value type against the stdlib parser
+    import json as py_json
+    doc = py_json.dumps({
+        "records": [{"id": i, "name": f"user{i}", "score": i / 3, "tags": ["a", "b"] * i, "active": i % 2 == 0}
+                    for i in range(200)],
+        "keys": {f"key{i}": None for i in range(3000)},
+        "escaped": "line\nbreak é \U0001f600 \"quoted\"",
+        "empty": [[], {}, ""],
+        "big": 18446744073709551615,
+        "negative": -9223372036854775808,
+    })
+    assert json.parse_json(doc) == py_json.loads(doc)
+
+def test_parse_json_trailing_data():
+    with pytest.raises(ValueError, match="Invalid JSON"):
+        json.parse_json('{"a": 1} {"b": 2}')
+
+def test_parse_json_decode_error_location():
+    # Test that errors carry the stdlib JSONDecodeError attributes
+    import json as py_json
+    doc = '{"a": 1,\n "b": }'
+    with pytest.raises(json.JSONDecodeError) as excinfo:
+        json.parse_json(doc)
+    err = excinfo.value
+    assert isinstance(err, py_json.JSONDecodeError)
+    assert isinstance(err, ValueError)
+    assert err.doc == doc
+    assert (err.pos, err.lineno, err.colno) == (15, 2, 7)
+    assert err.msg.startswith("Invalid JSON")
+
+def test_parse_json_decode_error_bytes_and_eof():
+    # Test positions for bytes input and truncated documents
+    import json as py_json
+    with pytest.raises(py_json.JSONDecodeError) as excinfo:
+        json.parse_json('{"é": x}'.encode())
+    assert (excinfo.value.doc, excinfo.value.pos, excinfo.value.colno) == ('{"é": x}', 6, 7)
+
+    with pytest.raises(py_json.JSONDecodeError) as excinfo:
+        json.parse_json("[1, 2")
+    assert excinfo.value.pos == 5
@@ -0,0 +1,83 @@
+import json as std_json
+
+import pytest
+from fastpy_rs import json
+
+
+def test_parse_jsonl_bytes():
+    data = b'{"id": 1, "tags": ["a"]}\n[1, 2]\n"text"\nnull\n'
+    assert json.parse_jsonl(data) == [{"id": 1, "tags": ["a"]}, [1, 2], "text", None]
+
+
+def test_parse_jsonl_skips_blank_lines_and_handles_crlf():
+    data = b'\xef\xbb\xbf{"a": 1}\r\n\r\n   \n{"a": 2}'
+    assert json.parse_jsonl(data) == [{"a": 1}, {"a": 2}]
+    assert json.parse_jsonl(b"") == []
+
+
+def test_parse_jsonl_path(tmp_path):
+    path = tmp_path / "data.jsonl"
+    path.write_text("\n".join(std_json.dumps({"id": i}) for i in range(1000)) + "\n")
+    expected = [{"id": i} for i in range(1000)]
+    assert json.parse_jsonl(path) == expected
+    assert json.parse_jsonl(str(path)) == expected
+
+
+def test_parse_jsonl_lazy(tmp_path):
+    path = tmp_path / "data.jsonl"
+    path.write_text('{"id": 1}\n{"id": 2}\n')
+    reader = json.parse_jsonl(path, lazy=True)
+    assert iter(reader) is reader
+    assert next(reader) == {"id": 1}
+    assert list(reader) == [{"id": 2}]
+    with pytest.raises(StopIteration):
+        next(reader)
+
+
+def test_parse_jsonl_reports_line_number():
+    data = b'{"a": 1}\n\n{"a": 2}\n{"a": }\n{"a": 4}\n'
+    with pytest.raises(json.JSONDecodeError) as exc_info:
+        json.parse_jsonl(data)
+    assert exc_info.value.lineno == 4
+    assert exc_info.value.colno == 7
+    assert exc_info.value.doc == '{"a": }'
+    assert "line 4" in str(exc_info.value)
+
+
+def test_parse_jsonl_lazy_yields_until_bad_line():
+    reader = json.parse_jsonl(b"1\n2\n{\n4\n", lazy=True)
+    assert next(reader) == 1
+    assert next(reader) == 2
+    with pytest.raises(std_json.JSONDecodeError) as exc_info:
+        next(reader)
+    assert exc_info.value.lineno == 3
+
+
+def test_parse_jsonl_invalid_source():
+    with pytest.raises(TypeError):
+        json.parse_jsonl(42)
+    with pytest.raises(FileNotFoundError):
+        json.parse_jsonl("/nonexistent/path/data.jsonl")
+
+
+def test_dump_jsonl_roundtrip(tmp_path):
+    path = tmp_path / "out.jsonl"
+    records = ({"id": i, "name": "é", "values": [i, i / 2]} for i in range(2000))
+    assert json.dump_jsonl(records, path) == 2000
+    lines = path.read_text(encoding="utf-8").splitlines()
+    assert len(lines) == 2000
+    assert lines[1] == '{"id":1,"name":"é","values":[1,0.5]}'
+    assert json.parse_jsonl(path) == [std_json.loads(line) for line in lines]
+
+
+def test_dump_jsonl_options(tmp_path):
+    path = tmp_path / "out.jsonl"
+    json.dump_jsonl([{"b": 1, "a": "é", "c": {1, 2}}], path, sort_keys=True, ensure_ascii=True, default=sorted)
+    assert path.read_text() == '{"a":"\\u00e9","b":1,"c":[1,2]}\n'
+
+
+def test_dump_jsonl_keeps_lines_before_error(tmp_path):
+    path = tmp_path / "out.jsonl"
+    with pytest.raises(ValueError):
+        json.dump_jsonl([1, [2], object(), 4], path)
+    assert path.read_text() == "1\n[2]\n"
@@ -23,3 +23,135 @@ def test_serialize_json_dict():
     assert rs_json.serialize_json({"nested": {"key": "value"}}) in ['{"nested":{"key":"value"}}']
 
 
+def test_serialize_json_matches_stdlib():
+    # Test a mixed document against the stdlib compact output
+    import json as py_json
+    data = {
+        "records": [{"id": i, "name": f"user{i}", "score": i / 3, "active": i % 2 == 0, "note": None}
+                    for i in range(100)],
+        "escaped": "line\nbreak \"quoted\" \\ tab\t",
+        "unicode": "é 中文 \U0001f600",
+        "floats": [0.1, -2.5, 1e100, 0.001],
+        "ints": [0, -1, 9223372036854775807, -9223372036854775808, 18446744073709551615],
+    }
+    expected = py_json.dumps(data, separators=(",", ":"), ensure_ascii=False)
+    assert rs_json.serialize_json(data) == expected
+
+def test_serialize_json_subclasses():
+    # Test with subclasses of the built-in types
+    class MyStr(str):
+        pass
+
+    class MyDict(dict):
+        pass
+
+    assert rs_json.serialize_json(MyStr("hi")) == '"hi"'
+    assert rs_json.serialize_json(MyDict(a=[MyStr("b")])) == '{"a":["b"]}'
+    assert rs_json.serialize_json({MyStr("key"): 1}) == '{"key":1}'
+
+def test_serialize_json_errors():
+    # Test with values that cannot be serialized
+    with pytest.raises(ValueError, match="not JSON-serializable"):
+        rs_json.serialize_json(object())
+    with pytest.raises(ValueError, match="Float out of range"):
+        rs_json.serialize_json([float("nan")])
+    with pytest.raises(ValueError, match="Dict keys must be str, int, float, bool or None"):
+        rs_json.serialize_json({(1, 2): "a"})
+
+def test_serialize_json_indent_sort_keys_matches_stdlib():
+    # Test that pretty output is byte-identical to json.dumps
+    import json as py_json
+    data = {"b": [1, {}, [], {"z": "é", "a": [[]]}], "a": None, "c": {"x": 1.5e-05}}
+    expected = py_json.dumps(data, indent=2, sort_keys=True)
+    assert rs_json.serialize_json(data, indent=2, sort_keys=True, ensure_ascii=True) == expected
+    assert rs_json.serialize_json(data, indent="\t") == py_json.dumps(data, indent="\t", ensure_ascii=False)
+    assert rs_json.serialize_json(data, indent=0) == py_json.dumps(data, indent=0, ensure_ascii=False)
+
+def test_serialize_json_separators_and_ensure_ascii():
+    # Test custom separators and ASCII escaping
+    data = {"name": "Zoë \U0001f600", "tags": [1, 2]}
+    assert rs_json.serialize_json(data, separators=(", ", ": ")) == '{"name": "Zoë \U0001f600", "tags": [1, 2]}'
+    assert rs_json.serialize_json(data, ensure_ascii=True) == '{"name":"Zo\\u00eb \\ud83d\\ude00","tags":[1,2]}'
+
+def test_serialize_json_float_repr():
+    # Test that floats are written like Python's repr
+    values = [1e-05, 1.5e-05, 1e-07, 0.0001, 1e16, 1e+100, 123456.789, -0.0, 5e-324]
+    assert rs_json.serialize_json(values) == "[" + ",".join(repr(v) for v in values) + "]"
+
+def test_serialize_json_invalid_indent():
+    with pytest.raises(TypeError):
+        rs_json.serialize_json({}, indent=[2])
+
+def test_serialize_json_extended_types():
+    # Test with the non-primitive types handled natively
+    import dataclasses
+    import datetime
+    import decimal
+    import enum
+    import uuid
+
+    class Color(enum.Enum):
+        RED = "red"
+
+    @dataclasses.dataclass
+    class Point:
+        x: int
+        y: int
+
+    tz = datetime.timezone.utc
+    assert rs_json.serialize_json((1, "a")) == '[1,"a"]'
+    assert rs_json.serialize_json({1}) == "[1]"
+    assert rs_json.serialize_json(frozenset()) == "[]"
+    assert rs_json.serialize_json(datetime.datetime(2024, 1, 2, 3, 4, 5, tzinfo=tz)) == '"2024-01-02T03:04:05+00:00"'
+    assert rs_json.serialize_json(datetime.date(2024, 1, 2)) == '"2024-01-02"'
+    assert rs_json.serialize_json(datetime.time(13, 30)) == '"13:30:00"'
+    assert rs_json.serialize_json(uuid.UUID(int=1)) == '"00000000-0000-0000-0000-000000000001"'
+    assert rs_json.serialize_json(decimal.Decimal("1.10")) == "1.10"
+    assert rs_json.serialize_json(Color.RED) == '"red"'
+    assert rs_json.serialize_json([Point(1, 2)]) == '[{"x":1,"y":2}]'
+    assert rs_json.serialize_json(b"hello") == '"aGVsbG8="'
+
+def test_serialize_json_default_hook():
+    # Test the default= fallback for unsupported types
+    class Money:
+        def __init__(self, cents):
+            self.cents = cents
+
+    assert rs_json.serialize_json({"price": Money(150)}, default=lambda o: {"cents": o.cents}) == '{"price":{"cents":150}}'
+
+    def reject(obj):
+        raise TypeError(f"cannot serialize {type(obj).__name__}")
+
+    with pytest.raises(TypeError, match="cannot serialize Money"):
+        rs_json.serialize_json([Money(1)], default=reject)
+    with pytest.raises(ValueError, match="recursion limit"):
+        rs_json.serialize_json(Money(1), default=lambda o: o)
+    with pytest.raises(ValueError, match="not JSON-serializable"):
+        rs_json.serialize_json(Money(1))
+
+def test_serialize_json_big_ints():
+    # Test that ints beyond 64 bits keep every digit
+    values = [2**64, -(2**64), 10**40, 18446744073709551615]
+    assert rs_json.serialize_json(values) == "[" + ",".join(str(v) for v in values) + "]"
+
+def test_serialize_json_non_string_keys():
+    # Test that dict keys are coerced like json.dumps does
+    import json as py_json
+    data = {1: "int", 2.5: "float", True: "bool", None: "none", 10**30: "big"}
+    assert rs_json.serialize_json(data) == py_json.dumps(data, separators=(",", ":"))
+    assert rs_json.serialize_json({2: "b", 1: "a"}, sort_keys=True) == '{"1":"a","2":"b"}'
+
+def test_serialize_json_builtin_subclasses():
+    # Test subclasses from the standard library
+    import collections
+    import enum
+
+    class Level(enum.IntEnum):
+        HIGH = 3
+
+    class Name(str, enum.Enum):
+        ADA = "ada"
+
+    data = collections.OrderedDict(level=Level.HIGH, name=Name.ADA)
+    data["counts"] = collections.defaultdict(int, {Level.HIGH: 1})
+    assert rs_json.serialize_json(data) == '{"level":3,"name":"ada","counts":{"3":1}}'